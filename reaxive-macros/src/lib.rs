@@ -0,0 +1,276 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Field, Item, ItemFn, ItemMod, Member, Stmt,
+};
+
+/// Makes components re-render when the observables they read change.
+///
+/// On a function, it makes that component reactive. On an inline module, it
+/// makes every `#[component]` function inside reactive, including in nested modules:
+///
+/// ```ignore
+/// use reaxive::attr::reaxive;
+///
+/// #[reaxive]
+/// mod pages {
+///     #[component]
+///     pub fn Counter() -> Element { ... }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn reaxive(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        let attr = proc_macro2::TokenStream::from(attr);
+        return syn::Error::new_spanned(attr, "#[reaxive] takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    match parse_macro_input!(item as Item) {
+        Item::Fn(mut function) => {
+            make_reactive(&mut function);
+            quote!(#function).into()
+        }
+        Item::Mod(mut module) => {
+            make_module_reactive(&mut module);
+            quote!(#module).into()
+        }
+        item => syn::Error::new_spanned(item, "#[reaxive] applies to functions and inline modules")
+            .to_compile_error()
+            .into(),
+    }
+}
+
+fn is_component(function: &ItemFn) -> bool {
+    function.attrs.iter().any(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "component")
+    })
+}
+
+fn make_reactive(function: &mut ItemFn) {
+    let tracking: Stmt = parse_quote! {
+        let __reaxive_tracking = ::reaxive::use_tracking_scope();
+    };
+    function.block.stmts.insert(0, tracking);
+}
+
+fn make_module_reactive(module: &mut ItemMod) {
+    let Some((_, items)) = &mut module.content else {
+        return;
+    };
+
+    for item in items {
+        match item {
+            Item::Fn(function) if is_component(function) => make_reactive(function),
+            Item::Mod(module) => make_module_reactive(module),
+            _ => {}
+        }
+    }
+}
+
+/// Implements `reaxive::Resettable` for a struct of observables that implements `Default`.
+///
+/// Each observable field is reset to its value in `Self::default()`. Mark a
+/// field holding another store `#[resettable(store)]` to reset it with its own
+/// `Resettable` impl, and other fields `#[resettable(skip)]`.
+#[proc_macro_derive(Resettable, attributes(resettable))]
+pub fn derive_resettable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match resettable_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+enum ResetField {
+    Observable,
+    Store,
+    Skip,
+}
+
+fn reset_field_kind(field: &Field) -> syn::Result<ResetField> {
+    let mut kind = ResetField::Observable;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("resettable"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("store") {
+                kind = ResetField::Store;
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                kind = ResetField::Skip;
+                Ok(())
+            } else {
+                Err(meta.error("expected `store` or `skip`"))
+            }
+        })?;
+    }
+    Ok(kind)
+}
+
+fn resettable_impl(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "Resettable can only be derived for structs",
+        ));
+    };
+
+    let mut resets = Vec::new();
+    for (index, field) in data.fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(index.into()),
+        };
+        match reset_field_kind(field)? {
+            ResetField::Observable => resets.push(quote! {
+                ::reaxive::__reset_field(&self.#member, &initial.#member);
+            }),
+            ResetField::Store => resets.push(quote! {
+                ::reaxive::Resettable::reset_state(&self.#member);
+            }),
+            ResetField::Skip => {}
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::reaxive::Resettable for #name #type_generics #where_clause {
+            fn reset_state(&self) {
+                #[allow(unused_variables)]
+                let initial = <Self as ::core::default::Default>::default();
+                ::reaxive::batch(|| {
+                    #(#resets)*
+                });
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(tokens: impl quote::ToTokens) -> String {
+        quote!(#tokens).to_string().replace(' ', "")
+    }
+
+    fn is_tracked(function: &ItemFn) -> bool {
+        function
+            .block
+            .stmts
+            .first()
+            .is_some_and(|stmt| tokens(stmt).contains("::reaxive::use_tracking_scope()"))
+    }
+
+    #[test]
+    fn reactive_functions_open_a_tracking_scope_first() {
+        let mut function: ItemFn = parse_quote! {
+            fn Counter() -> Element {
+                let count = store.count.get();
+                rsx! { "{count}" }
+            }
+        };
+        make_reactive(&mut function);
+
+        assert!(is_tracked(&function));
+        assert_eq!(function.block.stmts.len(), 3);
+    }
+
+    #[test]
+    fn modules_make_only_their_components_reactive() {
+        let mut module: ItemMod = parse_quote! {
+            mod pages {
+                #[component]
+                pub fn Home() -> Element { rsx! {} }
+
+                fn title() -> String { String::new() }
+
+                mod settings {
+                    #[dioxus::prelude::component]
+                    pub fn Settings() -> Element { rsx! {} }
+                }
+            }
+        };
+        make_module_reactive(&mut module);
+
+        let items = &module.content.as_ref().unwrap().1;
+        let (Item::Fn(home), Item::Fn(title), Item::Mod(settings)) =
+            (&items[0], &items[1], &items[2])
+        else {
+            panic!("module items changed kind");
+        };
+        assert!(is_tracked(home));
+        assert!(!is_tracked(title));
+        let Item::Fn(nested) = &settings.content.as_ref().unwrap().1[0] else {
+            panic!("nested item changed kind");
+        };
+        assert!(is_tracked(nested));
+    }
+
+    #[test]
+    fn resettable_resets_observables_and_stores_and_skips_the_rest() {
+        let input: DeriveInput = parse_quote! {
+            #[derive(Default)]
+            struct FormStore<T: Clone> where T: Default {
+                name: ObservableValue<String>,
+                #[resettable(store)]
+                address: AddressStore,
+                #[resettable(skip)]
+                id: u64,
+                extra: ObservableValue<T>,
+            }
+        };
+        let output = tokens(resettable_impl(&input).unwrap());
+
+        assert!(output.contains(
+            "impl<T:Clone>::reaxive::Resettable for FormStore<T> where T:Default"
+                .replace(' ', "")
+                .as_str()
+        ));
+        assert!(output.contains("::reaxive::__reset_field(&self.name,&initial.name);"));
+        assert!(output.contains("::reaxive::Resettable::reset_state(&self.address);"));
+        assert!(output.contains("::reaxive::__reset_field(&self.extra,&initial.extra);"));
+        assert!(!output.contains("self.id"));
+    }
+
+    #[test]
+    fn resettable_resets_tuple_fields_by_index() {
+        let input: DeriveInput = parse_quote! {
+            struct Pair(ObservableValue<u32>, #[resettable(skip)] u32, ObservableValue<u32>);
+        };
+        let output = tokens(resettable_impl(&input).unwrap());
+
+        assert!(output.contains("::reaxive::__reset_field(&self.0,&initial.0);"));
+        assert!(!output.contains("self.1"));
+        assert!(output.contains("::reaxive::__reset_field(&self.2,&initial.2);"));
+    }
+
+    #[test]
+    fn resettable_rejects_enums_and_unknown_options() {
+        let input: DeriveInput = parse_quote! {
+            enum Mode { Light, Dark }
+        };
+        let error = resettable_impl(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Resettable can only be derived for structs"
+        );
+
+        let input: DeriveInput = parse_quote! {
+            struct Form {
+                #[resettable(keep)]
+                name: ObservableValue<String>,
+            }
+        };
+        let error = resettable_impl(&input).unwrap_err();
+        assert_eq!(error.to_string(), "expected `store` or `skip`");
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{CancelToken, Observable, ObservableValue, ReadOnlyObservable};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+static PENDING_ACTIONS: LazyLock<ObservableValue<usize>> =
+    LazyLock::new(|| ObservableValue::new(0));
+
+/// The number of async store actions in flight across every store.
+pub fn pending_actions() -> ReadOnlyObservable<usize> {
+    PENDING_ACTIONS.read_only()
+}
+
+/// Whether any store has an async action in flight, e.g. to block navigation;
+/// subscribes the current observer.
+pub fn any_store_busy() -> bool {
+    PENDING_ACTIONS.get() > 0
+}
+
+/// State of the async actions of a store, shared by its clones.
+///
+/// Every async action of the store macros counts as pending until it
+/// completes or is dropped, which the store exposes as `is_loading()` and
+/// `pending_count()`:
+///
+/// ```ignore
+/// if cart.is_loading() {
+///     return rsx! { Spinner {} };
+/// }
+/// ```
+///
+/// An async action whose first parameter after `&self` is marked `#[cancel]`
+/// gets a token that is cancelled when the action is called again, so only
+/// the latest call may write its result. The marker may be left out when the
+/// parameter's type is spelled `CancelToken`:
+///
+/// ```ignore
+/// actions {
+///     async fn search(&self, #[cancel] cancel: reaxive::CancelToken, query: String) {
+///         if let Some(results) = cancel.run(api::search(&query)).await {
+///             self.results.assign(results);
+///         }
+///     }
+/// }
+///
+/// spawn(store.search("rea".into())); // cancelled by the next call
+/// spawn(store.search("reaxive".into()));
+/// ```
+///
+/// Callers pass only the other parameters. When the action runs inside
+/// [`CancelToken::run`], e.g. with the token of [`use_cancel_token`](crate::use_cancel_token),
+/// its token is also cancelled with that one.
+#[derive(Clone)]
+pub struct ActionTokens {
+    tokens: Arc<Mutex<HashMap<&'static str, CancelToken>>>,
+    pending: ObservableValue<usize>,
+}
+
+impl Default for ActionTokens {
+    fn default() -> Self {
+        Self {
+            tokens: Arc::new(Mutex::new(HashMap::new())),
+            pending: ObservableValue::new(0),
+        }
+    }
+}
+
+impl ActionTokens {
+    /// Cancels the previous call of action `name` and returns the token of the new one.
+    pub fn begin(&self, name: &'static str) -> CancelToken {
+        let token = CancelToken::current().map_or_else(CancelToken::new, |owner| owner.child());
+        let previous = self.tokens.lock().unwrap().insert(name, token.clone());
+        if let Some(previous) = previous {
+            previous.cancel();
+        }
+        token
+    }
+
+    /// Cancels every action in flight.
+    pub fn cancel_all(&self) {
+        for (_, token) in self.tokens.lock().unwrap().drain() {
+            token.cancel();
+        }
+    }
+
+    /// Counts an action as pending until the returned guard is dropped.
+    pub fn start(&self) -> PendingAction {
+        self.pending.set(|pending| *pending += 1);
+        PENDING_ACTIONS.set(|pending| *pending += 1);
+        PendingAction {
+            pending: Some(self.pending.clone()),
+        }
+    }
+
+    /// The number of actions of the store in flight.
+    pub fn pending(&self) -> ReadOnlyObservable<usize> {
+        self.pending.read_only()
+    }
+}
+
+/// Returned by [`ActionTokens::start`].
+pub struct PendingAction {
+    pending: Option<ObservableValue<usize>>,
+}
+
+impl PendingAction {
+    /// Counts an action of no store instance, such as an async store action
+    /// without `&self`, in [`pending_actions`] until the guard is dropped.
+    pub fn global() -> Self {
+        PENDING_ACTIONS.set(|pending| *pending += 1);
+        PendingAction { pending: None }
+    }
+}
+
+impl Drop for PendingAction {
+    fn drop(&mut self) {
+        if let Some(pending) = &self.pending {
+            pending.set(|pending| *pending -= 1);
+        }
+        PENDING_ACTIONS.set(|pending| *pending -= 1);
+    }
+}
@@ -0,0 +1,5 @@
+//! Attribute macros (feature `macros`): `use reaxive::attr::reaxive;`
+//!
+//! Kept out of the crate root so `#[reaxive]` doesn't shadow the `reaxive!` macro.
+
+pub use reaxive_macros::reaxive;
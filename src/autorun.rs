@@ -0,0 +1,52 @@
+use crate::observable::{track, Observer};
+use crate::Priority;
+use std::cell::{OnceCell, RefCell};
+use std::rc::{Rc, Weak};
+
+/// A side effect that re-runs whenever an observable it read changes.
+///
+/// It runs once when created and stops when disposed or dropped:
+///
+/// ```ignore
+/// let saver = autorun(move || save_draft(&editor.text.get()));
+/// // ...
+/// saver.dispose();
+/// ```
+#[must_use = "the effect stops as soon as the Autorun is dropped"]
+pub struct Autorun {
+    observer: RefCell<Option<Rc<Observer>>>,
+}
+
+impl Autorun {
+    pub fn new<F: FnMut() + 'static>(mut effect: F) -> Self {
+        let this = Rc::new(OnceCell::<Weak<Observer>>::new());
+        let observer = Observer::with_priority(Priority::Effect, {
+            let this = this.clone();
+            move || {
+                // Each run replaces the observables read by the previous one
+                if let Some(observer) = this.get().and_then(Weak::upgrade) {
+                    track(&observer, &mut effect);
+                }
+            }
+        });
+        let _ = this.set(Rc::downgrade(&observer));
+        observer.notify();
+
+        Self {
+            observer: RefCell::new(Some(observer)),
+        }
+    }
+
+    /// Stops the effect and detaches it from every observable it read.
+    pub fn dispose(&self) {
+        self.observer.borrow_mut().take();
+    }
+
+    pub fn is_disposed(&self) -> bool {
+        self.observer.borrow().is_none()
+    }
+}
+
+pub fn autorun<F: FnMut() + 'static>(effect: F) -> Autorun {
+    Autorun::new(effect)
+}
@@ -0,0 +1,96 @@
+use crate::{Observable, ObservableValue};
+use dioxus::prelude::{Attribute, Event, FormData};
+
+/// Values that can be edited through a form control.
+pub trait Bindable: Sized {
+    fn to_input(&self) -> String;
+    /// Returns `None` for input that cannot be parsed, leaving the observable unchanged.
+    fn from_input(input: &str) -> Option<Self>;
+}
+
+impl Bindable for String {
+    fn to_input(&self) -> String {
+        self.clone()
+    }
+
+    fn from_input(input: &str) -> Option<Self> {
+        Some(input.to_string())
+    }
+}
+
+macro_rules! impl_bindable_parse {
+    ($($type:ty),*) => {
+        $(
+            impl Bindable for $type {
+                fn to_input(&self) -> String {
+                    self.to_string()
+                }
+
+                fn from_input(input: &str) -> Option<Self> {
+                    input.trim().parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_bindable_parse!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+/// `value` and `oninput` attributes binding a form control to `observable`.
+pub fn bind_value<T>(observable: &ObservableValue<T>) -> Vec<Attribute>
+where
+    T: Bindable + Clone + 'static,
+{
+    bind_with(observable, T::to_input, T::from_input)
+}
+
+/// Like [`bind_value`], with custom formatting and parsing.
+pub fn bind_with<T, Format, Parse>(
+    observable: &ObservableValue<T>,
+    format: Format,
+    parse: Parse,
+) -> Vec<Attribute>
+where
+    T: Clone + 'static,
+    Format: Fn(&T) -> String,
+    Parse: Fn(&str) -> Option<T> + 'static,
+{
+    let value = observable.map(|value| format(value));
+    let target = observable.clone();
+
+    vec![
+        Attribute::new("value", value, None, true),
+        dioxus::html::events::oninput(move |event: Event<FormData>| {
+            if let Some(value) = parse(&event.value()) {
+                target.assign(value);
+            }
+        }),
+    ]
+}
+
+/// Two-way binding for `input`, `textarea` and `select` elements:
+///
+/// ```ignore
+/// input { ..bind!(store.name) }
+/// input { r#type: "number", ..bind!(store.age) }
+/// input { ..bind!(store.price, format = |p: &f64| format!("{p:.2}")) }
+/// input { ..bind!(store.price, format = |p: &f64| format!("{p:.2}"), parse = |s: &str| s.parse().ok()) }
+/// ```
+#[macro_export]
+macro_rules! bind {
+    ($observable:expr, format = $format:expr, parse = $parse:expr $(,)?) => {
+        $crate::bind_with(&$observable, $format, $parse)
+    };
+    ($observable:expr, parse = $parse:expr, format = $format:expr $(,)?) => {
+        $crate::bind_with(&$observable, $format, $parse)
+    };
+    ($observable:expr, parse = $parse:expr $(,)?) => {
+        $crate::bind_with(&$observable, $crate::Bindable::to_input, $parse)
+    };
+    ($observable:expr, format = $format:expr $(,)?) => {
+        $crate::bind_with(&$observable, $format, $crate::Bindable::from_input)
+    };
+    ($observable:expr $(,)?) => {
+        $crate::bind_value(&$observable)
+    };
+}
@@ -0,0 +1,135 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll, Waker};
+
+thread_local! {
+    /// Tokens of the [`CancelToken::run`] calls polling on this thread, innermost last.
+    static RUNNING: RefCell<Vec<CancelToken>> = const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+    children: Mutex<Vec<Weak<CancelState>>>,
+}
+
+impl CancelState {
+    fn cancel(&self) {
+        if self.cancelled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        for waker in std::mem::take(&mut *self.wakers.lock().unwrap()) {
+            waker.wake();
+        }
+        for child in std::mem::take(&mut *self.children.lock().unwrap()) {
+            if let Some(child) = child.upgrade() {
+                child.cancel();
+            }
+        }
+    }
+}
+
+/// Tells async work that its result is no longer wanted.
+///
+/// Clones share the same state. Async code checks
+/// [`is_cancelled`](Self::is_cancelled) between steps, or wraps futures in
+/// [`run`](Self::run) to drop them at the next await after cancellation:
+///
+/// ```ignore
+/// let Some(profile) = cancel.run(api::profile(id)).await else {
+///     return; // cancelled, leave the store alone
+/// };
+/// store.profile.assign(Some(profile));
+/// ```
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    state: Arc<CancelState>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels this token and every child token; cancelling again does nothing.
+    pub fn cancel(&self) {
+        self.state.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// A new token that is cancelled along with this one, and can also be
+    /// cancelled on its own.
+    pub fn child(&self) -> CancelToken {
+        let child = CancelToken::new();
+        if self.is_cancelled() {
+            child.cancel();
+        } else {
+            let mut children = self.state.children.lock().unwrap();
+            children.retain(|child| child.strong_count() > 0);
+            children.push(Arc::downgrade(&child.state));
+        }
+        child
+    }
+
+    /// Completes once the token is cancelled.
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled {
+            token: self.clone(),
+        }
+    }
+
+    /// Runs `future` until it completes, or until the token is cancelled, in
+    /// which case the future is dropped and `None` returned.
+    ///
+    /// Cancellable store actions started by `future` get child tokens of this one.
+    pub async fn run<F: Future>(&self, future: F) -> Option<F::Output> {
+        let mut future = std::pin::pin!(future);
+        let mut cancelled = self.cancelled();
+        std::future::poll_fn(move |cx| {
+            if Pin::new(&mut cancelled).poll(cx).is_ready() {
+                return Poll::Ready(None);
+            }
+            RUNNING.with(|running| running.borrow_mut().push(self.clone()));
+            let output = future.as_mut().poll(cx);
+            RUNNING.with(|running| running.borrow_mut().pop());
+            output.map(Some)
+        })
+        .await
+    }
+
+    /// The token of the innermost [`run`](Self::run) polling on this thread, if any.
+    pub fn current() -> Option<CancelToken> {
+        RUNNING.with(|running| running.borrow().last().cloned())
+    }
+}
+
+/// Future returned by [`CancelToken::cancelled`].
+pub struct Cancelled {
+    token: CancelToken,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        let mut wakers = self.token.state.wakers.lock().unwrap();
+        // Checked again under the lock, so a concurrent cancel can't be missed
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{Observable, ObservableValue};
+use std::cell::RefCell;
+use std::sync::mpsc::{Receiver, Sender, TryRecvError};
+
+/// Moves values received from a `std::sync::mpsc` channel into an observable.
+///
+/// Observables track their observers per thread, so the feed is pumped on the
+/// thread that owns the observable while producers send from anywhere.
+pub struct ReceiverFeed<T: Clone + 'static> {
+    receiver: Receiver<T>,
+    target: ObservableValue<T>,
+    // A message received by `is_disconnected`, applied by the next pump
+    peeked: RefCell<Option<T>>,
+}
+
+impl<T: Clone + 'static> ReceiverFeed<T> {
+    pub fn new(receiver: Receiver<T>, target: ObservableValue<T>) -> Self {
+        Self {
+            receiver,
+            target,
+            peeked: RefCell::new(None),
+        }
+    }
+
+    /// Applies every pending message in order and returns how many were applied.
+    pub fn pump(&self) -> usize {
+        let mut count = 0;
+        while let Some(value) = self.try_next() {
+            self.target.assign(value);
+            count += 1;
+        }
+        count
+    }
+
+    /// Drains pending messages but only applies the newest one, notifying once.
+    pub fn pump_latest(&self) -> bool {
+        let mut latest = None;
+        while let Some(value) = self.try_next() {
+            latest = Some(value);
+        }
+
+        match latest {
+            Some(value) => {
+                self.target.assign(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Blocks the current thread, applying messages until every sender is dropped.
+    pub fn run_blocking(&self) {
+        if let Some(value) = self.peeked.take() {
+            self.target.assign(value);
+        }
+        while let Ok(value) = self.receiver.recv() {
+            self.target.assign(value);
+        }
+    }
+
+    /// Whether every sender is dropped and no message is left to apply.
+    pub fn is_disconnected(&self) -> bool {
+        if self.peeked.borrow().is_some() {
+            return false;
+        }
+        match self.receiver.try_recv() {
+            Ok(value) => {
+                *self.peeked.borrow_mut() = Some(value);
+                false
+            }
+            Err(error) => error == TryRecvError::Disconnected,
+        }
+    }
+
+    fn try_next(&self) -> Option<T> {
+        self.peeked.take().or_else(|| self.receiver.try_recv().ok())
+    }
+
+    pub fn target(&self) -> &ObservableValue<T> {
+        &self.target
+    }
+}
+
+/// Subscription that forwards every change of an observable into a channel.
+///
+/// The subscription is removed when the sink is dropped or closed.
+pub struct ChannelSink<T: Clone + 'static> {
+    source: ObservableValue<T>,
+    id: Option<usize>,
+}
+
+impl<T: Clone + 'static> ChannelSink<T> {
+    pub fn close(mut self) {
+        self.detach();
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.id.is_some()
+    }
+
+    fn detach(&mut self) {
+        if let Some(id) = self.id.take() {
+            self.source.unsubscribe(id);
+        }
+    }
+}
+
+impl<T: Clone + 'static> Drop for ChannelSink<T> {
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
+
+impl<T: Clone + 'static> ObservableValue<T> {
+    /// Connects a std receiver to this observable: `count.feed_from(rx).pump()`
+    pub fn feed_from(&self, receiver: Receiver<T>) -> ReceiverFeed<T> {
+        ReceiverFeed::new(receiver, self.clone())
+    }
+
+    /// Sends a clone of every new value into `sender` until the sink is dropped
+    pub fn sink_to(&self, sender: Sender<T>) -> ChannelSink<T>
+    where
+        T: Send,
+    {
+        let id = self.subscribe(move |value: &T| {
+            let _ = sender.send(value.clone());
+        });
+
+        ChannelSink {
+            source: self.clone(),
+            id: Some(id),
+        }
+    }
+
+    /// Sends every new value into a tokio channel until the sink is dropped
+    #[cfg(feature = "tokio")]
+    pub fn sink_to_tokio(&self, sender: tokio::sync::mpsc::UnboundedSender<T>) -> ChannelSink<T>
+    where
+        T: Send,
+    {
+        let id = self.subscribe(move |value: &T| {
+            let _ = sender.send(value.clone());
+        });
+
+        ChannelSink {
+            source: self.clone(),
+            id: Some(id),
+        }
+    }
+}
+
+/// Applies values from a tokio receiver until the channel closes.
+#[cfg(feature = "tokio")]
+pub async fn feed_from_tokio<T: Clone + 'static>(
+    mut receiver: tokio::sync::mpsc::Receiver<T>,
+    target: ObservableValue<T>,
+) {
+    while let Some(value) = receiver.recv().await {
+        target.assign(value);
+    }
+}
+
+/// Applies values from an unbounded tokio receiver until the channel closes.
+#[cfg(feature = "tokio")]
+pub async fn feed_from_tokio_unbounded<T: Clone + 'static>(
+    mut receiver: tokio::sync::mpsc::UnboundedReceiver<T>,
+    target: ObservableValue<T>,
+) {
+    while let Some(value) = receiver.recv().await {
+        target.assign(value);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::observable::{track, Observer};
+use crate::{Observable, ObservableValue, Priority};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+struct ComputedInner<T: Clone + 'static> {
+    compute: Box<dyn Fn() -> T>,
+    cache: RefCell<Option<T>>,
+    dirty: Rc<Cell<bool>>,
+    version: ObservableValue<u64>,
+    observer: Rc<Observer>,
+}
+
+/// A memoized value derived from other observables.
+///
+/// The closure runs lazily and its result is cached until one of the
+/// observables it read changes. Reading a computed value inside a reactive
+/// component subscribes the component to it like any other observable.
+pub struct Computed<T: Clone + 'static> {
+    inner: Rc<ComputedInner<T>>,
+}
+
+impl<T: Clone + 'static> Clone for Computed<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Clone + 'static> Computed<T> {
+    pub fn new<F: Fn() -> T + 'static>(compute: F) -> Self {
+        let dirty = Rc::new(Cell::new(true));
+        let version = ObservableValue::new(0u64);
+
+        let observer = {
+            let dirty = dirty.clone();
+            let version = version.clone();
+            Observer::with_priority(Priority::Computed, move || {
+                if !dirty.replace(true) {
+                    version.set(|v| *v += 1);
+                }
+            })
+        };
+
+        Self {
+            inner: Rc::new(ComputedInner {
+                compute: Box::new(compute),
+                cache: RefCell::new(None),
+                dirty,
+                version,
+                observer,
+            }),
+        }
+    }
+
+    /// Returns the cached value, recomputing it first if an input changed.
+    pub fn get(&self) -> T {
+        self.inner.version.map(|_| ());
+        self.refresh();
+        self.inner.cache.borrow().clone().unwrap()
+    }
+
+    /// Like `get`, but passes a reference to avoid cloning the cached value.
+    pub fn map<U, F: FnOnce(&T) -> U>(&self, mapper: F) -> U {
+        self.inner.version.map(|_| ());
+        self.refresh();
+        mapper(self.inner.cache.borrow().as_ref().unwrap())
+    }
+
+    /// Returns the current value without subscribing the current observer.
+    pub fn peek(&self) -> T {
+        self.refresh();
+        self.inner.cache.borrow().clone().unwrap()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.inner.dirty.get()
+    }
+
+    /// Drops the cached value so the next read recomputes it.
+    pub fn invalidate(&self) {
+        self.inner.observer.notify();
+    }
+
+    /// Subscribes `observer` to changes of this computed value.
+    #[cfg(feature = "dioxus")]
+    pub(crate) fn observe(&self, observer: Rc<Observer>) {
+        crate::observable::with_observer(Some(observer), || self.inner.version.map(|_| ()));
+    }
+
+    fn refresh(&self) {
+        if !self.inner.dirty.get() {
+            return;
+        }
+
+        let value = track(&self.inner.observer, || (self.inner.compute)());
+        *self.inner.cache.borrow_mut() = Some(value);
+        self.inner.dirty.set(false);
+    }
+}
+
+pub fn computed<T: Clone + 'static, F: Fn() -> T + 'static>(compute: F) -> Computed<T> {
+    Computed::new(compute)
+}
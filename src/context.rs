@@ -1,63 +1,360 @@
-use crate::Store;
+use crate::context_middleware::Middleware;
+use crate::inject::{construct_in, expect_resolved, resolve_in};
+use crate::store::{
+    async_store_factory, build_lazy, build_lazy_async, list_entries, notify_clearing,
+    notify_removed, notify_replaced, store_factory, Factories, StoreEntry, Stores,
+};
+use crate::visibility::assert_not_private;
+use crate::{
+    current_request_context, ContextAccess, ContextMiddleware, Injectable, KeyedStores,
+    ProvidedStore, Store, StoreInfo,
+};
 use std::any::TypeId;
+use std::cell::RefCell;
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, RwLock, RwLockWriteGuard};
 
 #[derive(Clone)]
 pub struct StoreContext {
-    stores: Arc<Mutex<HashMap<TypeId, Arc<dyn std::any::Any + Send + Sync>>>>,
+    stores: Arc<Mutex<Stores>>,
+    keyed: Arc<Mutex<KeyedStores>>,
+    factories: Arc<Mutex<Factories>>,
+    middleware: Arc<RwLock<Vec<Middleware>>>,
     name: String,
+    parent: Option<Box<StoreContext>>,
 }
 
 impl StoreContext {
     pub fn new() -> Self {
-        Self {
-            stores: Arc::new(Mutex::new(HashMap::new())),
-            name: "default".to_string(),
-        }
+        Self::with_name("default")
     }
 
     pub fn with_name(name: impl Into<String>) -> Self {
         Self {
             stores: Arc::new(Mutex::new(HashMap::new())),
+            keyed: Arc::new(Mutex::new(HashMap::new())),
+            factories: Arc::new(Mutex::new(HashMap::new())),
+            middleware: Arc::new(RwLock::new(Vec::new())),
             name: name.into(),
+            parent: None,
+        }
+    }
+
+    /// Makes lookups that miss in this context fall back to `parent`.
+    pub fn with_parent(mut self, parent: StoreContext) -> Self {
+        self.parent = Some(Box::new(parent));
+        self
+    }
+
+    /// A new, empty context whose lookups fall back to this one.
+    pub fn child(&self, name: impl Into<String>) -> Self {
+        Self::with_name(name).with_parent(self.clone())
+    }
+
+    pub fn parent(&self) -> Option<&StoreContext> {
+        self.parent.as_deref()
+    }
+
+    /// Runs `middleware` on the operations of this context and its clones, after
+    /// the middleware added before it. Parents and children are not affected.
+    pub fn add_middleware(&self, middleware: impl ContextMiddleware) {
+        self.middleware.write().unwrap().push(Arc::new(middleware));
+    }
+
+    /// Like [`add_middleware`](Self::add_middleware), for building a context.
+    pub fn with_middleware(self, middleware: impl ContextMiddleware) -> Self {
+        self.add_middleware(middleware);
+        self
+    }
+
+    fn access<'a, S: 'static>(&'a self, key: Option<&'a str>) -> ContextAccess<'a> {
+        ContextAccess {
+            context: &self.name,
+            type_id: TypeId::of::<S>(),
+            type_name: std::any::type_name::<S>(),
+            key,
+        }
+    }
+
+    /// A copy of the middleware list, so middleware may use this context.
+    fn middleware(&self) -> Vec<Middleware> {
+        self.middleware.read().unwrap().clone()
+    }
+
+    fn allows_register(&self, access: &ContextAccess) -> bool {
+        self.middleware()
+            .iter()
+            .all(|middleware| middleware.on_register(access))
+    }
+
+    fn allows_get(&self, access: &ContextAccess) -> bool {
+        self.middleware()
+            .iter()
+            .all(|middleware| middleware.on_get(access))
+    }
+
+    fn notify_remove(&self, access: &ContextAccess) {
+        for middleware in self.middleware() {
+            middleware.on_remove(access);
         }
     }
 
+    /// The store the first middleware resolving `S` provides, if any.
+    fn resolve_with_middleware<S: Store>(&self, access: &ContextAccess) -> Option<StoreEntry> {
+        self.middleware()
+            .iter()
+            .filter_map(|middleware| middleware.resolve(access))
+            .map(|ProvidedStore(entry)| entry)
+            .find(|entry| entry.downcast::<S>().is_some())
+    }
+
     pub fn register<S: Store + Send + Sync>(&self, store: S) {
+        self.register_entry(store);
+    }
+
+    /// Registers `store`, returning its entry unless middleware refused it.
+    pub(crate) fn register_entry<S: Store + Send + Sync>(&self, store: S) -> Option<StoreEntry> {
+        if !self.allows_register(&self.access::<S>(None)) {
+            return None;
+        }
         let type_id = store.id();
-        self.stores.lock().unwrap().insert(type_id, Arc::new(store));
+        let entry = StoreEntry::new(store);
+        let previous = self.stores.lock().unwrap().insert(type_id, entry.clone());
+        notify_replaced(type_id, previous, &entry);
+        Some(entry)
+    }
+
+    /// Registers `store` unless this context holds an `S` already, returning the
+    /// registered one. Middleware refusing the registration leaves `store` unregistered.
+    #[cfg(feature = "serde")]
+    pub(crate) fn register_if_absent<S: Store + Send + Sync>(&self, store: S) -> S {
+        if !self.allows_register(&self.access::<S>(None)) {
+            return store;
+        }
+        crate::store::insert_built(
+            &self.stores,
+            TypeId::of::<S>(),
+            StoreEntry::new(store.clone()),
+        )
+        .downcast::<S>()
+        .unwrap_or(store)
     }
 
+    /// Looks `S` up in this context, then in each parent in turn.
     pub fn get<S: Store>(&self) -> Option<S> {
-        self.stores
+        if !self.allows_get(&self.access::<S>(None)) {
+            return None;
+        }
+        self.lookup::<S>()
+            .or_else(|| self.parent.as_ref().and_then(|parent| parent.get::<S>()))
+    }
+
+    /// Looks `S` up in this context only, ignoring parents.
+    pub fn get_local<S: Store>(&self) -> Option<S> {
+        if !self.allows_get(&self.access::<S>(None)) {
+            return None;
+        }
+        self.lookup::<S>()
+    }
+
+    fn lookup<S: Store>(&self) -> Option<S> {
+        let existing = self
+            .stores
             .lock()
             .unwrap()
             .get(&TypeId::of::<S>())
-            .and_then(|store| store.downcast_ref::<S>())
-            .cloned()
+            .and_then(StoreEntry::downcast::<S>);
+        existing
+            .or_else(|| build_lazy(&self.stores, &self.factories))
+            .or_else(|| {
+                let type_id = TypeId::of::<S>();
+                let entry = self.resolve_with_middleware::<S>(&self.access::<S>(None))?;
+                let previous = self.stores.lock().unwrap().insert(type_id, entry.clone());
+                notify_replaced(type_id, previous, &entry);
+                entry.downcast::<S>()
+            })
+    }
+
+    /// Registers a constructor that builds `S` in this context the first time it is looked up.
+    pub fn register_lazy<S, F>(&self, factory: F)
+    where
+        S: Store + Send + Sync,
+        F: Fn() -> S + Send + Sync + 'static,
+    {
+        self.factories
+            .lock()
+            .unwrap()
+            .insert(TypeId::of::<S>(), store_factory(factory));
     }
 
-    pub fn get_or_create<S: Store + Default + Send + Sync>(&self) -> S {
+    /// Registers an async constructor that [`get_async`](Self::get_async)
+    /// awaits the first time `S` is looked up in this context.
+    pub fn register_lazy_async<S, F, Fut>(&self, factory: F)
+    where
+        S: Store + Send + Sync,
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = S> + 'static,
+    {
+        self.factories
+            .lock()
+            .unwrap()
+            .insert(TypeId::of::<S>(), async_store_factory(factory));
+    }
+
+    /// Like [`get`](Self::get), also awaiting the async lazy factory of this
+    /// context or, failing that, of the nearest parent with one.
+    pub async fn get_async<S: Store>(&self) -> Option<S> {
+        if let Some(store) = self.get::<S>() {
+            return Some(store);
+        }
+        if !self.allows_get(&self.access::<S>(None)) {
+            return None;
+        }
+
+        let mut context = Some(self);
+        while let Some(current) = context {
+            if let Some(store) = build_lazy_async(&current.stores, &current.factories).await {
+                return Some(store);
+            }
+            context = current.parent();
+        }
+        None
+    }
+
+    /// The registered `S`, or a new one constructed from its
+    /// [`Injectable`] dependencies in this context.
+    pub fn get_or_create<S: Injectable>(&self) -> S {
         if let Some(store) = self.get::<S>() {
             store
         } else {
-            let store = S::default();
-            self.register(store.clone());
-            store
+            assert_not_private::<S>();
+            expect_resolved(resolve_in::<S, _>(self))
         }
     }
 
     pub fn has<S: Store>(&self) -> bool {
+        self.has_local::<S>() || self.parent.as_ref().is_some_and(|parent| parent.has::<S>())
+    }
+
+    pub fn has_local<S: Store>(&self) -> bool {
         self.stores.lock().unwrap().contains_key(&TypeId::of::<S>())
     }
 
     pub fn remove<S: Store>(&self) {
-        self.stores.lock().unwrap().remove(&TypeId::of::<S>());
+        self.notify_remove(&self.access::<S>(None));
+        let removed = self.stores.lock().unwrap().remove_entry(&TypeId::of::<S>());
+        notify_removed(removed);
+    }
+
+    #[cfg(any(feature = "dioxus", feature = "leptos"))]
+    /// Removes `S` if `entry` is still the registered instance, and not one
+    /// registered since.
+    pub(crate) fn remove_entry<S: Store>(&self, entry: &StoreEntry) {
+        let type_id = TypeId::of::<S>();
+        let is_current = self
+            .stores
+            .lock()
+            .unwrap()
+            .get(&type_id)
+            .is_some_and(|current| Arc::ptr_eq(&current.store, &entry.store));
+        if is_current {
+            self.remove::<S>();
+        }
+    }
+
+    /// Registers one of several independent instances of `S`, under `key`.
+    pub fn register_keyed<S: Store + Send + Sync>(&self, key: impl Into<String>, store: S) {
+        let key = key.into();
+        if !self.allows_register(&self.access::<S>(Some(&key))) {
+            return;
+        }
+        let type_id = TypeId::of::<S>();
+        let entry = StoreEntry::new(store);
+        let previous = self
+            .keyed
+            .lock()
+            .unwrap()
+            .insert((type_id, key), entry.clone());
+        notify_replaced(type_id, previous, &entry);
+    }
+
+    /// Looks the `key` instance of `S` up in this context, then in each parent in turn.
+    pub fn get_keyed<S: Store>(&self, key: &str) -> Option<S> {
+        let access = self.access::<S>(Some(key));
+        if !self.allows_get(&access) {
+            return None;
+        }
+        let type_id = TypeId::of::<S>();
+        let existing = self
+            .keyed
+            .lock()
+            .unwrap()
+            .get(&(type_id, key.to_string()))
+            .and_then(StoreEntry::downcast::<S>);
+        existing
+            .or_else(|| {
+                let entry = self.resolve_with_middleware::<S>(&access)?;
+                let previous = self
+                    .keyed
+                    .lock()
+                    .unwrap()
+                    .insert((type_id, key.to_string()), entry.clone());
+                notify_replaced(type_id, previous, &entry);
+                entry.downcast::<S>()
+            })
+            .or_else(|| {
+                self.parent
+                    .as_ref()
+                    .and_then(|parent| parent.get_keyed::<S>(key))
+            })
+    }
+
+    pub fn get_or_create_keyed<S: Injectable>(&self, key: &str) -> S {
+        if let Some(store) = self.get_keyed::<S>(key) {
+            store
+        } else {
+            assert_not_private::<S>();
+            let store = expect_resolved(construct_in::<S, _>(self));
+            self.register_keyed(key, store.clone());
+            store
+        }
+    }
+
+    pub fn has_keyed<S: Store>(&self, key: &str) -> bool {
+        self.get_keyed::<S>(key).is_some()
+    }
+
+    pub fn remove_keyed<S: Store>(&self, key: &str) {
+        self.notify_remove(&self.access::<S>(Some(key)));
+        let removed = self
+            .keyed
+            .lock()
+            .unwrap()
+            .remove(&(TypeId::of::<S>(), key.to_string()));
+        notify_removed(removed.map(|entry| (TypeId::of::<S>(), entry)));
     }
 
     pub fn clear(&self) {
-        self.stores.lock().unwrap().clear();
+        self.clear_with(|_| {});
+    }
+
+    /// Like [`clear`](Self::clear), calling `before_remove` on every store first.
+    pub fn clear_with(&self, before_remove: impl FnMut(&StoreInfo)) {
+        notify_clearing(&self.list(), before_remove);
+
+        let stores = std::mem::take(&mut *self.stores.lock().unwrap());
+        let keyed = std::mem::take(&mut *self.keyed.lock().unwrap());
+        self.factories.lock().unwrap().clear();
+
+        notify_removed(stores);
+        notify_removed(
+            keyed
+                .into_iter()
+                .map(|((type_id, _), store)| (type_id, store)),
+        );
     }
 
     pub fn name(&self) -> &str {
@@ -68,10 +365,21 @@ impl StoreContext {
         self.stores.lock().unwrap().len()
     }
 
+    /// Every store registered in this context (not its parents), oldest first.
+    pub fn list(&self) -> Vec<StoreInfo> {
+        let stores = self.stores.lock().unwrap().clone();
+        let keyed = self.keyed.lock().unwrap().clone();
+        list_entries(&stores, &keyed)
+    }
+
     pub fn clone_to(&self, name: impl Into<String>) -> Self {
         Self {
             stores: self.stores.clone(),
+            keyed: self.keyed.clone(),
+            factories: self.factories.clone(),
+            middleware: self.middleware.clone(),
             name: name.into(),
+            parent: self.parent.clone(),
         }
     }
 }
@@ -82,21 +390,16 @@ impl Default for StoreContext {
     }
 }
 
-static mut DEFAULT_CONTEXT: Option<StoreContext> = None;
+static DEFAULT_CONTEXT: LazyLock<RwLock<StoreContext>> =
+    LazyLock::new(|| RwLock::new(StoreContext::new()));
 
-pub fn get_default_context() -> &'static StoreContext {
-    unsafe {
-        if DEFAULT_CONTEXT.is_none() {
-            DEFAULT_CONTEXT = Some(StoreContext::new());
-        }
-        DEFAULT_CONTEXT.as_ref().unwrap()
-    }
+/// The default context. Contexts are handles, so the returned clone shares its stores.
+pub fn get_default_context() -> StoreContext {
+    DEFAULT_CONTEXT.read().unwrap().clone()
 }
 
 pub fn set_default_context(context: StoreContext) {
-    unsafe {
-        DEFAULT_CONTEXT = Some(context);
-    }
+    *DEFAULT_CONTEXT.write().unwrap() = context;
 }
 
 pub fn provide_store<S: Store + Send + Sync>(store: S) {
@@ -118,10 +421,32 @@ pub fn get_context_store<S: Store>() -> Option<S> {
 mod dioxus_support {
     use super::*;
     use dioxus::prelude::*;
-
+    use std::rc::Rc;
+
+    /// Provides a fresh [`StoreContext`] to its children.
+    ///
+    /// `stores` is called once with the new context to add
+    /// [`ContextMiddleware`] and pre-register stores, and the context is
+    /// cleared when the provider unmounts.
+    ///
+    /// Inside another provider, lookups that miss fall back to the enclosing
+    /// provider, so a nested provider only shadows the stores it registers:
+    ///
+    /// ```ignore
+    /// StoreProvider { stores: |context: StoreContext| context.register(MockApiStore::new()),
+    ///     Preview {}
+    /// }
+    /// ```
+    ///
+    /// `inherit: true` also falls back to the default context at the top level,
+    /// and `inherit: false` isolates the provider from everything above it.
     #[component]
-    pub fn StoreProvider(children: Element) -> Element {
-        let context = StoreContext::new();
+    pub fn StoreProvider(
+        stores: Option<Callback<StoreContext>>,
+        inherit: Option<bool>,
+        children: Element,
+    ) -> Element {
+        let context = use_scoped_context(StoreContext::new, stores, inherit);
         use_context_provider(|| context);
 
         rsx! {
@@ -129,9 +454,27 @@ mod dioxus_support {
         }
     }
 
+    /// The enclosing [`NamedStoreProvider`] contexts, innermost last.
+    #[derive(Clone, Default)]
+    struct NamedContexts(Rc<Vec<StoreContext>>);
+
+    /// A [`StoreProvider`] that descendants can also target by name, with
+    /// [`use_named_store`], through other providers.
     #[component]
-    pub fn NamedStoreProvider(name: String, children: Element) -> Element {
-        let context = StoreContext::with_name(name);
+    pub fn NamedStoreProvider(
+        name: String,
+        stores: Option<Callback<StoreContext>>,
+        inherit: Option<bool>,
+        children: Element,
+    ) -> Element {
+        let context = use_scoped_context(move || StoreContext::with_name(name), stores, inherit);
+        use_context_provider(|| {
+            let mut named = try_consume_context::<NamedContexts>()
+                .map(|named| named.0.to_vec())
+                .unwrap_or_default();
+            named.push(context.clone());
+            NamedContexts(Rc::new(named))
+        });
         use_context_provider(|| context);
 
         rsx! {
@@ -139,9 +482,89 @@ mod dioxus_support {
         }
     }
 
+    fn use_scoped_context(
+        create: impl FnOnce() -> StoreContext,
+        stores: Option<Callback<StoreContext>>,
+        inherit: Option<bool>,
+    ) -> StoreContext {
+        let context = use_hook(|| {
+            let enclosing = try_consume_context::<StoreContext>();
+            let parent = match inherit {
+                None => enclosing,
+                Some(true) => Some(enclosing.unwrap_or_else(get_default_context)),
+                Some(false) => None,
+            };
+            let mut context = create();
+            if let Some(parent) = parent {
+                context = context.with_parent(parent);
+            }
+            if let Some(stores) = stores {
+                stores.call(context.clone());
+            }
+            context
+        });
+
+        use_drop({
+            let context = context.clone();
+            move || context.clear()
+        });
+
+        context
+    }
+
+    /// Registers `store` in the nearest provided context when the component
+    /// mounts, and removes it when the component unmounts.
+    ///
+    /// Later renders keep the first instance, so a re-mount starts fresh.
     pub fn use_provide_store<S: Store + Send + Sync>(store: S) {
+        use_provide_store_with(store, false);
+    }
+
+    /// Like [`use_provide_store`]; with `keep_alive`, the store stays registered
+    /// after the component unmounts, and a re-mount keeps using it.
+    pub fn use_provide_store_with<S: Store + Send + Sync>(store: S, keep_alive: bool) {
         let context = use_context::<StoreContext>();
-        context.register(store);
+        let entry = use_hook(|| {
+            if keep_alive && context.has_local::<S>() {
+                return None;
+            }
+            context.register_entry(store)
+        });
+
+        use_drop(move || {
+            if let Some(entry) = entry.filter(|_| !keep_alive) {
+                context.remove_entry::<S>(&entry);
+            }
+        });
+    }
+
+    /// The context of the nearest enclosing [`NamedStoreProvider`] called `name`.
+    pub fn use_named_context(name: &str) -> Option<StoreContext> {
+        let named = try_consume_context::<NamedContexts>()?;
+        named
+            .0
+            .iter()
+            .rev()
+            .find(|context| context.name() == name)
+            .cloned()
+    }
+
+    /// The instance of `S` in the nearest enclosing [`NamedStoreProvider`]
+    /// called `name`, skipping closer providers:
+    ///
+    /// ```ignore
+    /// let selection = use_named_store::<SelectionStore>("left_panel");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If no enclosing provider is called `name`.
+    pub fn use_named_store<S: Store + Default + Send + Sync>(name: &str) -> S {
+        use_named_context(name)
+            .unwrap_or_else(|| {
+                panic!("no NamedStoreProvider called {name:?} encloses this component")
+            })
+            .get_or_create::<S>()
     }
 
     pub fn use_store_from_context<S: Store + Default + Send + Sync>() -> S {
@@ -157,9 +580,151 @@ mod dioxus_support {
 
 #[cfg(feature = "dioxus")]
 pub use dioxus_support::*;
+
+#[cfg(feature = "serde")]
+mod serde_support {
+    use super::*;
+    use crate::hydration::{hydrate_in_context, hydration_key};
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+    use std::collections::BTreeMap;
+
+    /// The state of one store instance in a [`ContextSnapshot`].
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct StoreSnapshot {
+        /// [`SerializableStore::store_key`](crate::SerializableStore::store_key) of the store.
+        pub store: String,
+        /// The key of a keyed instance.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub key: Option<String>,
+        pub state: Value,
+    }
+
+    /// Every named context and the state of its stores; see [`ContextManager::snapshot`].
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    pub struct ContextSnapshot {
+        pub current: String,
+        pub contexts: BTreeMap<String, Vec<StoreSnapshot>>,
+    }
+
+    fn snapshot_context(context: &StoreContext) -> Result<Vec<StoreSnapshot>, serde_json::Error> {
+        let mut stores = Vec::new();
+        for info in context.list() {
+            let (Some(store), Some(state)) = (hydration_key(info.type_id), info.serialize()) else {
+                continue;
+            };
+            stores.push(StoreSnapshot {
+                store: store.to_string(),
+                key: info.key.clone(),
+                state: state?,
+            });
+        }
+        Ok(stores)
+    }
+
+    fn restore_context(
+        context: &StoreContext,
+        stores: Vec<StoreSnapshot>,
+    ) -> Result<(), serde_json::Error> {
+        crate::batch(|| {
+            for snapshot in stores {
+                if let Some(result) = hydrate_in_context(
+                    context,
+                    &snapshot.store,
+                    snapshot.key.as_deref(),
+                    snapshot.state,
+                ) {
+                    result?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// A whole [`StoreContext`] in transit, e.g. from the server to the client
+    /// or from the main thread to a worker.
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct ContextPayload {
+        pub name: String,
+        pub stores: Vec<StoreSnapshot>,
+    }
+
+    impl StoreContext {
+        /// Serializes the context with the state of every store registered with
+        /// [`register_hydratable`](crate::register_hydratable) into one payload.
+        ///
+        /// Parents are not included.
+        pub fn to_payload(&self) -> Result<String, serde_json::Error> {
+            serde_json::to_string(&ContextPayload {
+                name: self.name.clone(),
+                stores: snapshot_context(self)?,
+            })
+        }
+
+        /// Rebuilds a context produced by [`to_payload`](Self::to_payload):
+        ///
+        /// ```ignore
+        /// // server
+        /// let payload = context.to_payload()?;
+        /// // client
+        /// let context = StoreContext::from_payload(&payload)?;
+        /// ```
+        pub fn from_payload(payload: &str) -> Result<Self, serde_json::Error> {
+            let payload: ContextPayload = serde_json::from_str(payload)?;
+            let context = Self::with_name(payload.name);
+            restore_context(&context, payload.stores)?;
+            Ok(context)
+        }
+
+        /// Applies a payload to the stores of this context, creating missing ones
+        /// and updating existing ones in place.
+        pub fn apply_payload(&self, payload: &str) -> Result<(), serde_json::Error> {
+            let payload: ContextPayload = serde_json::from_str(payload)?;
+            restore_context(self, payload.stores)
+        }
+    }
+
+    impl ContextManager {
+        /// Captures every context with the state of its stores, e.g. to save a
+        /// workspace or the whole app across restarts.
+        ///
+        /// Only stores registered with [`register_hydratable`](crate::register_hydratable)
+        /// are captured.
+        pub fn snapshot(&self) -> Result<ContextSnapshot, serde_json::Error> {
+            let mut contexts = BTreeMap::new();
+            for (name, context) in &self.contexts {
+                contexts.insert(name.clone(), snapshot_context(context)?);
+            }
+            Ok(ContextSnapshot {
+                current: self.current.clone(),
+                contexts,
+            })
+        }
+
+        /// Applies a snapshot: missing contexts and stores are created, and
+        /// existing stores are updated in place so their subscribers are notified.
+        pub fn restore(&mut self, snapshot: ContextSnapshot) -> Result<(), serde_json::Error> {
+            for (name, stores) in snapshot.contexts {
+                let context = self
+                    .contexts
+                    .entry(name.clone())
+                    .or_insert_with(|| StoreContext::with_name(name));
+                restore_context(context, stores)?;
+            }
+            self.set_current(snapshot.current);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use serde_support::*;
+
 pub struct ContextManager {
     contexts: HashMap<String, StoreContext>,
     current: String,
+    // Active `ContextGuard`s, outermost first, with the context each one restores
+    switches: Vec<(u64, String)>,
 }
 
 impl ContextManager {
@@ -167,6 +732,7 @@ impl ContextManager {
         let mut manager = Self {
             contexts: HashMap::new(),
             current: "default".to_string(),
+            switches: Vec::new(),
         };
         manager
             .contexts
@@ -196,6 +762,10 @@ impl ContextManager {
         self.contexts.get(&self.current).unwrap()
     }
 
+    pub fn current_name(&self) -> &str {
+        &self.current
+    }
+
     pub fn remove_context(&mut self, name: &str) {
         if name != "default" {
             self.contexts.remove(name);
@@ -206,6 +776,26 @@ impl ContextManager {
         self.contexts.retain(|k, _| k == "default");
         self.current = "default".to_string();
     }
+
+    /// Makes `name` current, recording the previous context under `token`.
+    fn push_switch(&mut self, token: u64, name: String) {
+        self.switches.push((token, self.current.clone()));
+        self.set_current(name);
+    }
+
+    /// Ends the switch `token`. The innermost switch restores its previous
+    /// context; an outer one ending first hands its previous context to the
+    /// switch made after it, so the last one to end restores it.
+    fn pop_switch(&mut self, token: u64) {
+        let Some(index) = self.switches.iter().position(|(id, _)| *id == token) else {
+            return;
+        };
+        let (_, previous) = self.switches.remove(index);
+        match self.switches.get_mut(index) {
+            Some((_, next)) => *next = previous,
+            None => self.set_current(previous),
+        }
+    }
 }
 
 impl Default for ContextManager {
@@ -214,28 +804,180 @@ impl Default for ContextManager {
     }
 }
 
-static mut GLOBAL_CONTEXT_MANAGER: Option<ContextManager> = None;
+static GLOBAL_CONTEXT_MANAGER: LazyLock<RwLock<ContextManager>> =
+    LazyLock::new(|| RwLock::new(ContextManager::new()));
+
+thread_local! {
+    static THREAD_CONTEXT_MANAGER: RefCell<ContextManager> = RefCell::new(ContextManager::new());
+}
+
+static PER_THREAD_CONTEXTS: AtomicBool = AtomicBool::new(false);
+
+/// Where the current context and named contexts live.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ContextMode {
+    /// One context manager shared by the whole process.
+    #[default]
+    Global,
+    /// Every thread has its own context manager, and the global store
+    /// functions resolve against the thread's current context, so parallel
+    /// tests and workers never share stores.
+    PerThread,
+}
+
+pub fn set_context_mode(mode: ContextMode) {
+    PER_THREAD_CONTEXTS.store(mode == ContextMode::PerThread, Ordering::SeqCst);
+}
+
+pub fn context_mode() -> ContextMode {
+    if PER_THREAD_CONTEXTS.load(Ordering::SeqCst) {
+        ContextMode::PerThread
+    } else {
+        ContextMode::Global
+    }
+}
+
+/// Exclusive access to the global context manager, held until the guard is dropped.
+///
+/// Store and context functions such as [`current_context`], [`create_named_context`]
+/// or [`with_context`] lock the manager too, so calling them while holding the
+/// guard deadlocks. It also ignores [`ContextMode::PerThread`].
+#[deprecated(note = "use `with_context_manager`, which can't be held across store calls")]
+pub fn get_context_manager() -> RwLockWriteGuard<'static, ContextManager> {
+    GLOBAL_CONTEXT_MANAGER.write().unwrap()
+}
 
-pub fn get_context_manager() -> &'static mut ContextManager {
-    unsafe {
-        if GLOBAL_CONTEXT_MANAGER.is_none() {
-            GLOBAL_CONTEXT_MANAGER = Some(ContextManager::new());
+/// Runs `f` on the context manager of the current [`ContextMode`].
+///
+/// The manager is locked while `f` runs, so `f` must not call the store or
+/// context functions itself.
+pub fn with_context_manager<R>(f: impl FnOnce(&mut ContextManager) -> R) -> R {
+    with_mode_manager(context_mode(), f)
+}
+
+fn with_mode_manager<R>(mode: ContextMode, f: impl FnOnce(&mut ContextManager) -> R) -> R {
+    match mode {
+        ContextMode::Global => f(&mut GLOBAL_CONTEXT_MANAGER.write().unwrap()),
+        ContextMode::PerThread => {
+            THREAD_CONTEXT_MANAGER.with(|manager| f(&mut manager.borrow_mut()))
         }
-        GLOBAL_CONTEXT_MANAGER.as_mut().unwrap()
     }
 }
 
-pub fn create_named_context(name: impl Into<String>) -> &'static StoreContext {
-    let manager = get_context_manager();
-    manager.create_context(name)
+/// The context the global store functions should use instead of the global
+/// registry: the request context if any, else the thread's current context
+/// in [`ContextMode::PerThread`].
+pub(crate) fn scoped_context() -> Option<StoreContext> {
+    current_request_context().or_else(|| match context_mode() {
+        ContextMode::Global => None,
+        ContextMode::PerThread => Some(current_context()),
+    })
+}
+
+pub fn create_named_context(name: impl Into<String>) -> StoreContext {
+    with_context_manager(|manager| manager.create_context(name).clone())
 }
 
 pub fn switch_to_context(name: impl Into<String>) {
-    let manager = get_context_manager();
-    manager.set_current(name);
+    with_context_manager(|manager| manager.set_current(name));
+}
+
+pub fn current_context() -> StoreContext {
+    with_context_manager(|manager| manager.current().clone())
+}
+
+static NEXT_SWITCH: AtomicU64 = AtomicU64::new(0);
+
+/// Switches the current context back to the previous one when dropped.
+///
+/// Guards may be dropped in any order, also across threads sharing the
+/// [`ContextMode::Global`] manager: the context current before the first of
+/// them is restored once all are dropped. A guard stays on the thread that
+/// created it, so in [`ContextMode::PerThread`] it restores that thread's context.
+#[must_use = "the previous context is restored as soon as the guard is dropped"]
+pub struct ContextGuard {
+    token: u64,
+    mode: ContextMode,
+    _thread: PhantomData<*const ()>,
+}
+
+impl ContextGuard {
+    /// Makes `name` the current context until the guard is dropped.
+    pub fn switch(name: impl Into<String>) -> Self {
+        let token = NEXT_SWITCH.fetch_add(1, Ordering::Relaxed);
+        let mode = context_mode();
+        with_mode_manager(mode, |manager| manager.push_switch(token, name.into()));
+        ContextGuard {
+            token,
+            mode,
+            _thread: PhantomData,
+        }
+    }
 }
 
-pub fn current_context() -> &'static StoreContext {
-    let manager = get_context_manager();
-    manager.current()
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        with_mode_manager(self.mode, |manager| manager.pop_switch(self.token));
+    }
+}
+
+/// Runs `f` with `name` as the current context, then restores the previous one.
+pub fn with_context<R>(name: impl Into<String>, f: impl FnOnce() -> R) -> R {
+    let _guard = ContextGuard::switch(name);
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn switches_ending_out_of_order_restore_the_first_previous_context() {
+        let mut manager = ContextManager::new();
+        manager.create_context("a");
+        manager.create_context("b");
+
+        manager.push_switch(1, "a".to_string());
+        manager.push_switch(2, "b".to_string());
+        manager.pop_switch(1);
+        assert_eq!(manager.current_name(), "b");
+        manager.pop_switch(2);
+        assert_eq!(manager.current_name(), "default");
+
+        manager.push_switch(3, "a".to_string());
+        manager.push_switch(4, "b".to_string());
+        manager.pop_switch(4);
+        assert_eq!(manager.current_name(), "a");
+        manager.pop_switch(3);
+        assert_eq!(manager.current_name(), "default");
+    }
+
+    #[test]
+    fn guards_of_other_threads_do_not_restore_a_stale_context() {
+        create_named_context("guard-main");
+        create_named_context("guard-worker");
+
+        let outer = ContextGuard::switch("guard-main");
+        let (switched_tx, switched_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let worker = std::thread::spawn(move || {
+            let _guard = ContextGuard::switch("guard-worker");
+            switched_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+
+        switched_rx.recv().unwrap();
+        drop(outer);
+        assert_eq!(
+            with_context_manager(|manager| manager.current_name().to_string()),
+            "guard-worker"
+        );
+
+        release_tx.send(()).unwrap();
+        worker.join().unwrap();
+        assert_eq!(
+            with_context_manager(|manager| manager.current_name().to_string()),
+            "default"
+        );
+    }
 }
@@ -0,0 +1,65 @@
+use crate::store::StoreEntry;
+use crate::Store;
+use std::any::TypeId;
+use std::sync::Arc;
+
+/// A store operation seen by a [`ContextMiddleware`].
+#[derive(Clone, Copy, Debug)]
+pub struct ContextAccess<'a> {
+    /// Name of the context the middleware was added to.
+    pub context: &'a str,
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+    /// The instance key, for keyed stores.
+    pub key: Option<&'a str>,
+}
+
+/// Runs on the `register`, `get` and `remove` calls of one [`StoreContext`](crate::StoreContext):
+///
+/// ```ignore
+/// struct OnlyUi;
+///
+/// impl ContextMiddleware for OnlyUi {
+///     fn on_get(&self, access: &ContextAccess) -> bool {
+///         access.type_name.contains("::ui::")
+///     }
+/// }
+///
+/// context.add_middleware(OnlyUi);
+/// ```
+///
+/// Middleware runs in the order it was added. A lookup that misses in a context
+/// falls back to its parent, which runs its own middleware.
+pub trait ContextMiddleware: Send + Sync + 'static {
+    /// Called before a store is registered; returning `false` drops the registration.
+    fn on_register(&self, _access: &ContextAccess) -> bool {
+        true
+    }
+
+    /// Called before a store is looked up; returning `false` makes the lookup
+    /// miss, without falling back to parents.
+    fn on_get(&self, _access: &ContextAccess) -> bool {
+        true
+    }
+
+    /// Called when a lookup misses in the context, before trying its parent.
+    /// A returned store of the requested type is registered and used, e.g. to
+    /// build stores from a dependency container on first use.
+    fn resolve(&self, _access: &ContextAccess) -> Option<ProvidedStore> {
+        None
+    }
+
+    /// Called before a store is removed.
+    fn on_remove(&self, _access: &ContextAccess) {}
+}
+
+/// A store returned by [`ContextMiddleware::resolve`].
+pub struct ProvidedStore(pub(crate) StoreEntry);
+
+impl ProvidedStore {
+    pub fn new<S: Store + Send + Sync>(store: S) -> Self {
+        Self(StoreEntry::new(store))
+    }
+}
+
+pub(crate) type Middleware = Arc<dyn ContextMiddleware>;
@@ -0,0 +1,144 @@
+use crate::{list_stores, use_tracking_scope, StoreInfo};
+use dioxus::prelude::*;
+use serde_json::{Map, Value};
+
+const PANEL_STYLE: &str = "position: fixed; bottom: 0; right: 0; z-index: 99999; \
+    max-height: 50vh; width: 420px; overflow: auto; background: #1e1e1e; color: #ddd; \
+    font: 12px monospace; border: 1px solid #444;";
+const HEADER_STYLE: &str = "display: flex; justify-content: space-between; padding: 4px 8px; \
+    background: #333; cursor: pointer;";
+
+/// Development panel listing every registered store with its live field values.
+///
+/// Field values, subscriber counts, editing and reset need the store type to be
+/// registered with [`register_hydratable`](crate::register_hydratable); other
+/// stores are only listed. Render it once near the root, behind a debug check:
+///
+/// ```ignore
+/// rsx! {
+///     App {}
+///     if cfg!(debug_assertions) { ReaxiveDevtools {} }
+/// }
+/// ```
+#[component]
+pub fn ReaxiveDevtools(#[props(default)] open: bool) -> Element {
+    let mut open = use_signal(|| open);
+    // Bumped to list stores registered since the last render
+    let mut refresh = use_signal(|| 0u32);
+    let _ = refresh.read();
+    let stores = list_stores();
+
+    rsx! {
+        div { style: PANEL_STYLE,
+            div { style: HEADER_STYLE, onclick: move |_| open.toggle(),
+                span { "reaxive · {stores.len()} stores" }
+                span { if open() { "▾" } else { "▸" } }
+            }
+            if open() {
+                div { style: "padding: 4px 8px;",
+                    button { onclick: move |_| *refresh.write() += 1, "refresh" }
+                }
+                for info in stores {
+                    DevtoolsStore { info }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn DevtoolsStore(info: StoreInfo) -> Element {
+    let mut error = use_signal(|| None::<String>);
+    let _tracking = use_tracking_scope();
+    info.track();
+    let title = match &info.key {
+        Some(key) => format!("{} [{key}]", info.name()),
+        None => info.name().to_string(),
+    };
+
+    let fields = match info.serialize() {
+        Some(Ok(Value::Object(fields))) => fields,
+        Some(Ok(value)) => Map::from_iter([("value".to_string(), value)]),
+        Some(Err(serialize_error)) => {
+            return rsx! {
+                div { style: "padding: 4px 8px; border-top: 1px solid #444; color: #f66;",
+                    "{title}: {serialize_error}"
+                }
+            };
+        }
+        None => {
+            return rsx! {
+                div { style: "padding: 4px 8px; border-top: 1px solid #444; opacity: 0.6;",
+                    "{title} (not hydratable)"
+                }
+            };
+        }
+    };
+    let stats = info.field_stats();
+
+    let reset = {
+        let info = info.clone();
+        move |_| {
+            error.set(info.reset().and_then(Result::err).map(|e| e.to_string()));
+        }
+    };
+
+    rsx! {
+        div { style: "padding: 4px 8px; border-top: 1px solid #444;",
+            div { style: "display: flex; justify-content: space-between;",
+                b { "{title}" }
+                button { onclick: reset, "reset" }
+            }
+            if let Some(error) = error() {
+                div { style: "color: #f66;", "{error}" }
+            }
+            for (field, value) in fields {
+                DevtoolsField {
+                    key: "{field}",
+                    info: info.clone(),
+                    subscribers: stats
+                        .iter()
+                        .find(|(name, _)| *name == field)
+                        .map(|(_, stats)| stats.subscribers + stats.observers),
+                    field,
+                    value: value.to_string(),
+                    error,
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn DevtoolsField(
+    info: StoreInfo,
+    field: String,
+    value: String,
+    subscribers: Option<usize>,
+    error: Signal<Option<String>>,
+) -> Element {
+    let apply = {
+        let field = field.clone();
+        move |event: Event<FormData>| {
+            let result = serde_json::from_str::<Value>(&event.value()).and_then(|value| {
+                let patch = Value::Object(Map::from_iter([(field.clone(), value)]));
+                info.restore(patch).unwrap_or(Ok(()))
+            });
+            error.set(result.err().map(|e| format!("{field}: {e}")));
+        }
+    };
+
+    rsx! {
+        div { style: "display: flex; gap: 6px; align-items: center; margin: 2px 0;",
+            span { style: "min-width: 90px;", "{field}" }
+            input {
+                style: "flex: 1; background: #111; color: #ddd; border: 1px solid #555;",
+                value: "{value}",
+                onchange: apply,
+            }
+            if let Some(subscribers) = subscribers {
+                span { title: "subscribers and observers", "{subscribers}" }
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{batch, use_store, EventBus, Store};
+use std::any::Any;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// A store that updates its observable fields in response to actions of type `A`:
+///
+/// ```ignore
+/// enum CartAction { Add(Item), Remove(u32), Clear }
+///
+/// impl Reducer<CartAction> for CartStore {
+///     fn reduce(&self, action: &CartAction) {
+///         match action {
+///             CartAction::Add(item) => self.items.push(item.clone()),
+///             CartAction::Remove(id) => self.items.retain(|item| item.id != *id),
+///             CartAction::Clear => self.items.clear(),
+///         }
+///     }
+/// }
+///
+/// register_reducer::<CartStore, CartAction>();
+/// dispatch(CartAction::Clear);
+/// ```
+///
+/// Components keep reading the fields as usual; only writes go through actions.
+pub trait Reducer<A>: Store {
+    fn reduce(&self, action: &A);
+}
+
+static REDUCERS: LazyLock<EventBus> = LazyLock::new(EventBus::new);
+
+/// Makes [`dispatch`] pass actions of type `A` to the instance of `S` that
+/// [`use_store`] resolves at that time.
+///
+/// Returns an id for [`remove_reducer`].
+pub fn register_reducer<S, A>() -> usize
+where
+    S: Reducer<A> + Default + Send + Sync,
+    A: 'static,
+{
+    add_reducer(|action: &A| use_store::<S>().reduce(action))
+}
+
+/// Registers a closure handling actions of type `A`, e.g. one updating several stores.
+pub fn add_reducer<A: 'static>(reducer: impl Fn(&A) + Send + Sync + 'static) -> usize {
+    REDUCERS.subscribe(reducer)
+}
+
+pub fn remove_reducer<A: 'static>(id: usize) {
+    REDUCERS.unsubscribe::<A>(id);
+}
+
+/// Passes `action` through the middleware added with [`add_middleware`], then
+/// runs every reducer registered for `A`, in registration order.
+///
+/// The reducers run in one [`batch`], so views update once per action.
+/// Actions without reducers are ignored.
+pub fn dispatch<A: Send + 'static>(action: A) {
+    let chain: Arc<[Middleware]> = MIDDLEWARE
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(_, middleware)| middleware.clone())
+        .collect();
+    Next { chain, index: 0 }.run(AnyAction::new(action));
+}
+
+/// An action of any type on its way through the dispatch middleware.
+pub struct AnyAction {
+    action: Box<dyn Any + Send>,
+    type_name: &'static str,
+    reduce: fn(Box<dyn Any + Send>),
+}
+
+impl AnyAction {
+    pub fn new<A: Send + 'static>(action: A) -> Self {
+        Self {
+            action: Box::new(action),
+            type_name: std::any::type_name::<A>(),
+            reduce: |action| {
+                if let Ok(action) = action.downcast::<A>() {
+                    batch(|| REDUCERS.emit(*action));
+                }
+            },
+        }
+    }
+
+    /// The type name of the action, e.g. for logging.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn is<A: 'static>(&self) -> bool {
+        self.action.is::<A>()
+    }
+
+    pub fn downcast_ref<A: 'static>(&self) -> Option<&A> {
+        self.action.downcast_ref::<A>()
+    }
+
+    /// Takes the action out, or gives it back if it is not an `A`.
+    pub fn downcast<A: 'static>(self) -> Result<A, Self> {
+        if self.is::<A>() {
+            Ok(*self.action.downcast::<A>().unwrap())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Debug for AnyAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AnyAction").field(&self.type_name).finish()
+    }
+}
+
+/// The rest of the dispatch pipeline after the current middleware.
+///
+/// It can be kept and run later, e.g. once an async operation completes.
+#[derive(Clone)]
+pub struct Next {
+    chain: Arc<[Middleware]>,
+    index: usize,
+}
+
+impl Next {
+    /// Passes `action` to the next middleware, or to the reducers after the last one.
+    pub fn run(self, action: AnyAction) {
+        match self.chain.get(self.index).cloned() {
+            Some(middleware) => middleware(
+                action,
+                Next {
+                    chain: self.chain,
+                    index: self.index + 1,
+                },
+            ),
+            None => (action.reduce)(action.action),
+        }
+    }
+}
+
+type Middleware = Arc<dyn Fn(AnyAction, Next) + Send + Sync>;
+
+static MIDDLEWARE: LazyLock<RwLock<Vec<(usize, Middleware)>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+static NEXT_MIDDLEWARE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Runs `middleware` on every dispatched action, after the middleware added
+/// before it. It decides what reaches the reducers by calling `next`: with
+/// the action, with another one, later, or not at all.
+///
+/// ```ignore
+/// add_middleware(|action, next| {
+///     log::debug!("dispatch {}", action.type_name());
+///     next.run(action);
+/// });
+///
+/// // Async actions: run the request, then dispatch its result
+/// add_middleware(|action, next| match action.downcast::<LoadProfile>() {
+///     Ok(LoadProfile(id)) => {
+///         spawn(async move { dispatch(ProfileLoaded(api::profile(id).await)) });
+///     }
+///     Err(action) => next.run(action),
+/// });
+/// ```
+///
+/// Returns an id for [`remove_middleware`].
+pub fn add_middleware(middleware: impl Fn(AnyAction, Next) + Send + Sync + 'static) -> usize {
+    let id = NEXT_MIDDLEWARE_ID.fetch_add(1, Ordering::Relaxed);
+    MIDDLEWARE.write().unwrap().push((id, Arc::new(middleware)));
+    id
+}
+
+pub fn remove_middleware(id: usize) {
+    MIDDLEWARE
+        .write()
+        .unwrap()
+        .retain(|(middleware_id, _)| *middleware_id != id);
+}
@@ -0,0 +1,146 @@
+use crate::{add_reducer, remove_reducer, CancelToken, Observable, ObservableValue};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+type Spawner = Rc<dyn Fn(Pin<Box<dyn Future<Output = ()>>>)>;
+
+thread_local! {
+    static SPAWNER: RefCell<Option<Spawner>> = const { RefCell::new(None) };
+}
+
+/// Sets how the current thread runs the futures of effects, e.g. on a
+/// [`TestScheduler`](crate::testing::TestScheduler) in tests.
+///
+/// By default they are spawned with `wasm_bindgen_futures::spawn_local` in
+/// the browser, and on the Dioxus runtime elsewhere.
+pub fn set_spawner(spawner: impl Fn(Pin<Box<dyn Future<Output = ()>>>) + 'static) {
+    SPAWNER.with(|current| *current.borrow_mut() = Some(Rc::new(spawner)));
+}
+
+/// Runs `future` in the background with the spawner of the current thread.
+pub fn spawn_effect(future: impl Future<Output = ()> + 'static) {
+    let spawner = SPAWNER.with(|spawner| spawner.borrow().clone());
+    match spawner {
+        Some(spawner) => spawner(Box::pin(future)),
+        None => spawn_default(future),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn spawn_default(future: impl Future<Output = ()> + 'static) {
+    wasm_bindgen_futures::spawn_local(future);
+}
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "dioxus"))]
+fn spawn_default(future: impl Future<Output = ()> + 'static) {
+    dioxus::prelude::spawn_forever(future);
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "dioxus")))]
+fn spawn_default(_future: impl Future<Output = ()> + 'static) {
+    panic!("effects need a spawner on this thread; install one with `set_spawner`");
+}
+
+/// Async work started in response to actions or changes, until disposed.
+///
+/// Disposing it, or dropping it, cancels the [`CancelToken`] of every run
+/// still in flight and stops new runs.
+#[must_use = "the effect stops as soon as the handle is dropped"]
+pub struct EffectHandle {
+    cancel: CancelToken,
+    unsubscribe: Option<Box<dyn FnOnce()>>,
+}
+
+impl EffectHandle {
+    pub fn dispose(mut self) {
+        self.stop();
+    }
+
+    /// Keeps the effect running for the rest of the program.
+    pub fn detach(mut self) {
+        self.unsubscribe = None;
+    }
+
+    fn stop(&mut self) {
+        if let Some(unsubscribe) = self.unsubscribe.take() {
+            unsubscribe();
+            self.cancel.cancel();
+        }
+    }
+}
+
+impl Drop for EffectHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Runs `effect` in the background for every dispatched action of type `A`,
+/// after its reducers ran:
+///
+/// ```ignore
+/// on_action(|LoginSucceeded(user): &LoginSucceeded, cancel| {
+///     let user = user.clone();
+///     async move {
+///         if let Some(profile) = cancel.run(api::profile(&user)).await {
+///             use_store::<ProfileStore>().profile.assign(Some(profile));
+///         }
+///     }
+/// })
+/// .detach();
+/// ```
+///
+/// Effects read and write stores through [`use_store`](crate::use_store) and
+/// may [`dispatch`](crate::dispatch) further actions. Each run gets its own
+/// token, cancelled when the effect is disposed.
+pub fn on_action<A, F, Fut>(effect: F) -> EffectHandle
+where
+    A: 'static,
+    F: Fn(&A, CancelToken) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + 'static,
+{
+    let cancel = CancelToken::new();
+    let id = add_reducer({
+        let cancel = cancel.clone();
+        move |action: &A| {
+            let run = cancel.child();
+            let future = effect(action, run.clone());
+            spawn_effect(async move {
+                run.run(future).await;
+            });
+        }
+    });
+
+    EffectHandle {
+        cancel,
+        unsubscribe: Some(Box::new(move || remove_reducer::<A>(id))),
+    }
+}
+
+/// Like [`on_action`], for every change of `observable`.
+pub fn on_mutation<T, F, Fut>(observable: &ObservableValue<T>, effect: F) -> EffectHandle
+where
+    T: Clone + 'static,
+    F: Fn(&T, CancelToken) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + 'static,
+{
+    let cancel = CancelToken::new();
+    let id = observable.subscribe({
+        let cancel = cancel.clone();
+        move |value: &T| {
+            let run = cancel.child();
+            let future = effect(value, run.clone());
+            spawn_effect(async move {
+                run.run(future).await;
+            });
+        }
+    });
+
+    let observable = observable.clone();
+    EffectHandle {
+        cancel,
+        unsubscribe: Some(Box::new(move || observable.unsubscribe(id))),
+    }
+}
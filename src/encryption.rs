@@ -0,0 +1,178 @@
+use crate::PersistError;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::sync::{LazyLock, RwLock};
+
+/// Prefix of encrypted values, followed by the base64 of the nonce and ciphertext.
+const PREFIX: &str = "reaxive-aes256gcm:";
+const NONCE_LEN: usize = 12;
+
+struct Encryption {
+    cipher: Aes256Gcm,
+    // Reads unencrypted values, to migrate data saved before the key was set
+    accept_plaintext: bool,
+}
+
+static CIPHER: LazyLock<RwLock<Option<Encryption>>> = LazyLock::new(|| RwLock::new(None));
+
+/// Encrypts every persisted value with AES-256-GCM under `key` from now on.
+///
+/// Each value is bound to its storage key, so it can't be moved to another
+/// key. Unencrypted values, and values that fail to decrypt, e.g. after the
+/// key changed, are treated as missing.
+pub fn set_persist_key(key: [u8; 32]) {
+    install(key, false);
+}
+
+/// Like [`set_persist_key`], but still reads unencrypted values, which are
+/// re-saved encrypted on their next change. Only for migrating data saved
+/// before encryption was enabled: anyone able to write the storage can then
+/// replace encrypted values with plaintext ones.
+pub fn set_persist_key_migrating(key: [u8; 32]) {
+    install(key, true);
+}
+
+fn install(key: [u8; 32], accept_plaintext: bool) {
+    *CIPHER.write().unwrap() = Some(Encryption {
+        cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)),
+        accept_plaintext,
+    });
+}
+
+/// Goes back to persisting plaintext JSON.
+pub fn clear_persist_key() {
+    *CIPHER.write().unwrap() = None;
+}
+
+/// Encrypts `json`, saved under `key`, if a key is set.
+pub(crate) fn seal(key: &str, json: String) -> Result<String, PersistError> {
+    let encryption = CIPHER.read().unwrap();
+    let Some(encryption) = encryption.as_ref() else {
+        return Ok(json);
+    };
+
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let payload = Payload {
+        msg: json.as_bytes(),
+        aad: key.as_bytes(),
+    };
+    let ciphertext = encryption
+        .cipher
+        .encrypt(&nonce, payload)
+        .map_err(|error| PersistError::Storage(format!("encryption failed: {error}")))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    Ok(format!("{PREFIX}{}", STANDARD.encode(sealed)))
+}
+
+/// Decrypts a value written by [`seal`] under `key`. Plaintext is passed
+/// through without a key, or while migrating.
+pub(crate) fn open(key: &str, stored: String) -> Option<String> {
+    let encryption = CIPHER.read().unwrap();
+    let Some(encoded) = stored.strip_prefix(PREFIX) else {
+        return match encryption.as_ref() {
+            Some(encryption) if !encryption.accept_plaintext => None,
+            _ => Some(stored),
+        };
+    };
+
+    let sealed = STANDARD.decode(encoded).ok()?;
+    if sealed.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+
+    let payload = Payload {
+        msg: ciphertext,
+        aad: key.as_bytes(),
+    };
+    let json = encryption
+        .as_ref()?
+        .cipher
+        .decrypt(Nonce::from_slice(nonce), payload)
+        .ok()?;
+    String::from_utf8(json).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::tests::MemoryBackend;
+    use crate::persistence::{load_persisted_from, save_persisted_to};
+    use std::sync::{Mutex, MutexGuard};
+
+    // The key is global, so tests that set it must not overlap
+    static KEY_LOCK: Mutex<()> = Mutex::new(());
+
+    fn with_key(key: Option<[u8; 32]>) -> MutexGuard<'static, ()> {
+        let guard = KEY_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match key {
+            Some(key) => set_persist_key(key),
+            None => clear_persist_key(),
+        }
+        guard
+    }
+
+    #[test]
+    fn values_round_trip_encrypted() {
+        let _key = with_key(Some([7; 32]));
+        let backend = MemoryBackend::default();
+        save_persisted_to(&backend, "token", &"secret".to_string()).unwrap();
+
+        let stored = backend.raw("token").unwrap();
+        assert!(stored.starts_with(PREFIX));
+        assert!(!stored.contains("secret"));
+        assert_eq!(
+            load_persisted_from::<String>(&backend, "token"),
+            Some("secret".to_string())
+        );
+    }
+
+    #[test]
+    fn values_moved_to_another_storage_key_are_rejected() {
+        let _key = with_key(Some([7; 32]));
+        let backend = MemoryBackend::default();
+        save_persisted_to(&backend, "guest", &false).unwrap();
+        save_persisted_to(&backend, "admin", &true).unwrap();
+
+        backend.put("guest", backend.raw("admin").unwrap());
+        assert_eq!(load_persisted_from::<bool>(&backend, "guest"), None);
+        assert_eq!(load_persisted_from::<bool>(&backend, "admin"), Some(true));
+    }
+
+    #[test]
+    fn values_sealed_under_another_key_are_rejected() {
+        let _key = with_key(Some([7; 32]));
+        let backend = MemoryBackend::default();
+        save_persisted_to(&backend, "token", &"secret".to_string()).unwrap();
+
+        set_persist_key([8; 32]);
+        assert_eq!(load_persisted_from::<String>(&backend, "token"), None);
+        clear_persist_key();
+        assert_eq!(load_persisted_from::<String>(&backend, "token"), None);
+    }
+
+    #[test]
+    fn plaintext_is_only_read_while_migrating() {
+        let _key = with_key(None);
+        let backend = MemoryBackend::default();
+        save_persisted_to(&backend, "count", &3u32).unwrap();
+        assert_eq!(backend.raw("count").as_deref(), Some("3"));
+
+        set_persist_key([7; 32]);
+        assert_eq!(load_persisted_from::<u32>(&backend, "count"), None);
+
+        set_persist_key_migrating([7; 32]);
+        assert_eq!(load_persisted_from::<u32>(&backend, "count"), Some(3));
+        save_persisted_to(&backend, "count", &4u32).unwrap();
+        assert!(backend.raw("count").unwrap().starts_with(PREFIX));
+
+        set_persist_key([7; 32]);
+        assert_eq!(load_persisted_from::<u32>(&backend, "count"), Some(4));
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{Observable, ObservableValue};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+/// Normalized collection of entities keyed by id.
+///
+/// Every entity lives in its own observable, so a component rendering one
+/// entity only re-renders when that entity changes. The id list is a separate
+/// observable that changes only when entities are added, removed or reordered.
+pub struct EntityStore<Id, T>
+where
+    Id: Eq + Hash + Clone + 'static,
+    T: Clone + 'static,
+{
+    ids: ObservableValue<Vec<Id>>,
+    entities: Arc<Mutex<HashMap<Id, ObservableValue<T>>>>,
+}
+
+impl<Id, T> Clone for EntityStore<Id, T>
+where
+    Id: Eq + Hash + Clone + 'static,
+    T: Clone + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            ids: self.ids.clone(),
+            entities: self.entities.clone(),
+        }
+    }
+}
+
+impl<Id, T> EntityStore<Id, T>
+where
+    Id: Eq + Hash + Clone + 'static,
+    T: Clone + 'static,
+{
+    pub fn new() -> Self {
+        Self {
+            ids: ObservableValue::new(Vec::new()),
+            entities: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn with_entities(entities: impl IntoIterator<Item = (Id, T)>) -> Self {
+        let store = Self::new();
+        store.upsert_many(entities);
+        store
+    }
+
+    /// Inserts a new entity or updates the observable of an existing one.
+    pub fn upsert(&self, id: Id, value: T) {
+        if let Some(id) = self.insert_or_assign(id, value) {
+            self.ids.set(|ids| ids.push(id));
+        }
+    }
+
+    /// Upserts many entities, notifying the id list at most once.
+    pub fn upsert_many(&self, entities: impl IntoIterator<Item = (Id, T)>) {
+        let added: Vec<Id> = entities
+            .into_iter()
+            .filter_map(|(id, value)| self.insert_or_assign(id, value))
+            .collect();
+
+        if !added.is_empty() {
+            self.ids.set(|ids| ids.extend(added));
+        }
+    }
+
+    /// Inserts `value` under `id` if it is new, returning the id to add to the
+    /// list, or assigns it to the existing entity. The lookup and the insert
+    /// happen under one lock; the assignment runs after it is released.
+    fn insert_or_assign(&self, id: Id, value: T) -> Option<Id> {
+        let existing = match self.entities.lock().unwrap().entry(id) {
+            Entry::Occupied(entry) => entry.get().clone(),
+            Entry::Vacant(entry) => {
+                let id = entry.key().clone();
+                entry.insert(ObservableValue::new(value));
+                return Some(id);
+            }
+        };
+        existing.assign(value);
+        None
+    }
+
+    pub fn update<F>(&self, id: &Id, updater: F) -> bool
+    where
+        F: FnOnce(&mut T),
+    {
+        match self.entity(id) {
+            Some(entity) => {
+                entity.set(updater);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&self, id: &Id) -> Option<T> {
+        let removed = self.entities.lock().unwrap().remove(id)?;
+        self.ids.set(|ids| ids.retain(|candidate| candidate != id));
+        Some(removed.peek())
+    }
+
+    pub fn clear(&self) {
+        self.entities.lock().unwrap().clear();
+        self.ids.assign(Vec::new());
+    }
+
+    /// Per-entity observable; tracks membership of `id` in the store.
+    pub fn get(&self, id: &Id) -> Option<ObservableValue<T>> {
+        self.ids.map(|_| ());
+        self.entity(id)
+    }
+
+    pub fn get_value(&self, id: &Id) -> Option<T> {
+        self.get(id).map(|entity| entity.get())
+    }
+
+    pub fn contains(&self, id: &Id) -> bool {
+        self.ids.when(|ids| ids.contains(id))
+    }
+
+    /// Ids in insertion (or last sorted) order.
+    pub fn ids(&self) -> Vec<Id> {
+        self.ids.get()
+    }
+
+    pub fn ids_observable(&self) -> &ObservableValue<Vec<Id>> {
+        &self.ids
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.map(|ids| ids.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.when(|ids| ids.is_empty())
+    }
+
+    /// Every entity in id order.
+    pub fn all(&self) -> Vec<T> {
+        self.ids()
+            .iter()
+            .filter_map(|id| self.entity(id))
+            .map(|entity| entity.get())
+            .collect()
+    }
+
+    /// Entities matching `predicate`, in id order.
+    pub fn select<F>(&self, predicate: F) -> Vec<T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.all()
+            .into_iter()
+            .filter(|value| predicate(value))
+            .collect()
+    }
+
+    /// Projects every entity through `selector`, in id order.
+    pub fn select_map<U, F>(&self, selector: F) -> Vec<U>
+    where
+        F: Fn(&T) -> U,
+    {
+        self.ids()
+            .iter()
+            .filter_map(|id| self.entity(id))
+            .map(|entity| entity.map(&selector))
+            .collect()
+    }
+
+    pub fn find<F>(&self, predicate: F) -> Option<T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.all().into_iter().find(|value| predicate(value))
+    }
+
+    /// Reorders the ids by comparing their entities.
+    pub fn sort_by<F>(&self, compare: F)
+    where
+        F: Fn(&T, &T) -> std::cmp::Ordering,
+    {
+        let entities = self.entities.lock().unwrap().clone();
+        self.ids.set(|ids| {
+            ids.sort_by(|a, b| match (entities.get(a), entities.get(b)) {
+                (Some(a), Some(b)) => compare(&a.peek(), &b.peek()),
+                _ => std::cmp::Ordering::Equal,
+            })
+        });
+    }
+
+    fn entity(&self, id: &Id) -> Option<ObservableValue<T>> {
+        self.entities.lock().unwrap().get(id).cloned()
+    }
+}
+
+impl<Id, T> Default for EntityStore<Id, T>
+where
+    Id: Eq + Hash + Clone + 'static,
+    T: Clone + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
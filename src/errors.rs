@@ -0,0 +1,148 @@
+use crate::{Observable, ObservableValue, ReadOnlyObservable};
+use std::any::Any;
+use std::fmt::{self, Display};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::SystemTime;
+
+/// Where a reported error came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorSource {
+    /// An async store action that returned `Err`.
+    Action {
+        store: &'static str,
+        action: &'static str,
+    },
+    /// A resource created with [`report_errors`](crate::ObservableResource::report_errors).
+    Resource,
+    /// Passed to [`report_error`].
+    Reported,
+}
+
+/// An error collected in [`errors`].
+#[derive(Clone)]
+pub struct ErrorEntry {
+    pub id: u64,
+    pub source: ErrorSource,
+    pub message: String,
+    pub reported_at: SystemTime,
+    error: Option<Arc<dyn Any + Send + Sync>>,
+}
+
+impl ErrorEntry {
+    /// The original error, if its type was `Clone` and is `E`.
+    pub fn downcast_ref<E: 'static>(&self) -> Option<&E> {
+        self.error.as_ref()?.downcast_ref::<E>()
+    }
+}
+
+impl PartialEq for ErrorEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl fmt::Debug for ErrorEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErrorEntry")
+            .field("id", &self.id)
+            .field("source", &self.source)
+            .field("message", &self.message)
+            .finish_non_exhaustive()
+    }
+}
+
+static ERRORS: LazyLock<ObservableValue<Vec<ErrorEntry>>> =
+    LazyLock::new(|| ObservableValue::new(Vec::new()));
+static NEXT_ERROR_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Errors of async actions and resources not dismissed yet, oldest first.
+pub fn errors() -> ReadOnlyObservable<Vec<ErrorEntry>> {
+    ERRORS.read_only()
+}
+
+/// Adds `error` to [`errors`], keeping it for [`ErrorEntry::downcast_ref`].
+pub fn report_error<E>(source: ErrorSource, error: E) -> u64
+where
+    E: Display + Send + Sync + 'static,
+{
+    let message = error.to_string();
+    push_error(source, message, Some(Arc::new(error)))
+}
+
+pub(crate) fn push_error(
+    source: ErrorSource,
+    message: String,
+    error: Option<Arc<dyn Any + Send + Sync>>,
+) -> u64 {
+    let id = NEXT_ERROR_ID.fetch_add(1, Ordering::Relaxed);
+    ERRORS.set(|errors| {
+        errors.push(ErrorEntry {
+            id,
+            source,
+            message,
+            reported_at: crate::time::now(),
+            error,
+        })
+    });
+    id
+}
+
+pub fn dismiss_error(id: u64) {
+    if ERRORS.peek().iter().any(|entry| entry.id == id) {
+        ERRORS.set(|errors| errors.retain(|entry| entry.id != id));
+    }
+}
+
+pub fn clear_errors() {
+    if !ERRORS.peek().is_empty() {
+        ERRORS.assign(Vec::new());
+    }
+}
+
+/// Reports the results of async actions of the store macros: `Err` values
+/// with a `Clone` error keep it, other `Display` errors keep their message,
+/// and other results are ignored.
+#[doc(hidden)]
+pub mod __report {
+    use super::*;
+
+    pub struct ActionResult<'a, R>(pub &'a R);
+
+    pub trait ReportTyped {
+        fn report(&self, store: &'static str, action: &'static str);
+    }
+
+    impl<T, E> ReportTyped for &ActionResult<'_, Result<T, E>>
+    where
+        E: Clone + Display + Send + Sync + 'static,
+    {
+        fn report(&self, store: &'static str, action: &'static str) {
+            if let Err(error) = self.0 {
+                report_error(ErrorSource::Action { store, action }, error.clone());
+            }
+        }
+    }
+
+    pub trait ReportMessage {
+        fn report(&self, store: &'static str, action: &'static str);
+    }
+
+    impl<T, E: Display> ReportMessage for &&ActionResult<'_, Result<T, E>> {
+        fn report(&self, store: &'static str, action: &'static str) {
+            if let Err(error) = self.0 {
+                push_error(
+                    ErrorSource::Action { store, action },
+                    error.to_string(),
+                    None,
+                );
+            }
+        }
+    }
+
+    pub trait ReportNothing {
+        fn report(&self, _store: &'static str, _action: &'static str) {}
+    }
+
+    impl<R> ReportNothing for ActionResult<'_, R> {}
+}
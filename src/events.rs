@@ -0,0 +1,237 @@
+use crate::observable::IdMap;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::thread::{self, ThreadId};
+
+type Handler<E> = Arc<dyn Fn(&E) + Send + Sync>;
+type LocalHandler<E> = Rc<dyn Fn(&E)>;
+type Handlers<E> = IdMap<HandlerEntry<E>>;
+
+thread_local! {
+    // Handlers subscribed with `subscribe_local` on this thread, by key
+    static LOCAL_HANDLERS: RefCell<HashMap<u64, Rc<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+static NEXT_LOCAL_HANDLER: AtomicU64 = AtomicU64::new(0);
+
+enum HandlerEntry<E> {
+    Sync(Handler<E>),
+    /// A handler that is not `Send`, kept in [`LOCAL_HANDLERS`] of its thread.
+    Local {
+        key: u64,
+        thread: ThreadId,
+    },
+}
+
+impl<E> Clone for HandlerEntry<E> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Sync(handler) => Self::Sync(handler.clone()),
+            Self::Local { key, thread } => Self::Local {
+                key: *key,
+                thread: *thread,
+            },
+        }
+    }
+}
+
+impl<E: 'static> HandlerEntry<E> {
+    fn call(&self, event: &E, current: ThreadId) {
+        match self {
+            Self::Sync(handler) => handler(event),
+            Self::Local { key, thread } if *thread == current => {
+                let handler = LOCAL_HANDLERS
+                    .try_with(|handlers| handlers.borrow().get(key).cloned())
+                    .ok()
+                    .flatten()
+                    .and_then(|handler| handler.downcast::<LocalHandler<E>>().ok());
+                if let Some(handler) = handler {
+                    handler(event);
+                }
+            }
+            Self::Local { .. } => {}
+        }
+    }
+
+    /// Drops a local handler; only possible on the thread that subscribed it.
+    fn release(self) {
+        if let Self::Local { key, thread } = self {
+            if thread == thread::current().id() {
+                let _ = LOCAL_HANDLERS.try_with(|handlers| handlers.borrow_mut().remove(&key));
+            }
+        }
+    }
+}
+
+/// Typed events for interactions that are not state, such as toasts or analytics:
+///
+/// ```ignore
+/// struct ItemAdded { id: u32 }
+///
+/// let _toasts = on(|event: &ItemAdded| show_toast(event.id));
+/// emit(ItemAdded { id: 7 });
+/// ```
+///
+/// Handlers run synchronously in subscription order, for every event: unlike
+/// observable changes, events are never coalesced by [`batch`](crate::batch).
+/// Clones share their handlers.
+///
+/// Handlers that are not `Send`, e.g. capturing a component's signals, are
+/// subscribed with [`on_local`](Self::on_local) and only receive events emitted
+/// on their own thread.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    // TypeId of the event -> Handlers<E>
+    handlers: Arc<Mutex<HashMap<TypeId, Box<dyn Any + Send + Sync>>>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls every handler of `E` with `event`.
+    pub fn emit<E: 'static>(&self, event: E) {
+        // Called without the lock, so handlers may emit or subscribe
+        let handlers: Vec<HandlerEntry<E>> = self
+            .handlers
+            .lock()
+            .unwrap()
+            .get(&TypeId::of::<E>())
+            .and_then(|handlers| handlers.downcast_ref::<Handlers<E>>())
+            .map(|handlers| {
+                handlers
+                    .iter()
+                    .map(|(_, handler)| handler.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let current = thread::current().id();
+        for handler in handlers {
+            handler.call(&event, current);
+        }
+    }
+
+    /// Calls `handler` with every `E` emitted until the returned subscription is dropped.
+    pub fn on<E: 'static>(
+        &self,
+        handler: impl Fn(&E) + Send + Sync + 'static,
+    ) -> EventSubscription {
+        let id = self.subscribe(handler);
+        let bus = self.clone();
+        EventSubscription {
+            unsubscribe: Some(Box::new(move || bus.unsubscribe::<E>(id))),
+        }
+    }
+
+    /// Like [`on`](Self::on) for a handler that is not `Send`: it is called for
+    /// the `E` emitted on the current thread only.
+    ///
+    /// Drop the subscription on the same thread, or the handler is only
+    /// released when the thread exits.
+    pub fn on_local<E: 'static>(&self, handler: impl Fn(&E) + 'static) -> EventSubscription {
+        let id = self.subscribe_local(handler);
+        let bus = self.clone();
+        EventSubscription {
+            unsubscribe: Some(Box::new(move || bus.unsubscribe::<E>(id))),
+        }
+    }
+
+    /// Like [`on`](Self::on), until [`unsubscribe`](Self::unsubscribe) is called with the returned id.
+    pub fn subscribe<E: 'static>(&self, handler: impl Fn(&E) + Send + Sync + 'static) -> usize {
+        self.insert(HandlerEntry::Sync(Arc::new(handler)))
+    }
+
+    /// Like [`on_local`](Self::on_local), until [`unsubscribe`](Self::unsubscribe) is called with the returned id.
+    pub fn subscribe_local<E: 'static>(&self, handler: impl Fn(&E) + 'static) -> usize {
+        let key = NEXT_LOCAL_HANDLER.fetch_add(1, Ordering::Relaxed);
+        let handler: LocalHandler<E> = Rc::new(handler);
+        LOCAL_HANDLERS.with(|handlers| handlers.borrow_mut().insert(key, Rc::new(handler)));
+        self.insert(HandlerEntry::<E>::Local {
+            key,
+            thread: thread::current().id(),
+        })
+    }
+
+    fn insert<E: 'static>(&self, handler: HandlerEntry<E>) -> usize {
+        self.handlers
+            .lock()
+            .unwrap()
+            .entry(TypeId::of::<E>())
+            .or_insert_with(|| Box::new(Handlers::<E>::default()))
+            .downcast_mut::<Handlers<E>>()
+            .expect("handlers are stored under the TypeId of their event")
+            .insert(handler)
+    }
+
+    pub fn unsubscribe<E: 'static>(&self, id: usize) {
+        let removed = self
+            .handlers
+            .lock()
+            .unwrap()
+            .get_mut(&TypeId::of::<E>())
+            .and_then(|handlers| handlers.downcast_mut::<Handlers<E>>())
+            .and_then(|handlers| handlers.remove(id));
+        if let Some(handler) = removed {
+            handler.release();
+        }
+    }
+
+    /// The number of handlers subscribed to `E`.
+    pub fn handler_count<E: 'static>(&self) -> usize {
+        self.handlers
+            .lock()
+            .unwrap()
+            .get(&TypeId::of::<E>())
+            .and_then(|handlers| handlers.downcast_ref::<Handlers<E>>())
+            .map_or(0, IdMap::len)
+    }
+}
+
+/// Unsubscribes its handler when dropped; returned by [`EventBus::on`].
+#[must_use = "the handler is unsubscribed as soon as the subscription is dropped"]
+pub struct EventSubscription {
+    unsubscribe: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+impl EventSubscription {
+    /// Keeps the handler subscribed for the rest of the program.
+    pub fn detach(mut self) {
+        self.unsubscribe = None;
+    }
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        if let Some(unsubscribe) = self.unsubscribe.take() {
+            unsubscribe();
+        }
+    }
+}
+
+static EVENT_BUS: LazyLock<EventBus> = LazyLock::new(EventBus::new);
+
+/// The process-wide bus used by [`emit`] and [`on`].
+pub fn event_bus() -> EventBus {
+    EVENT_BUS.clone()
+}
+
+/// Emits `event` on the global [`EventBus`].
+pub fn emit<E: 'static>(event: E) {
+    EVENT_BUS.emit(event);
+}
+
+/// Subscribes `handler` to `E` on the global [`EventBus`].
+pub fn on<E: 'static>(handler: impl Fn(&E) + Send + Sync + 'static) -> EventSubscription {
+    EVENT_BUS.on(handler)
+}
+
+/// Subscribes `handler` to the `E` emitted on the current thread, on the global [`EventBus`].
+pub fn on_local<E: 'static>(handler: impl Fn(&E) + 'static) -> EventSubscription {
+    EVENT_BUS.on_local(handler)
+}
@@ -0,0 +1,209 @@
+use crate::{
+    add_reducer, remove_reducer, spawn_effect, Autorun, CancelToken, Observable, ObservableValue,
+};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlowState {
+    Running,
+    Paused,
+    Completed,
+    Cancelled,
+}
+
+/// A running multi-step workflow, started with [`flow!`](crate::flow!) or [`start_flow`].
+///
+/// Its steps await actions, conditions and futures. Each step returns `None`
+/// once the flow is cancelled, so the body stops at the next `?`. While the
+/// flow is paused, steps don't complete; they continue where they were on
+/// [`resume`](Self::resume). Clones control the same flow.
+#[derive(Clone)]
+pub struct Flow {
+    cancel: CancelToken,
+    paused: ObservableValue<bool>,
+    state: ObservableValue<FlowState>,
+}
+
+impl Flow {
+    /// Waits for the next dispatched action of type `A`.
+    pub async fn take<A: Clone + Send + 'static>(&self) -> Option<A> {
+        self.step(TakeAction::new()).await
+    }
+
+    /// Waits until `condition`, which may read observables, is true.
+    pub async fn until(&self, condition: impl Fn() -> bool + 'static) -> Option<()> {
+        self.step(when(condition)).await
+    }
+
+    /// Runs `future` as a step, e.g. a request.
+    pub async fn run<F: Future>(&self, future: F) -> Option<F::Output> {
+        self.step(future).await
+    }
+
+    async fn step<F: Future>(&self, future: F) -> Option<F::Output> {
+        self.cancel
+            .run(async {
+                self.resumed().await;
+                let output = future.await;
+                self.resumed().await;
+                output
+            })
+            .await
+    }
+
+    async fn resumed(&self) {
+        let paused = self.paused.clone();
+        when(move || !paused.get()).await;
+    }
+
+    pub fn pause(&self) {
+        if self.state.peek() == FlowState::Running {
+            self.paused.assign(true);
+            self.state.assign(FlowState::Paused);
+        }
+    }
+
+    pub fn resume(&self) {
+        if self.state.peek() == FlowState::Paused {
+            self.paused.assign(false);
+            self.state.assign(FlowState::Running);
+        }
+    }
+
+    /// Stops the flow at its current step.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// The state of the flow, observable from components.
+    pub fn state(&self) -> &ObservableValue<FlowState> {
+        &self.state
+    }
+
+    /// The token cancelled with the flow, for work started by its steps.
+    pub fn token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+}
+
+/// Starts `body` in the background with the spawner of effects:
+///
+/// ```ignore
+/// let checkout = start_flow(|flow| async move {
+///     let Address(address) = flow.take::<Address>().await?;
+///     let quote = flow.run(api::shipping_quote(&address)).await?;
+///     use_store::<CheckoutStore>().quote.assign(Some(quote));
+///     flow.take::<ConfirmOrder>().await?;
+///     flow.run(api::place_order()).await?;
+///     Some(())
+/// });
+/// ```
+pub fn start_flow<F, Fut>(body: F) -> Flow
+where
+    F: FnOnce(Flow) -> Fut,
+    Fut: Future<Output = Option<()>> + 'static,
+{
+    let flow = Flow {
+        cancel: CancelToken::new(),
+        paused: ObservableValue::new(false),
+        state: ObservableValue::new(FlowState::Running),
+    };
+
+    let future = body(flow.clone());
+    spawn_effect({
+        let flow = flow.clone();
+        async move {
+            flow.cancel.run(future).await;
+            let state = if flow.is_cancelled() {
+                FlowState::Cancelled
+            } else {
+                FlowState::Completed
+            };
+            flow.state.assign(state);
+        }
+    });
+    flow
+}
+
+/// Completes once `condition` is true, re-checking it whenever an observable it read changes.
+async fn when(condition: impl Fn() -> bool + 'static) {
+    let state = Rc::new(RefCell::new((false, None::<Waker>)));
+    let _autorun = Autorun::new({
+        let state = state.clone();
+        move || {
+            if condition() {
+                let mut state = state.borrow_mut();
+                state.0 = true;
+                if let Some(waker) = state.1.take() {
+                    waker.wake();
+                }
+            }
+        }
+    });
+
+    std::future::poll_fn(|cx| {
+        let mut state = state.borrow_mut();
+        if state.0 {
+            Poll::Ready(())
+        } else {
+            state.1 = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+/// Future of [`Flow::take`]; listens from creation until dropped.
+struct TakeAction<A: 'static> {
+    slot: Arc<Mutex<(Option<A>, Option<Waker>)>>,
+    reducer: usize,
+}
+
+impl<A: Clone + Send + 'static> TakeAction<A> {
+    fn new() -> Self {
+        let slot = Arc::new(Mutex::new((None::<A>, None::<Waker>)));
+        let reducer = add_reducer({
+            let slot = slot.clone();
+            move |action: &A| {
+                let mut slot = slot.lock().unwrap();
+                if slot.0.is_none() {
+                    slot.0 = Some(action.clone());
+                    if let Some(waker) = slot.1.take() {
+                        waker.wake();
+                    }
+                }
+            }
+        });
+        Self { slot, reducer }
+    }
+}
+
+impl<A: 'static> Future for TakeAction<A> {
+    type Output = A;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<A> {
+        let mut slot = self.slot.lock().unwrap();
+        match slot.0.take() {
+            Some(action) => Poll::Ready(action),
+            None => {
+                slot.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<A: 'static> Drop for TakeAction<A> {
+    fn drop(&mut self) {
+        remove_reducer::<A>(self.reducer);
+    }
+}
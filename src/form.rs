@@ -0,0 +1,292 @@
+use crate::{
+    Computed, Observable, ObservableBool, ObservableString, ObservableValue, ReadOnlyObservable,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+pub type FieldValidator = Rc<dyn Fn(&str) -> Result<(), String>>;
+
+#[derive(Clone)]
+struct FormField {
+    name: String,
+    value: ObservableString,
+    initial: String,
+    validators: Vec<FieldValidator>,
+}
+
+impl FormField {
+    fn error(&self) -> Option<String> {
+        let value = self.value.get();
+        self.validators
+            .iter()
+            .find_map(|validator| validator(&value).err())
+    }
+}
+
+/// A field name that was never added to the form.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownField(pub String);
+
+impl fmt::Display for UnknownField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the form has no field named `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownField {}
+
+#[derive(Default)]
+pub struct FormStoreBuilder {
+    fields: Vec<FormField>,
+}
+
+impl FormStoreBuilder {
+    pub fn field(mut self, name: impl Into<String>, initial: impl Into<String>) -> Self {
+        let initial = initial.into();
+        self.fields.push(FormField {
+            name: name.into(),
+            value: ObservableValue::new(initial.clone()),
+            initial,
+            validators: Vec::new(),
+        });
+        self
+    }
+
+    /// Attaches a validator to a previously registered field.
+    pub fn validator<F>(mut self, name: &str, validator: F) -> Result<Self, UnknownField>
+    where
+        F: Fn(&str) -> Result<(), String> + 'static,
+    {
+        let Some(field) = self.fields.iter_mut().find(|field| field.name == name) else {
+            return Err(UnknownField(name.to_string()));
+        };
+        field.validators.push(Rc::new(validator));
+        Ok(self)
+    }
+
+    pub fn build(self) -> FormStore {
+        let fields = Rc::new(self.fields);
+        let errors = Computed::new({
+            let fields = fields.clone();
+            move || {
+                fields
+                    .iter()
+                    .filter_map(|field| field.error().map(|error| (field.name.clone(), error)))
+                    .collect::<HashMap<_, _>>()
+            }
+        });
+        let valid = Computed::new({
+            let errors = errors.clone();
+            move || errors.map(HashMap::is_empty)
+        });
+        let dirty = Computed::new({
+            let fields = fields.clone();
+            move || {
+                fields
+                    .iter()
+                    .any(|field| field.value.when(|value| *value != field.initial))
+            }
+        });
+
+        FormStore {
+            fields,
+            errors,
+            valid,
+            dirty,
+            submitting: ObservableValue::new(false),
+            submit_count: ObservableValue::new(0),
+        }
+    }
+}
+
+/// A store of string fields with validation and dirty tracking.
+///
+/// `errors()`, `dirty()` and `valid()` are computed values derived from the
+/// field observables, so reactive components reading them re-render as fields change.
+#[derive(Clone)]
+pub struct FormStore {
+    fields: Rc<Vec<FormField>>,
+    errors: Computed<HashMap<String, String>>,
+    valid: Computed<bool>,
+    dirty: Computed<bool>,
+    submitting: ObservableBool,
+    submit_count: ObservableValue<u32>,
+}
+
+impl FormStore {
+    pub fn builder() -> FormStoreBuilder {
+        FormStoreBuilder::default()
+    }
+
+    pub fn field(&self, name: &str) -> Option<ObservableString> {
+        self.find(name).map(|field| field.value.clone())
+    }
+
+    pub fn value(&self, name: &str) -> String {
+        self.find(name)
+            .map(|field| field.value.get())
+            .unwrap_or_default()
+    }
+
+    pub fn set(&self, name: &str, value: impl Into<String>) {
+        if let Some(field) = self.find(name) {
+            field.value.assign(value.into());
+        }
+    }
+
+    pub fn values(&self) -> HashMap<String, String> {
+        self.fields
+            .iter()
+            .map(|field| (field.name.clone(), field.value.get()))
+            .collect()
+    }
+
+    /// First validation error of every invalid field.
+    pub fn errors(&self) -> &Computed<HashMap<String, String>> {
+        &self.errors
+    }
+
+    pub fn field_error(&self, name: &str) -> Option<String> {
+        self.find(name).and_then(FormField::error)
+    }
+
+    pub fn valid(&self) -> &Computed<bool> {
+        &self.valid
+    }
+
+    pub fn dirty(&self) -> &Computed<bool> {
+        &self.dirty
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.valid.get()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    pub fn is_field_dirty(&self, name: &str) -> bool {
+        self.find(name)
+            .map(|field| field.value.when(|value| *value != field.initial))
+            .unwrap_or(false)
+    }
+
+    pub fn submitting(&self) -> ReadOnlyObservable<bool> {
+        self.submitting.read_only()
+    }
+
+    pub fn is_submitting(&self) -> bool {
+        self.submitting.get()
+    }
+
+    pub fn submit_count(&self) -> u32 {
+        self.submit_count.get()
+    }
+
+    /// Restores every field to its initial value.
+    pub fn reset(&self) {
+        for field in self.fields.iter() {
+            field.value.assign(field.initial.clone());
+        }
+        self.submit_count.assign(0);
+    }
+
+    /// Calls `handler` with the field values if the form is valid,
+    /// otherwise returns the current errors.
+    pub fn submit<R, F>(&self, handler: F) -> Result<R, HashMap<String, String>>
+    where
+        F: FnOnce(HashMap<String, String>) -> R,
+    {
+        self.submit_count.set(|count| *count += 1);
+
+        let errors = self.errors.get();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let _submitting = Submitting::start(&self.submitting);
+        Ok(handler(self.values()))
+    }
+
+    /// Async version of [`FormStore::submit`]; `is_submitting()` is true while it runs.
+    pub async fn submit_async<R, F, Fut>(&self, handler: F) -> Result<R, HashMap<String, String>>
+    where
+        F: FnOnce(HashMap<String, String>) -> Fut,
+        Fut: std::future::Future<Output = R>,
+    {
+        self.submit_count.set(|count| *count += 1);
+
+        let errors = self.errors.get();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let _submitting = Submitting::start(&self.submitting);
+        Ok(handler(self.values()).await)
+    }
+
+    fn find(&self, name: &str) -> Option<&FormField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+}
+
+/// Marks a form as submitting until dropped, even if the handler panics or
+/// its future is dropped.
+struct Submitting<'a>(&'a ObservableBool);
+
+impl<'a> Submitting<'a> {
+    fn start(submitting: &'a ObservableBool) -> Self {
+        submitting.assign(true);
+        Self(submitting)
+    }
+}
+
+impl Drop for Submitting<'_> {
+    fn drop(&mut self) {
+        self.0.assign(false);
+    }
+}
+
+/// Common validators for [`FormStoreBuilder::validator`].
+pub mod validators {
+    pub fn required(message: impl Into<String>) -> impl Fn(&str) -> Result<(), String> {
+        let message = message.into();
+        move |value| {
+            if value.trim().is_empty() {
+                Err(message.clone())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    pub fn min_length(
+        min: usize,
+        message: impl Into<String>,
+    ) -> impl Fn(&str) -> Result<(), String> {
+        let message = message.into();
+        move |value| {
+            if value.chars().count() < min {
+                Err(message.clone())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    pub fn max_length(
+        max: usize,
+        message: impl Into<String>,
+    ) -> impl Fn(&str) -> Result<(), String> {
+        let message = message.into();
+        move |value| {
+            if value.chars().count() > max {
+                Err(message.clone())
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
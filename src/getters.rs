@@ -0,0 +1,80 @@
+use crate::{observable, Observable, ObservableValue, SyncObserverContext};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+struct CachedGetter<T> {
+    value: Mutex<Option<T>>,
+    // Bumped on every invalidation, so a result computed meanwhile isn't cached
+    generation: AtomicU64,
+    version: ObservableValue<u64>,
+    observer: SyncObserverContext,
+}
+
+impl<T> CachedGetter<T> {
+    fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.value.lock().unwrap().take();
+        self.version.set(|version| *version += 1);
+    }
+}
+
+type Getters = HashMap<(&'static str, TypeId), Arc<dyn Any + Send + Sync>>;
+
+/// Results of the `#[cached]` getters of a store, shared by its clones.
+///
+/// Results are kept per getter name and result type.
+#[derive(Clone, Default)]
+pub struct GetterCache {
+    getters: Arc<Mutex<Getters>>,
+}
+
+impl GetterCache {
+    /// The cached result of getter `name`, computed again by `compute` once an observable it read changed.
+    ///
+    /// Reading it subscribes the current observer like reading an observable.
+    pub fn get<T, F>(&self, name: &'static str, compute: F) -> T
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> T,
+    {
+        let getter = self.getter::<T>(name);
+        getter.version.map(|_| ());
+
+        if let Some(value) = getter.value.lock().unwrap().clone() {
+            return value;
+        }
+
+        let generation = getter.generation.load(Ordering::SeqCst);
+        let value = getter.observer.run(compute);
+        if getter.generation.load(Ordering::SeqCst) == generation {
+            *getter.value.lock().unwrap() = Some(value.clone());
+        }
+        value
+    }
+
+    fn getter<T: Send + Sync + 'static>(&self, name: &'static str) -> Arc<CachedGetter<T>> {
+        let mut getters = self.getters.lock().unwrap();
+        let getter = getters.entry((name, TypeId::of::<T>())).or_insert_with(|| {
+            Arc::new_cyclic(|getter: &Weak<CachedGetter<T>>| {
+                let getter = getter.clone();
+                CachedGetter {
+                    value: Mutex::new(None),
+                    generation: AtomicU64::new(0),
+                    version: observable(0),
+                    observer: SyncObserverContext::new(move || {
+                        if let Some(getter) = getter.upgrade() {
+                            getter.invalidate();
+                        }
+                    }),
+                }
+            })
+        });
+
+        getter
+            .clone()
+            .downcast()
+            .unwrap_or_else(|_| unreachable!("getters are keyed by their result type"))
+    }
+}
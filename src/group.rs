@@ -0,0 +1,104 @@
+use crate::lifecycle::notify_lifecycle;
+use crate::{get_store, register_store, remove_store, LifecycleEvent, Resettable, Store};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+/// A store type tagged with a group by [`register_in_group`].
+#[derive(Clone, Copy)]
+struct GroupMember {
+    type_id: TypeId,
+    reset: fn(),
+    remove: fn(),
+}
+
+static GROUPS: LazyLock<Mutex<HashMap<String, Vec<GroupMember>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn reset_member<S: Store + Resettable + Send + Sync>() {
+    if let Some(store) = get_store::<S>() {
+        notify_lifecycle(TypeId::of::<S>(), &store, LifecycleEvent::Reset);
+        store.reset_state();
+    }
+}
+
+fn clear_member<S: Store + Send + Sync>() {
+    if let Some(store) = get_store::<S>() {
+        notify_lifecycle(TypeId::of::<S>(), &store, LifecycleEvent::Clear);
+    }
+    remove_store::<S>();
+}
+
+fn members(group: &str) -> Vec<GroupMember> {
+    GROUPS
+        .lock()
+        .unwrap()
+        .get(group)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Registers `store` and tags its type with `group`, for bulk operations such as
+/// wiping every session store on logout:
+///
+/// ```ignore
+/// register_in_group("session", CartStore::default());
+/// register_in_group("session", ProfileStore::default());
+/// // on logout
+/// clear_group("session");
+/// ```
+pub fn register_in_group<S: Store + Resettable + Send + Sync>(group: &str, store: S) {
+    register_store(store);
+
+    let mut groups = GROUPS.lock().unwrap();
+    let members = groups.entry(group.to_string()).or_default();
+    if !members
+        .iter()
+        .any(|member| member.type_id == TypeId::of::<S>())
+    {
+        members.push(GroupMember {
+            type_id: TypeId::of::<S>(),
+            reset: reset_member::<S>,
+            remove: clear_member::<S>,
+        });
+    }
+}
+
+/// Puts every store of `group` back to its initial values in place.
+pub fn reset_group(group: &str) {
+    crate::batch(|| {
+        for member in members(group) {
+            (member.reset)();
+        }
+    });
+}
+
+/// Removes every store of `group`, calling their
+/// [`on_clear`](crate::StoreLifecycle::on_clear) hooks first, and forgets the group.
+pub fn clear_group(group: &str) {
+    let members = GROUPS.lock().unwrap().remove(group).unwrap_or_default();
+    for member in members {
+        (member.remove)();
+    }
+}
+
+/// The names of every group, in no particular order.
+pub fn groups() -> Vec<String> {
+    GROUPS.lock().unwrap().keys().cloned().collect()
+}
+
+/// The state of the stores of `group`, in the format of
+/// [`serialize_stores`](crate::serialize_stores), so it can be restored with
+/// [`hydrate_stores`](crate::hydrate_stores).
+///
+/// Only stores registered with [`register_hydratable`](crate::register_hydratable) are included.
+#[cfg(feature = "serde")]
+pub fn snapshot_group(group: &str) -> Result<String, serde_json::Error> {
+    let mut state = serde_json::Map::new();
+    for member in members(group) {
+        if let Some((key, value)) = crate::hydration::serialize_store(member.type_id) {
+            state.insert(key.to_string(), value?);
+        }
+    }
+    serde_json::to_string(&serde_json::Value::Object(state))
+}
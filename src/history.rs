@@ -0,0 +1,86 @@
+use crate::time;
+use crate::ObservableValue;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// A value an observable held, and when it was written.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryEntry<T> {
+    pub value: T,
+    pub at: SystemTime,
+}
+
+/// The last `capacity` values of an observable; snapshots are shared, not copied.
+pub(crate) struct History<T> {
+    capacity: usize,
+    entries: VecDeque<(Arc<T>, SystemTime)>,
+}
+
+impl<T> History<T> {
+    fn record(&mut self, value: Arc<T>) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((value, time::now()));
+    }
+}
+
+impl<T: Clone + 'static> ObservableValue<T> {
+    /// Records the last `capacity` values, starting with the current one:
+    /// `observable(0.0).with_history(60)`
+    pub fn with_history(self, capacity: usize) -> Self {
+        self.enable_history(capacity);
+        self
+    }
+
+    /// Starts (or restarts) recording the last `capacity` values.
+    pub fn enable_history(&self, capacity: usize) {
+        let mut history = History {
+            capacity: capacity.max(1),
+            entries: VecDeque::new(),
+        };
+        history.record(self.peek_arc());
+        *self.history.lock().unwrap() = Some(history);
+    }
+
+    pub fn disable_history(&self) {
+        self.history.lock().unwrap().take();
+    }
+
+    /// Recorded values, oldest first; empty unless history is enabled.
+    ///
+    /// Tracked, so a component plotting the history re-renders on every write.
+    pub fn history(&self) -> Vec<HistoryEntry<T>> {
+        self.map(|_| ());
+        self.history
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|history| {
+                history
+                    .entries
+                    .iter()
+                    .map(|(value, at)| HistoryEntry {
+                        value: T::clone(value),
+                        at: *at,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Forgets recorded values except the current one.
+    pub fn clear_history(&self) {
+        if let Some(history) = self.history.lock().unwrap().as_mut() {
+            history.entries.clear();
+            history.record(self.peek_arc());
+        }
+    }
+
+    pub(crate) fn record_history(&self) {
+        if let Some(history) = self.history.lock().unwrap().as_mut() {
+            history.record(self.peek_arc());
+        }
+    }
+}
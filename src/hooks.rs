@@ -0,0 +1,147 @@
+use crate::observable::Observer;
+use crate::{
+    dismiss_error, errors, event_bus, use_store, Autorun, CancelToken, Computed, ErrorEntry,
+    Observable, ObservableValue, Store,
+};
+use dioxus::prelude::{schedule_update, use_hook, use_hook_with_cleanup, RenderError};
+use dioxus::CapturedError;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Creates an observable owned by the current component.
+///
+/// The component re-renders whenever the observable changes, and the
+/// subscription is removed when the component unmounts.
+pub fn use_observable<T: Clone + 'static>(initial: impl FnOnce() -> T) -> ObservableValue<T> {
+    let (observable, _) = use_hook_with_cleanup(
+        || {
+            let observable = ObservableValue::new(initial());
+            let update = schedule_update();
+            let id = observable.subscribe(move |_| update());
+            (observable, id)
+        },
+        |(observable, id)| observable.unsubscribe(id),
+    );
+
+    observable
+}
+
+/// Runs `effect` now and whenever an observable it read changes, until the component unmounts.
+///
+/// Like other hooks, only the closure passed on the first render is kept.
+pub fn use_autorun(effect: impl FnMut() + 'static) {
+    use_hook_with_cleanup(
+        || Rc::new(Autorun::new(effect)),
+        |autorun| autorun.dispose(),
+    );
+}
+
+/// Derives a value from observables inside a component.
+///
+/// The result is cached, and the component only re-renders when the derived
+/// value changes, not every time one of the observables it reads changes.
+pub fn use_computed<T, F>(compute: F) -> T
+where
+    T: Clone + PartialEq + 'static,
+    F: Fn() -> T + 'static,
+{
+    let (_, last, _) = use_hook(|| {
+        let computed = Computed::new(compute);
+        let last = Rc::new(RefCell::new(computed.peek()));
+        let update = schedule_update();
+
+        let observer = {
+            let computed = computed.clone();
+            let last = last.clone();
+            Observer::new(move || {
+                let value = computed.peek();
+                if *last.borrow() != value {
+                    *last.borrow_mut() = value;
+                    update();
+                }
+            })
+        };
+        computed.observe(observer.clone());
+
+        (computed, last, observer)
+    });
+
+    let value = last.borrow().clone();
+    value
+}
+
+/// Subscribes the component to a projection of the global store `S`:
+/// `use_selector::<AppStore, _>(|s| s.user.get().name)`
+///
+/// The component re-renders only when the selected value changes.
+pub fn use_selector<S, T>(select: impl Fn(&S) -> T + 'static) -> T
+where
+    S: Store + Default + Send + Sync,
+    T: Clone + PartialEq + 'static,
+{
+    use_computed(move || select(&use_store::<S>()))
+}
+
+/// Calls `handler` with every `E` emitted on the global [`EventBus`](crate::EventBus)
+/// until the component unmounts.
+///
+/// Like other hooks, only the closure passed on the first render is kept.
+pub fn use_event<E: 'static>(handler: impl Fn(&E) + Send + Sync + 'static) {
+    use_hook_with_cleanup(
+        || event_bus().subscribe(handler),
+        |id| event_bus().unsubscribe::<E>(id),
+    );
+}
+
+/// Like [`use_event`] for a handler that is not `Send`, e.g. one capturing a
+/// signal: it is called for the `E` emitted on the component's thread.
+pub fn use_local_event<E: 'static>(handler: impl Fn(&E) + 'static) {
+    use_hook_with_cleanup(
+        || event_bus().subscribe_local(handler),
+        |id| event_bus().unsubscribe::<E>(id),
+    );
+}
+
+/// A token cancelled when the component unmounts.
+///
+/// Cancellable store actions run with [`CancelToken::run`] on it are cancelled
+/// along with it, so they don't write results for a view that is gone.
+pub fn use_cancel_token() -> CancelToken {
+    use_hook_with_cleanup(CancelToken::new, |token| token.cancel())
+}
+
+/// The errors collected in [`errors`](crate::errors), re-rendering the
+/// component when they change, e.g. to show them as toasts that call
+/// [`dismiss_error`](crate::dismiss_error) when closed.
+pub fn use_errors() -> Vec<ErrorEntry> {
+    use_hook_with_cleanup(
+        || {
+            let update = schedule_update();
+            errors().subscribe(move |_| update())
+        },
+        |id| errors().unsubscribe(id),
+    );
+    errors().peek()
+}
+
+/// Hands the oldest collected error to the nearest `ErrorBoundary`, and
+/// dismisses it:
+///
+/// ```ignore
+/// #[component]
+/// fn Cart() -> Element {
+///     use_throw_errors()?;
+///     rsx! { CartItems {} }
+/// }
+/// ```
+pub fn use_throw_errors() -> Result<(), RenderError> {
+    match use_errors().into_iter().next() {
+        Some(entry) => {
+            dismiss_error(entry.id);
+            Err(RenderError::Aborted(CapturedError::from_display(
+                entry.message,
+            )))
+        }
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,461 @@
+use crate::store::register_store_if_absent;
+use crate::{get_store, has_store, register_store, ObservableStats, Store, StoreContext};
+use serde_json::{Map, Value};
+use std::any::{Any, TypeId};
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
+
+/// Stores whose state can be captured and restored as JSON.
+///
+/// Usually implemented with the [`serializable_store!`](crate::serializable_store) macro.
+pub trait SerializableStore: Store {
+    /// Key under which the store is written into the serialized state.
+    fn store_key() -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn snapshot(&self) -> Result<Value, serde_json::Error>;
+
+    /// Applies a snapshot to the live observables, notifying subscribers.
+    ///
+    /// Implementations deserialize the whole snapshot before assigning any
+    /// field, so an invalid snapshot leaves the store unchanged.
+    fn restore(&self, value: Value) -> Result<(), serde_json::Error>;
+
+    /// Builds a new instance holding a snapshot, used when hydration finds no
+    /// existing store.
+    ///
+    /// Defaults to restoring the snapshot into `Self::default()`; override it
+    /// when the default constructor is expensive, e.g. fetches data.
+    /// [`serializable_store!`](crate::serializable_store) overrides it when it
+    /// lists every field of the store.
+    fn from_snapshot(value: Value) -> Result<Self, serde_json::Error>
+    where
+        Self: Sized + Default,
+    {
+        let store = Self::default();
+        store.restore(value)?;
+        Ok(store)
+    }
+
+    /// Reads the serialized fields so the current observer is notified when they change.
+    fn track(&self) {}
+
+    /// [`stats`](crate::ObservableValue::stats) of each serialized field.
+    fn field_stats(&self) -> Vec<(&'static str, ObservableStats)> {
+        Vec::new()
+    }
+}
+
+type ErasedStore<'a> = &'a (dyn Any + Send + Sync);
+
+#[derive(Clone, Copy)]
+struct HydrationEntry {
+    key: &'static str,
+    type_id: TypeId,
+    snapshot: fn(ErasedStore) -> Option<Result<Value, serde_json::Error>>,
+    restore: fn(ErasedStore, Value) -> Option<Result<(), serde_json::Error>>,
+    reset: fn(ErasedStore) -> Option<Result<(), serde_json::Error>>,
+    track: fn(ErasedStore),
+    field_stats: fn(ErasedStore) -> Vec<(&'static str, ObservableStats)>,
+    serialize: fn() -> Option<Result<Value, serde_json::Error>>,
+    hydrate: fn(Value) -> Result<(), serde_json::Error>,
+    hydrate_in: fn(&StoreContext, Option<&str>, Value) -> Result<(), serde_json::Error>,
+}
+
+static HYDRATION_REGISTRY: LazyLock<Mutex<Vec<HydrationEntry>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Keys of the stores that received state from [`hydrate_stores`].
+static HYDRATED: LazyLock<Mutex<HashSet<&'static str>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Id of the `<script>` element produced by [`hydration_script`].
+pub const HYDRATION_ELEMENT_ID: &str = "reaxive-state";
+
+fn serialize_entry<S: SerializableStore>() -> Option<Result<Value, serde_json::Error>> {
+    get_store::<S>().map(|store| store.snapshot())
+}
+
+fn snapshot_entry<S: SerializableStore>(
+    store: ErasedStore,
+) -> Option<Result<Value, serde_json::Error>> {
+    store.downcast_ref::<S>().map(|store| store.snapshot())
+}
+
+fn restore_entry<S: SerializableStore>(
+    store: ErasedStore,
+    value: Value,
+) -> Option<Result<(), serde_json::Error>> {
+    store.downcast_ref::<S>().map(|store| store.restore(value))
+}
+
+fn reset_entry<S: SerializableStore + Default>(
+    store: ErasedStore,
+) -> Option<Result<(), serde_json::Error>> {
+    let store = store.downcast_ref::<S>()?;
+    Some(
+        S::default()
+            .snapshot()
+            .and_then(|value| store.restore(value)),
+    )
+}
+
+fn track_entry<S: SerializableStore>(store: ErasedStore) {
+    if let Some(store) = store.downcast_ref::<S>() {
+        store.track();
+    }
+}
+
+fn field_stats_entry<S: SerializableStore>(
+    store: ErasedStore,
+) -> Vec<(&'static str, ObservableStats)> {
+    store
+        .downcast_ref::<S>()
+        .map(|store| store.field_stats())
+        .unwrap_or_default()
+}
+
+fn entry_for(type_id: TypeId) -> Option<HydrationEntry> {
+    HYDRATION_REGISTRY
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|entry| entry.type_id == type_id)
+        .copied()
+}
+
+/// Snapshot of a type-erased store whose type was registered with [`register_hydratable`].
+pub(crate) fn snapshot_store(
+    type_id: TypeId,
+    store: ErasedStore,
+) -> Option<Result<Value, serde_json::Error>> {
+    (entry_for(type_id)?.snapshot)(store)
+}
+
+/// The hydration key and state of the current instance of the store type `type_id`.
+pub(crate) fn serialize_store(
+    type_id: TypeId,
+) -> Option<(&'static str, Result<Value, serde_json::Error>)> {
+    let entry = entry_for(type_id)?;
+    Some((entry.key, (entry.serialize)()?))
+}
+
+/// The [`SerializableStore::store_key`] of the store type `type_id`, if registered.
+pub(crate) fn hydration_key(type_id: TypeId) -> Option<&'static str> {
+    entry_for(type_id).map(|entry| entry.key)
+}
+
+/// Restores the store registered under `key` in `context`, creating it if needed.
+pub(crate) fn hydrate_in_context(
+    context: &StoreContext,
+    store_key: &str,
+    instance_key: Option<&str>,
+    value: Value,
+) -> Option<Result<(), serde_json::Error>> {
+    let hydrate_in = HYDRATION_REGISTRY
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|entry| entry.key == store_key)
+        .map(|entry| entry.hydrate_in)?;
+    Some(hydrate_in(context, instance_key, value))
+}
+
+/// Applies `value` to a type-erased store, as [`SerializableStore::restore`] does.
+pub(crate) fn restore_store(
+    type_id: TypeId,
+    store: ErasedStore,
+    value: Value,
+) -> Option<Result<(), serde_json::Error>> {
+    (entry_for(type_id)?.restore)(store, value)
+}
+
+/// Restores a type-erased store to the state of a default instance.
+pub(crate) fn reset_store(
+    type_id: TypeId,
+    store: ErasedStore,
+) -> Option<Result<(), serde_json::Error>> {
+    (entry_for(type_id)?.reset)(store)
+}
+
+pub(crate) fn track_store(type_id: TypeId, store: ErasedStore) {
+    if let Some(entry) = entry_for(type_id) {
+        (entry.track)(store);
+    }
+}
+
+pub(crate) fn store_field_stats(
+    type_id: TypeId,
+    store: ErasedStore,
+) -> Vec<(&'static str, ObservableStats)> {
+    entry_for(type_id)
+        .map(|entry| (entry.field_stats)(store))
+        .unwrap_or_default()
+}
+
+fn hydrate_entry<S: SerializableStore + Default + Send + Sync>(
+    value: Value,
+) -> Result<(), serde_json::Error> {
+    // `has_store` first so a lazy factory is not run only to be overwritten.
+    if let Some(store) = has_store::<S>().then(get_store::<S>).flatten() {
+        return store.restore(value);
+    }
+    register_store(S::from_snapshot(value)?);
+    Ok(())
+}
+
+fn hydrate_in_entry<S: SerializableStore + Default + Send + Sync>(
+    context: &StoreContext,
+    key: Option<&str>,
+    value: Value,
+) -> Result<(), serde_json::Error> {
+    let existing = match key {
+        Some(key) => context.get_keyed::<S>(key),
+        None => context.has::<S>().then(|| context.get::<S>()).flatten(),
+    };
+    if let Some(store) = existing {
+        return store.restore(value);
+    }
+
+    let store = S::from_snapshot(value)?;
+    match key {
+        Some(key) => context.register_keyed(key, store),
+        None => context.register(store),
+    }
+    Ok(())
+}
+
+/// Includes `S` in [`serialize_stores`] and [`hydrate_stores`].
+///
+/// Call it with the same stores on the server and on the client.
+pub fn register_hydratable<S: SerializableStore + Default + Send + Sync>() {
+    let mut registry = HYDRATION_REGISTRY.lock().unwrap();
+    if registry.iter().any(|entry| entry.key == S::store_key()) {
+        return;
+    }
+
+    registry.push(HydrationEntry {
+        key: S::store_key(),
+        type_id: TypeId::of::<S>(),
+        snapshot: snapshot_entry::<S>,
+        restore: restore_entry::<S>,
+        reset: reset_entry::<S>,
+        track: track_entry::<S>,
+        field_stats: field_stats_entry::<S>,
+        serialize: serialize_entry::<S>,
+        hydrate: hydrate_entry::<S>,
+        hydrate_in: hydrate_in_entry::<S>,
+    });
+}
+
+/// Serializes every registered hydratable store that exists in the global registry.
+pub fn serialize_stores() -> Result<String, serde_json::Error> {
+    let entries: Vec<_> = HYDRATION_REGISTRY
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|entry| (entry.key, entry.serialize))
+        .collect();
+
+    let mut state = Map::new();
+    for (key, serialize) in entries {
+        if let Some(value) = serialize() {
+            state.insert(key.to_string(), value?);
+        }
+    }
+
+    serde_json::to_string(&Value::Object(state))
+}
+
+/// Applies state produced by [`serialize_stores`], building missing stores
+/// with [`SerializableStore::from_snapshot`].
+///
+/// Returns how many stores were hydrated. Keys without a registered store are ignored.
+/// Stores are hydrated one after another: if one fails to decode, the error is
+/// returned and the stores hydrated before it keep their new state, while the
+/// failing store and the ones after it are unchanged.
+pub fn hydrate_stores(json: &str) -> Result<usize, serde_json::Error> {
+    let state: Map<String, Value> = serde_json::from_str(json)?;
+    let entries: Vec<_> = HYDRATION_REGISTRY
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|entry| (entry.key, entry.hydrate))
+        .collect();
+
+    let mut hydrated = 0;
+    for (key, hydrate) in entries {
+        if let Some(value) = state.get(key) {
+            hydrate(value.clone())?;
+            HYDRATED.lock().unwrap().insert(key);
+            hydrated += 1;
+        }
+    }
+
+    Ok(hydrated)
+}
+
+/// Whether `S` received server state from [`hydrate_stores`].
+pub fn is_hydrated<S: SerializableStore>() -> bool {
+    HYDRATED.lock().unwrap().contains(S::store_key())
+}
+
+/// The global `S`, built with `init` only if no instance exists yet. If another
+/// thread registers one while `init` runs, that instance is kept and returned.
+///
+/// A store hydrated from the server already exists, so expensive setup such
+/// as an initial fetch runs on the server but is skipped on the client:
+///
+/// ```ignore
+/// let feed = init_store(|| {
+///     let feed = FeedStore::default();
+///     feed.fetch_latest();
+///     feed
+/// });
+/// ```
+pub fn init_store<S: SerializableStore + Send + Sync>(init: impl FnOnce() -> S) -> S {
+    if let Some(store) = get_store::<S>() {
+        return store;
+    }
+    register_store_if_absent(init())
+}
+
+/// Restores the store registered under `key`, or `None` if there is none.
+pub(crate) fn hydrate_store(key: &str, value: Value) -> Option<Result<(), serde_json::Error>> {
+    let hydrate = HYDRATION_REGISTRY
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|entry| entry.key == key)
+        .map(|entry| entry.hydrate)?;
+    Some(hydrate(value))
+}
+
+/// Applies a JSON object of field values to the registered instance of `S`,
+/// e.g. to load a saved workspace: `apply_json::<EditorStore>(&saved)?`.
+///
+/// Fields missing from `json` keep their value, and subscribers are notified once
+/// every field is assigned. If any field fails to deserialize, nothing is
+/// assigned. Returns `false` if no instance of `S` is registered.
+pub fn apply_json<S: SerializableStore>(json: &str) -> Result<bool, serde_json::Error> {
+    let value: Value = serde_json::from_str(json)?;
+    let Some(store) = get_store::<S>() else {
+        return Ok(false);
+    };
+    crate::batch(|| store.restore(value))?;
+    Ok(true)
+}
+
+/// A `<script>` tag embedding the serialized stores, for the server-rendered HTML.
+pub fn hydration_script() -> Result<String, serde_json::Error> {
+    let json = serialize_stores()?.replace("</", "<\\/");
+    Ok(format!(
+        r#"<script id="{HYDRATION_ELEMENT_ID}" type="application/json">{json}</script>"#
+    ))
+}
+
+/// Reads the state embedded by [`hydration_script`] from the page and hydrates the stores.
+#[cfg(target_arch = "wasm32")]
+pub fn hydrate_from_document() -> Result<usize, serde_json::Error> {
+    let json = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id(HYDRATION_ELEMENT_ID))
+        .and_then(|element| element.text_content());
+
+    match json {
+        Some(json) => hydrate_stores(&json),
+        None => Ok(0),
+    }
+}
+
+/// Decodes and removes the field `name` of a snapshot, failing if it is missing.
+#[doc(hidden)]
+pub fn __snapshot_field<T: serde::de::DeserializeOwned>(
+    state: &mut Map<String, Value>,
+    name: &'static str,
+) -> Result<T, serde_json::Error> {
+    match state.remove(name) {
+        Some(value) => serde_json::from_value(value),
+        None => Err(serde::de::Error::missing_field(name)),
+    }
+}
+
+/// Implements [`SerializableStore`] by serializing the listed observable fields:
+///
+/// ```ignore
+/// serializable_store!(CounterStore { count, user });
+/// ```
+///
+/// When the list names every field of the store, hydration builds missing
+/// stores from the snapshot alone, without running `Default`. End the list
+/// with `..` if the store has other fields; missing stores are then restored
+/// into `Default`:
+///
+/// ```ignore
+/// serializable_store!(FeedStore { items, .. });
+/// ```
+#[macro_export]
+macro_rules! serializable_store {
+    (@impl $store:ty { $($field:ident),* } { $($from_snapshot:tt)* }) => {
+        impl $crate::SerializableStore for $store {
+            fn snapshot(&self) -> ::std::result::Result<$crate::__serde_json::Value, $crate::__serde_json::Error> {
+                let mut state = $crate::__serde_json::Map::new();
+                $(
+                    state.insert(
+                        stringify!($field).to_string(),
+                        $crate::__serde_json::to_value(self.$field.peek())?,
+                    );
+                )*
+                Ok($crate::__serde_json::Value::Object(state))
+            }
+
+            fn restore(&self, value: $crate::__serde_json::Value) -> ::std::result::Result<(), $crate::__serde_json::Error> {
+                let mut state: $crate::__serde_json::Map<String, $crate::__serde_json::Value> =
+                    $crate::__serde_json::from_value(value)?;
+                // Every field is decoded before any is assigned.
+                $(
+                    let $field = match state.remove(stringify!($field)) {
+                        Some(value) => Some($crate::__serde_json::from_value(value)?),
+                        None => None,
+                    };
+                )*
+                $(
+                    if let Some(value) = $field {
+                        $crate::Observable::assign(&self.$field, value);
+                    }
+                )*
+                Ok(())
+            }
+
+            $($from_snapshot)*
+
+            fn track(&self) {
+                $(
+                    self.$field.map(|_| ());
+                )*
+            }
+
+            fn field_stats(&self) -> ::std::vec::Vec<(&'static str, $crate::ObservableStats)> {
+                ::std::vec![$((stringify!($field), self.$field.stats())),*]
+            }
+        }
+    };
+    ($store:ty { $($field:ident,)* .. }) => {
+        $crate::serializable_store!(@impl $store { $($field),* } {});
+    };
+    ($store:ty { $($field:ident),* $(,)? }) => {
+        $crate::serializable_store!(@impl $store { $($field),* } {
+            fn from_snapshot(value: $crate::__serde_json::Value) -> ::std::result::Result<Self, $crate::__serde_json::Error> {
+                let mut state: $crate::__serde_json::Map<String, $crate::__serde_json::Value> =
+                    $crate::__serde_json::from_value(value)?;
+                $(
+                    let $field = $crate::ObservableValue::new(
+                        $crate::__snapshot_field(&mut state, stringify!($field))?,
+                    );
+                )*
+                Ok(Self { $($field),* })
+            }
+        });
+    };
+}
@@ -0,0 +1,149 @@
+//! IndexedDB storage for persisted values (feature `indexed-db`, wasm only).
+//!
+//! localStorage holds a few megabytes at most; IndexedDB fits large stores
+//! such as cached entity collections. IndexedDB is async, so the database is
+//! read once when opened and later reads are served from memory, while writes
+//! are queued to the database in order:
+//!
+//! ```ignore
+//! IndexedDbBackend::open("my-app").await?.install();
+//! persist(&entities.items, "entities.items");
+//! ```
+
+use crate::{set_storage_backend, PersistError, StorageBackend};
+use js_sys::{Array, Promise};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbOpenDbRequest, IdbRequest, IdbTransactionMode};
+
+/// Object store holding the values, keyed by persistence key.
+const OBJECT_STORE: &str = "reaxive";
+
+thread_local! {
+    static DATABASES: RefCell<HashMap<String, IdbDatabase>> = RefCell::new(HashMap::new());
+}
+
+/// Persistence backend over an IndexedDB database.
+#[derive(Clone)]
+pub struct IndexedDbBackend {
+    name: Arc<str>,
+    cache: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl IndexedDbBackend {
+    /// Opens (or creates) the database `name` and loads every persisted value.
+    pub async fn open(name: &str) -> Result<Self, PersistError> {
+        let database = open_database(name).await.map_err(storage_error)?;
+        let cache = load_all(&database).await.map_err(storage_error)?;
+        DATABASES.with(|databases| {
+            databases.borrow_mut().insert(name.to_string(), database);
+        });
+
+        Ok(Self {
+            name: name.into(),
+            cache: Arc::new(Mutex::new(cache)),
+        })
+    }
+
+    /// Makes [`persist`](crate::persist) and the other persistence functions use this database.
+    pub fn install(self) {
+        set_storage_backend(self);
+    }
+
+    fn object_store(&self) -> Result<web_sys::IdbObjectStore, PersistError> {
+        DATABASES.with(|databases| {
+            let databases = databases.borrow();
+            let database = databases
+                .get(&*self.name)
+                .ok_or_else(|| PersistError::Storage(format!("{} is not open", self.name)))?;
+            database
+                .transaction_with_str_and_mode(OBJECT_STORE, IdbTransactionMode::Readwrite)
+                .and_then(|transaction| transaction.object_store(OBJECT_STORE))
+                .map_err(storage_error)
+        })
+    }
+}
+
+impl StorageBackend for IndexedDbBackend {
+    fn load(&self, key: &str) -> Option<String> {
+        self.cache.lock().unwrap().get(key).cloned()
+    }
+
+    fn save(&self, key: &str, json: &str) -> Result<(), PersistError> {
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), json.to_string());
+        self.object_store()?
+            .put_with_key(&JsValue::from_str(json), &JsValue::from_str(key))
+            .map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) {
+        self.cache.lock().unwrap().remove(key);
+        if let Ok(store) = self.object_store() {
+            let _ = store.delete(&JsValue::from_str(key));
+        }
+    }
+}
+
+fn storage_error(error: JsValue) -> PersistError {
+    PersistError::Storage(format!("{error:?}"))
+}
+
+/// Resolves with the result of `request` once it succeeds.
+async fn complete(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    JsFuture::from(promise).await?;
+    request.result()
+}
+
+async fn open_database(name: &str) -> Result<IdbDatabase, JsValue> {
+    let factory = web_sys::window()
+        .ok_or("no window")?
+        .indexed_db()?
+        .ok_or("IndexedDB is unavailable")?;
+    let request = factory.open_with_u32(name, 1)?;
+
+    let upgrade = Closure::<dyn FnMut(web_sys::Event)>::new(move |event: web_sys::Event| {
+        let database = event
+            .target()
+            .and_then(|target| target.dyn_into::<IdbOpenDbRequest>().ok())
+            .and_then(|request| request.result().ok())
+            .map(JsCast::unchecked_into::<IdbDatabase>);
+        if let Some(database) = database {
+            if !database.object_store_names().contains(OBJECT_STORE) {
+                let _ = database.create_object_store(OBJECT_STORE);
+            }
+        }
+    });
+    request.set_onupgradeneeded(Some(upgrade.as_ref().unchecked_ref()));
+
+    let database = complete(&request).await?;
+    Ok(database.unchecked_into())
+}
+
+async fn load_all(database: &IdbDatabase) -> Result<HashMap<String, String>, JsValue> {
+    let store = database
+        .transaction_with_str(OBJECT_STORE)?
+        .object_store(OBJECT_STORE)?;
+    // Both requests are made before awaiting, while the transaction is still active
+    let keys = store.get_all_keys()?;
+    let values = store.get_all()?;
+    let keys: Array = complete(&keys).await?.unchecked_into();
+    let values: Array = complete(&values).await?.unchecked_into();
+
+    Ok(keys
+        .iter()
+        .zip(values.iter())
+        .filter_map(|(key, value)| Some((key.as_string()?, value.as_string()?)))
+        .collect())
+}
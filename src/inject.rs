@@ -0,0 +1,192 @@
+use crate::context::scoped_context;
+use crate::{get_global_registry, Store, StoreContext, StoreRegistry};
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::fmt;
+
+/// A store built from other stores of the same context.
+///
+/// [`use_store`](crate::use_store) and `get_or_create` resolve the dependencies
+/// of a missing store before constructing it. Stores with `Default` are
+/// injectable without dependencies; stores with dependencies implement this
+/// trait instead of `Default`:
+///
+/// ```ignore
+/// impl Injectable for CartStore {
+///     type Deps = (AuthStore, ApiStore);
+///
+///     fn construct((auth, api): Self::Deps) -> Self {
+///         CartStore::new(auth, api)
+///     }
+/// }
+/// ```
+pub trait Injectable: Store + Send + Sync {
+    type Deps: Dependencies;
+
+    fn construct(deps: Self::Deps) -> Self;
+}
+
+impl<S: Store + Default + Send + Sync> Injectable for S {
+    type Deps = ();
+
+    fn construct(_: ()) -> Self {
+        S::default()
+    }
+}
+
+/// A tuple of [`Injectable`] stores resolved before constructing a dependent store.
+pub trait Dependencies: Sized {
+    fn resolve<R: StoreResolver>(resolver: &R) -> Result<Self, DependencyCycle>;
+}
+
+/// Where dependencies are looked up and newly constructed stores are registered.
+pub trait StoreResolver {
+    fn lookup<S: Store>(&self) -> Option<S>;
+    fn insert<S: Store + Send + Sync>(&self, store: S);
+}
+
+impl StoreResolver for StoreContext {
+    fn lookup<S: Store>(&self) -> Option<S> {
+        self.get::<S>()
+    }
+
+    fn insert<S: Store + Send + Sync>(&self, store: S) {
+        self.register(store);
+    }
+}
+
+impl StoreResolver for StoreRegistry {
+    fn lookup<S: Store>(&self) -> Option<S> {
+        self.get::<S>()
+    }
+
+    fn insert<S: Store + Send + Sync>(&self, store: S) {
+        self.register(store);
+    }
+}
+
+/// Returned when constructing a store requires, directly or not, the store itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyCycle {
+    /// Type names from the first store in the cycle back to itself.
+    pub path: Vec<&'static str>,
+}
+
+impl fmt::Display for DependencyCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "store dependency cycle: {}", self.path.join(" -> "))
+    }
+}
+
+impl std::error::Error for DependencyCycle {}
+
+thread_local! {
+    static RESOLVING: RefCell<Vec<(TypeId, &'static str)>> = const { RefCell::new(Vec::new()) };
+}
+
+struct ResolvingGuard;
+
+impl ResolvingGuard {
+    fn enter<S: 'static>() -> Result<Self, DependencyCycle> {
+        RESOLVING.with(|resolving| {
+            let mut resolving = resolving.borrow_mut();
+            let type_id = TypeId::of::<S>();
+            let name = std::any::type_name::<S>();
+
+            if let Some(start) = resolving.iter().position(|(id, _)| *id == type_id) {
+                let mut path: Vec<_> = resolving[start..].iter().map(|(_, name)| *name).collect();
+                path.push(name);
+                return Err(DependencyCycle { path });
+            }
+
+            resolving.push((type_id, name));
+            Ok(ResolvingGuard)
+        })
+    }
+}
+
+impl Drop for ResolvingGuard {
+    fn drop(&mut self) {
+        RESOLVING.with(|resolving| {
+            resolving.borrow_mut().pop();
+        });
+    }
+}
+
+/// Returns the registered `S`, or constructs and registers it after resolving its dependencies.
+pub fn resolve_in<S: Injectable, R: StoreResolver>(resolver: &R) -> Result<S, DependencyCycle> {
+    if let Some(store) = resolver.lookup::<S>() {
+        return Ok(store);
+    }
+
+    let _guard = ResolvingGuard::enter::<S>()?;
+    let deps = S::Deps::resolve(resolver)?;
+
+    // A dependency may have constructed `S` through another path.
+    if let Some(store) = resolver.lookup::<S>() {
+        return Ok(store);
+    }
+
+    let store = S::construct(deps);
+    resolver.insert(store.clone());
+    Ok(store)
+}
+
+/// Constructs a new `S` from its dependencies, without registering it, e.g. as a keyed instance.
+pub(crate) fn construct_in<S: Injectable, R: StoreResolver>(
+    resolver: &R,
+) -> Result<S, DependencyCycle> {
+    let _guard = ResolvingGuard::enter::<S>()?;
+    Ok(S::construct(S::Deps::resolve(resolver)?))
+}
+
+/// Unwraps the result of resolving for the infallible `get_or_create` family,
+/// where a cycle is a bug in the store definitions.
+pub(crate) fn expect_resolved<S>(result: Result<S, DependencyCycle>) -> S {
+    result.unwrap_or_else(|cycle| panic!("{cycle}"))
+}
+
+/// [`resolve_in`] against the request or per-thread context if one is active, otherwise the global registry.
+pub fn resolve_store<S: Injectable>() -> Result<S, DependencyCycle> {
+    if let Some(context) = scoped_context() {
+        return resolve_in(&context);
+    }
+    let registry = get_global_registry().clone();
+    resolve_in(&registry)
+}
+
+impl StoreContext {
+    /// Resolves `S` and its dependencies from this context (and its parents).
+    pub fn resolve<S: Injectable>(&self) -> Result<S, DependencyCycle> {
+        resolve_in(self)
+    }
+}
+
+impl StoreRegistry {
+    pub fn resolve<S: Injectable>(&self) -> Result<S, DependencyCycle> {
+        resolve_in(self)
+    }
+}
+
+impl Dependencies for () {
+    fn resolve<R: StoreResolver>(_resolver: &R) -> Result<Self, DependencyCycle> {
+        Ok(())
+    }
+}
+
+macro_rules! impl_dependencies {
+    ($($dep:ident),+) => {
+        impl<$($dep: Injectable),+> Dependencies for ($($dep,)+) {
+            fn resolve<R: StoreResolver>(resolver: &R) -> Result<Self, DependencyCycle> {
+                Ok(($(resolve_in::<$dep, R>(resolver)?,)+))
+            }
+        }
+    };
+}
+
+impl_dependencies!(A);
+impl_dependencies!(A, B);
+impl_dependencies!(A, B, C);
+impl_dependencies!(A, B, C, D);
+impl_dependencies!(A, B, C, D, E);
+impl_dependencies!(A, B, C, D, E, F);
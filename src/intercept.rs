@@ -0,0 +1,72 @@
+use crate::ObservableValue;
+use std::sync::Arc;
+
+/// What an interceptor does with a proposed write.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InterceptResult<T> {
+    /// Let the value through unchanged.
+    Accept,
+    /// Discard the write and keep the current value.
+    Reject,
+    /// Write this value instead.
+    Replace(T),
+}
+
+pub(crate) type Interceptor<T> = Arc<dyn Fn(&T) -> InterceptResult<T> + Send + Sync>;
+
+impl<T: Clone + 'static> ObservableValue<T> {
+    /// Sees every write before it is applied and may reject or rewrite it:
+    ///
+    /// ```ignore
+    /// volume.intercept(|v| match *v {
+    ///     v if v > 100 => InterceptResult::Replace(100),
+    ///     _ => InterceptResult::Accept,
+    /// });
+    /// ```
+    ///
+    /// Interceptors run in registration order, each seeing the value left by the
+    /// previous one, and before validators. Returns an id for [`ObservableValue::remove_interceptor`].
+    pub fn intercept<F>(&self, interceptor: F) -> usize
+    where
+        F: Fn(&T) -> InterceptResult<T> + Send + Sync + 'static,
+    {
+        self.interceptors
+            .lock()
+            .unwrap()
+            .insert(Arc::new(interceptor))
+    }
+
+    pub fn remove_interceptor(&self, id: usize) {
+        self.interceptors.lock().unwrap().remove(id);
+    }
+
+    pub(crate) fn has_interceptors(&self) -> bool {
+        !self.interceptors.lock().unwrap().is_empty()
+    }
+
+    /// The value to write after every interceptor ran and whether one replaced it,
+    /// or `None` if one rejected it.
+    pub(crate) fn intercept_write(&self, mut value: T) -> Option<(T, bool)> {
+        // Called without the lock, so interceptors may read or intercept this observable
+        let interceptors: Vec<_> = self
+            .interceptors
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, interceptor)| interceptor.clone())
+            .collect();
+
+        let mut replaced = false;
+        for interceptor in interceptors {
+            match interceptor(&value) {
+                InterceptResult::Accept => {}
+                InterceptResult::Reject => return None,
+                InterceptResult::Replace(replacement) => {
+                    value = replacement;
+                    replaced = true;
+                }
+            }
+        }
+        Some((value, replaced))
+    }
+}
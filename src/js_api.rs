@@ -0,0 +1,182 @@
+//! JavaScript access to stores through `window.__reaxive` (feature `js`, wasm only).
+//!
+//! ```ignore
+//! expose_store::<CartStore>();
+//! expose_action::<CartStore, _>("add", |cart, args| {
+//!     cart.add(serde_json::from_value(args).map_err(|e| e.to_string())?);
+//!     Ok(Value::Null)
+//! });
+//! install_js_api()?;
+//! ```
+//!
+//! ```js
+//! const { stores, get, dispatch, subscribe } = window.__reaxive;
+//! const off = subscribe("app::CartStore", (state) => console.log(state.items));
+//! dispatch("app::CartStore", "add", { id: 1 });
+//! off();
+//! ```
+
+use crate::{use_store, ObserverContext, SerializableStore};
+use js_sys::{Array, Function, Reflect, JSON};
+use serde_json::Value;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsValue;
+
+/// Name of the API object on `window`.
+pub const JS_API_NAME: &str = "__reaxive";
+
+type Action = Rc<dyn Fn(Value) -> Result<Value, String>>;
+type Snapshot = fn() -> Result<Value, serde_json::Error>;
+
+struct ExposedStore {
+    key: &'static str,
+    snapshot: Snapshot,
+    track: fn(),
+}
+
+thread_local! {
+    static EXPOSED: RefCell<Vec<ExposedStore>> = const { RefCell::new(Vec::new()) };
+    static ACTIONS: RefCell<HashMap<(String, String), Action>> = RefCell::new(HashMap::new());
+    static SUBSCRIPTIONS: RefCell<HashMap<u64, ObserverContext>> = RefCell::new(HashMap::new());
+    static NEXT_SUBSCRIPTION: Cell<u64> = const { Cell::new(0) };
+}
+
+fn snapshot_store<S: SerializableStore + Default + Send + Sync>() -> Result<Value, serde_json::Error>
+{
+    use_store::<S>().snapshot()
+}
+
+fn track_store<S: SerializableStore + Default + Send + Sync>() {
+    use_store::<S>().track();
+}
+
+/// Makes the global instance of `S` readable and subscribable from JavaScript under [`SerializableStore::store_key`].
+pub fn expose_store<S: SerializableStore + Default + Send + Sync>() {
+    EXPOSED.with(|exposed| {
+        let mut exposed = exposed.borrow_mut();
+        if !exposed.iter().any(|store| store.key == S::store_key()) {
+            exposed.push(ExposedStore {
+                key: S::store_key(),
+                snapshot: snapshot_store::<S>,
+                track: track_store::<S>,
+            });
+        }
+    });
+}
+
+/// Lets JavaScript run `action` on the global instance of `S` with `dispatch(key, name, args)`.
+///
+/// `args` and the returned value cross the boundary as JSON; an `Err` is thrown in JavaScript.
+pub fn expose_action<S, F>(name: impl Into<String>, action: F)
+where
+    S: SerializableStore + Default + Send + Sync,
+    F: Fn(&S, Value) -> Result<Value, String> + 'static,
+{
+    expose_store::<S>();
+    let action: Action = Rc::new(move |args| action(&use_store::<S>(), args));
+    ACTIONS.with(|actions| {
+        actions
+            .borrow_mut()
+            .insert((S::store_key().to_string(), name.into()), action)
+    });
+}
+
+fn exposed(key: &str) -> Option<(Snapshot, fn())> {
+    EXPOSED.with(|exposed| {
+        exposed
+            .borrow()
+            .iter()
+            .find(|store| store.key == key)
+            .map(|store| (store.snapshot, store.track))
+    })
+}
+
+fn to_js(value: &Value) -> Result<JsValue, JsValue> {
+    JSON::parse(&value.to_string())
+}
+
+fn from_js(value: &JsValue) -> Result<Value, JsValue> {
+    if value.is_undefined() {
+        return Ok(Value::Null);
+    }
+    let json: String = JSON::stringify(value)?.into();
+    serde_json::from_str(&json).map_err(|error| error.to_string().into())
+}
+
+fn get(key: String) -> Result<JsValue, JsValue> {
+    let Some((snapshot, _)) = exposed(&key) else {
+        return Ok(JsValue::UNDEFINED);
+    };
+    to_js(&snapshot().map_err(|error| error.to_string())?)
+}
+
+fn dispatch(key: String, name: String, args: JsValue) -> Result<JsValue, JsValue> {
+    let action =
+        ACTIONS.with(|actions| actions.borrow().get(&(key.clone(), name.clone())).cloned());
+    let Some(action) = action else {
+        return Err(format!("unknown action {name} of {key}").into());
+    };
+    to_js(&action(from_js(&args)?)?)
+}
+
+/// Calls `callback` with the new state after every change; returns the unsubscribe function.
+fn subscribe(key: String, callback: Function) -> Result<JsValue, JsValue> {
+    let Some((snapshot, track)) = exposed(&key) else {
+        return Err(format!("store {key} is not exposed").into());
+    };
+
+    let context = ObserverContext::new_untracked(move || {
+        if let Ok(state) = snapshot()
+            .map_err(|error| JsValue::from(error.to_string()))
+            .and_then(|state| to_js(&state))
+        {
+            let _ = callback.call1(&JsValue::NULL, &state);
+        }
+    });
+    {
+        let _scope = context.track();
+        track();
+    }
+
+    let id = NEXT_SUBSCRIPTION.with(|next| next.replace(next.get() + 1));
+    SUBSCRIPTIONS.with(|subscriptions| subscriptions.borrow_mut().insert(id, context));
+
+    let unsubscribe = Closure::once_into_js(move || {
+        SUBSCRIPTIONS.with(|subscriptions| subscriptions.borrow_mut().remove(&id));
+    });
+    Ok(unsubscribe)
+}
+
+/// Installs `window.__reaxive` with `stores()`, `get(key)`, `dispatch(key, action, args)`
+/// and `subscribe(key, callback)`.
+pub fn install_js_api() -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or("no window")?;
+    let api = js_sys::Object::new();
+
+    let stores = Closure::<dyn Fn() -> Array>::new(|| {
+        EXPOSED.with(|exposed| {
+            exposed
+                .borrow()
+                .iter()
+                .map(|store| JsValue::from_str(store.key))
+                .collect()
+        })
+    });
+    Reflect::set(&api, &"stores".into(), &stores.into_js_value())?;
+
+    let get = Closure::<dyn Fn(String) -> Result<JsValue, JsValue>>::new(get);
+    Reflect::set(&api, &"get".into(), &get.into_js_value())?;
+
+    let dispatch =
+        Closure::<dyn Fn(String, String, JsValue) -> Result<JsValue, JsValue>>::new(dispatch);
+    Reflect::set(&api, &"dispatch".into(), &dispatch.into_js_value())?;
+
+    let subscribe = Closure::<dyn Fn(String, Function) -> Result<JsValue, JsValue>>::new(subscribe);
+    Reflect::set(&api, &"subscribe".into(), &subscribe.into_js_value())?;
+
+    Reflect::set(&window, &JS_API_NAME.into(), &api)?;
+    Ok(())
+}
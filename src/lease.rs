@@ -0,0 +1,135 @@
+use crate::{get_keyed, register_keyed, remove_keyed, Store};
+#[cfg(feature = "dioxus")]
+use dioxus::prelude::use_hook;
+use std::any::TypeId;
+#[cfg(feature = "dioxus")]
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Deref;
+#[cfg(feature = "dioxus")]
+use std::rc::Rc;
+use std::sync::{LazyLock, Mutex};
+
+static LEASES: LazyLock<Mutex<HashMap<(TypeId, String), usize>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// A counted reference to the keyed instance of `S` for one key.
+///
+/// The instance is created by the first lease and removed from the registry
+/// when the last lease for its key is dropped.
+pub struct StoreLease<S: Store> {
+    key: String,
+    store: S,
+}
+
+impl<S: Store> StoreLease<S> {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<S: Store> Deref for StoreLease<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<S: Store> Drop for StoreLease<S> {
+    fn drop(&mut self) {
+        let mut leases = LEASES.lock().unwrap();
+        let entry = (TypeId::of::<S>(), self.key.clone());
+        if let Some(count) = leases.get_mut(&entry) {
+            *count -= 1;
+            if *count == 0 {
+                leases.remove(&entry);
+                // Removal runs lifecycle hooks, which may lease other stores.
+                drop(leases);
+                remove_keyed::<S>(&self.key);
+            }
+        }
+    }
+}
+
+/// Leases the instance of `S` for `key`, creating it with `Default` if needed.
+pub fn lease_store<S>(key: impl ToString) -> StoreLease<S>
+where
+    S: Store + Default + Send + Sync,
+{
+    lease_store_with(key, |_| S::default())
+}
+
+/// Leases the instance of `S` for `key`, creating it with `factory` if needed.
+pub fn lease_store_with<S, F>(key: impl ToString, factory: F) -> StoreLease<S>
+where
+    S: Store + Send + Sync,
+    F: FnOnce(&str) -> S,
+{
+    let key = key.to_string();
+
+    // The factory and registration hooks may lease other stores, so they run
+    // without holding `LEASES`.
+    let store = match get_keyed::<S>(&key) {
+        Some(store) => store,
+        None => {
+            let store = factory(&key);
+            register_keyed(key.clone(), store.clone());
+            store
+        }
+    };
+
+    let mut leases = LEASES.lock().unwrap();
+    // Another thread may have registered its own instance in the meantime;
+    // lease whichever one stayed registered.
+    let store = get_keyed::<S>(&key).unwrap_or(store);
+    *leases.entry((TypeId::of::<S>(), key.clone())).or_insert(0) += 1;
+
+    StoreLease { key, store }
+}
+
+/// Number of live leases on the instance of `S` for `key`.
+pub fn lease_count<S: Store>(key: &str) -> usize {
+    LEASES
+        .lock()
+        .unwrap()
+        .get(&(TypeId::of::<S>(), key.to_string()))
+        .copied()
+        .unwrap_or(0)
+}
+
+/// One store instance per key, shared by every component using the same key:
+/// `let chat = use_store_for::<ChatStore>(chat_id);`
+///
+/// The instance is disposed once no mounted component uses its key.
+#[cfg(feature = "dioxus")]
+pub fn use_store_for<S>(key: impl ToString) -> S
+where
+    S: Store + Default + Send + Sync,
+{
+    use_store_for_with(key, |_| S::default())
+}
+
+/// Like [`use_store_for`], building new instances with `factory`.
+#[cfg(feature = "dioxus")]
+pub fn use_store_for_with<S, F>(key: impl ToString, factory: F) -> S
+where
+    S: Store + Send + Sync,
+    F: FnOnce(&str) -> S,
+{
+    let lease = use_hook(|| Rc::new(RefCell::new(None::<StoreLease<S>>)));
+    let key = key.to_string();
+
+    let mut current = lease.borrow_mut();
+    if current.as_ref().is_none_or(|lease| lease.key != key) {
+        // Take the new lease before releasing the old one.
+        let previous = current.replace(lease_store_with(key, factory));
+        drop(previous);
+    }
+
+    current.as_ref().unwrap().store.clone()
+}
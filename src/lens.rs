@@ -0,0 +1,121 @@
+use crate::{observable, Observable, ObservableValue, Priority};
+use std::sync::Arc;
+
+type Getter<P, F> = Arc<dyn Fn(&P) -> &F + Send + Sync>;
+type GetterMut<P, F> = Arc<dyn Fn(&mut P) -> &mut F + Send + Sync>;
+
+/// Removes the parent subscription once the last clone of a [`Focus`] is dropped.
+struct FocusSubscription<P: Clone + 'static> {
+    parent: ObservableValue<P>,
+    id: usize,
+}
+
+impl<P: Clone + 'static> Drop for FocusSubscription<P> {
+    fn drop(&mut self) {
+        self.parent.unsubscribe(self.id);
+    }
+}
+
+/// A field of an observable struct, observable on its own.
+///
+/// Reads and writes go through the parent, but readers and subscribers are
+/// only notified when the focused field changes, not on every parent write.
+/// Created with [`ObservableValue::focus`].
+pub struct Focus<P: Clone + 'static, F: Clone + 'static> {
+    parent: ObservableValue<P>,
+    // Mirrors the field; written by the parent subscription when the field changes
+    field: ObservableValue<F>,
+    get: Getter<P, F>,
+    get_mut: GetterMut<P, F>,
+    _subscription: Arc<FocusSubscription<P>>,
+}
+
+impl<P: Clone + 'static, F: Clone + 'static> Clone for Focus<P, F> {
+    fn clone(&self) -> Self {
+        Self {
+            parent: self.parent.clone(),
+            field: self.field.clone(),
+            get: self.get.clone(),
+            get_mut: self.get_mut.clone(),
+            _subscription: self._subscription.clone(),
+        }
+    }
+}
+
+impl<T: Clone + 'static> ObservableValue<T> {
+    /// Projects a field into its own observable:
+    /// `let name = user.focus(|user| &user.name, |user| &mut user.name);`
+    pub fn focus<F, G, M>(&self, get: G, get_mut: M) -> Focus<T, F>
+    where
+        F: Clone + PartialEq + Send + Sync + 'static,
+        G: Fn(&T) -> &F + Send + Sync + 'static,
+        M: Fn(&mut T) -> &mut F + Send + Sync + 'static,
+    {
+        let get: Getter<T, F> = Arc::new(get);
+        let field = observable(get(&self.peek_arc()).clone());
+
+        let id = self.subscribe_with_priority(Priority::Computed, {
+            let get = get.clone();
+            let field = field.clone();
+            move |parent: &T| {
+                let value = get(parent);
+                if *field.peek_arc() != *value {
+                    field.assign(value.clone());
+                }
+            }
+        });
+
+        Focus {
+            parent: self.clone(),
+            field,
+            get,
+            get_mut: Arc::new(get_mut),
+            _subscription: Arc::new(FocusSubscription {
+                parent: self.clone(),
+                id,
+            }),
+        }
+    }
+}
+
+impl<P: Clone + 'static, F: Clone + 'static> Focus<P, F> {
+    /// Read the field without subscribing the current observer
+    pub fn peek(&self) -> F {
+        (self.get)(&self.parent.peek_arc()).clone()
+    }
+
+    pub fn map<U>(&self, mapper: impl FnOnce(&F) -> U) -> U {
+        self.field.map(|_| ());
+        mapper((self.get)(&self.parent.peek_arc()))
+    }
+
+    /// The observable holding the whole struct.
+    pub fn parent(&self) -> &ObservableValue<P> {
+        &self.parent
+    }
+}
+
+impl<P: Clone + 'static, F: Clone + 'static> Observable<F> for Focus<P, F> {
+    fn get(&self) -> F {
+        self.map(F::clone)
+    }
+
+    fn assign(&self, value: F) {
+        self.set(|field| *field = value);
+    }
+
+    fn set<U>(&self, updater: U)
+    where
+        U: FnOnce(&mut F),
+    {
+        self.parent.set(|parent| updater((self.get_mut)(parent)));
+    }
+
+    fn subscribe<U: Fn(&F) + Send + Sync + 'static>(&self, callback: U) -> usize {
+        self.field.subscribe(callback)
+    }
+
+    fn unsubscribe(&self, id: usize) {
+        self.field.unsubscribe(id);
+    }
+}
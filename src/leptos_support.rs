@@ -0,0 +1,97 @@
+//! Leptos bindings: `use reaxive::leptos_support::*;`
+
+use crate::{
+    get_default_context, Observable, ObservableValue, Store, StoreContext, SyncObserverContext,
+};
+use leptos::prelude::*;
+
+/// Provides a fresh [`StoreContext`] to the current component and its children.
+///
+/// With `inherit`, stores missing from it are looked up in the enclosing
+/// context, then in the default context. The context is cleared when the
+/// component is disposed.
+pub fn provide_store_context(inherit: bool) -> StoreContext {
+    let mut context = StoreContext::new();
+    if inherit {
+        let parent = use_context::<StoreContext>().unwrap_or_else(get_default_context);
+        context = context.with_parent(parent);
+    }
+    provide_context(context.clone());
+
+    on_cleanup({
+        let context = context.clone();
+        move || context.clear()
+    });
+
+    context
+}
+
+/// Registers `store` in the provided context, and removes it when the
+/// component is disposed.
+pub fn use_provide_store<S: Store + Send + Sync>(store: S) {
+    use_provide_store_with(store, false);
+}
+
+/// Like [`use_provide_store`]; with `keep_alive`, the store stays registered
+/// after the component is disposed, and a new instance of the component keeps using it.
+pub fn use_provide_store_with<S: Store + Send + Sync>(store: S, keep_alive: bool) {
+    let context = expect_context::<StoreContext>();
+    if keep_alive {
+        if !context.has_local::<S>() {
+            context.register(store);
+        }
+    } else if let Some(entry) = context.register_entry(store) {
+        on_cleanup(move || context.remove_entry::<S>(&entry));
+    }
+}
+
+/// The instance of `S` in the nearest provided context, or in the default context.
+pub fn use_store_from_context<S: Store + Default + Send + Sync>() -> S {
+    use_context::<StoreContext>()
+        .unwrap_or_else(get_default_context)
+        .get_or_create::<S>()
+}
+
+pub fn get_store_from_context<S: Store>() -> Option<S> {
+    use_context::<StoreContext>()?.get::<S>()
+}
+
+/// A Leptos signal following `observable`, unsubscribed when the owner is disposed.
+pub fn to_signal<T>(observable: &ObservableValue<T>) -> ReadSignal<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let (signal, set_signal) = signal(observable.peek());
+    let id = observable.subscribe(move |value: &T| set_signal.set(value.clone()));
+
+    on_cleanup({
+        let observable = observable.clone();
+        move || observable.unsubscribe(id)
+    });
+
+    signal
+}
+
+/// The Leptos counterpart of Dioxus' `use_reactive`: a signal computed by `f`
+/// that updates whenever an observable read by `f` changes.
+///
+/// ```ignore
+/// let total = use_reactive(move || cart.items.get().len());
+/// view! { <span>{total}</span> }
+/// ```
+pub fn use_reactive<T, F>(f: F) -> Signal<T>
+where
+    T: Clone + Send + Sync + 'static,
+    F: Fn() -> T + Send + Sync + 'static,
+{
+    let trigger = ArcTrigger::new();
+    let observer = SyncObserverContext::new({
+        let trigger = trigger.clone();
+        move || trigger.notify()
+    });
+
+    Signal::derive(move || {
+        trigger.track();
+        observer.run(&f)
+    })
+}
@@ -1,25 +1,184 @@
+pub mod actions;
+#[cfg(feature = "macros")]
+pub mod attr;
+pub mod autorun;
+#[cfg(feature = "dioxus")]
+pub mod bind;
+pub mod cancel;
+pub mod channel;
+pub mod computed;
 pub mod context;
+pub mod context_middleware;
+#[cfg(feature = "devtools")]
+pub mod devtools;
+pub mod dispatch;
+pub mod effects;
+#[cfg(feature = "encryption")]
+pub mod encryption;
+pub mod entity;
+pub mod errors;
+pub mod events;
+pub mod flow;
+pub mod form;
+pub mod getters;
+pub mod group;
+pub mod history;
+#[cfg(feature = "dioxus")]
+pub mod hooks;
+#[cfg(feature = "serde")]
+pub mod hydration;
+#[cfg(all(feature = "indexed-db", target_arch = "wasm32"))]
+pub mod indexed_db;
+pub mod inject;
+pub mod intercept;
+#[cfg(all(feature = "js", target_arch = "wasm32"))]
+pub mod js_api;
+pub mod lease;
+pub mod lens;
+#[cfg(feature = "leptos")]
+pub mod leptos_support;
+pub mod lifecycle;
 pub mod macros;
+pub mod mutation;
 pub mod observable;
+#[cfg(feature = "serde")]
+pub mod patch;
+#[cfg(feature = "serde")]
+pub mod persistence;
+#[cfg(feature = "persistent")]
+pub mod persistent;
+#[cfg(feature = "dioxus")]
+pub mod query;
+pub mod read_only;
+#[cfg(feature = "serde")]
+pub mod recorder;
+pub mod request;
+pub mod reset;
+#[cfg(feature = "dioxus")]
+pub mod resource;
+pub mod retry;
+#[cfg(feature = "router")]
+pub mod router;
+pub mod scheduler;
+pub mod selector;
+#[cfg(all(feature = "sled", not(target_arch = "wasm32")))]
+pub mod sled_backend;
+pub mod stats;
 pub mod store;
+pub mod sync_observer;
+#[cfg(feature = "tauri")]
+pub mod tauri_bridge;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod time;
+pub mod validation;
+pub mod visibility;
+#[cfg(feature = "yew")]
+pub mod yew_support;
 
+pub use actions::*;
+pub use autorun::*;
+#[cfg(feature = "dioxus")]
+pub use bind::*;
+pub use cancel::*;
+pub use channel::*;
+pub use computed::*;
 pub use context::*;
+pub use context_middleware::*;
+#[cfg(feature = "devtools")]
+pub use devtools::*;
+pub use dispatch::*;
+pub use effects::*;
+#[cfg(feature = "encryption")]
+pub use encryption::*;
+pub use entity::*;
+pub use errors::*;
+pub use events::*;
+pub use flow::*;
+pub use form::*;
+pub use getters::*;
+pub use group::*;
+pub use history::*;
+#[cfg(feature = "dioxus")]
+pub use hooks::*;
+#[cfg(feature = "serde")]
+pub use hydration::*;
+pub use inject::*;
+pub use intercept::*;
+pub use lease::*;
+pub use lens::*;
+pub use lifecycle::*;
+pub use mutation::*;
 pub use observable::*;
+#[cfg(feature = "serde")]
+pub use patch::*;
+#[cfg(feature = "serde")]
+pub use persistence::*;
+#[cfg(feature = "persistent")]
+pub use persistent::*;
+#[cfg(feature = "dioxus")]
+pub use query::*;
+pub use read_only::*;
+#[cfg(feature = "serde")]
+pub use recorder::*;
+pub use request::*;
+pub use reset::*;
+#[cfg(feature = "dioxus")]
+pub use resource::*;
+pub use retry::*;
+pub use scheduler::*;
+pub use selector::*;
+pub use stats::*;
 pub use store::*;
+pub use sync_observer::*;
+pub use validation::*;
+pub use visibility::*;
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde_json as __serde_json;
 
 pub mod prelude {
     pub use crate::{
-        clear_all_stores, create_store, get_context_store, get_store, has_store, observable,
-        observable_bool, observable_map, observable_number, observable_option, observable_string,
-        observable_vec, provide_store, register_store, remove_store, store_action,
-        store_action_mut, store_count, use_context_store, use_reactive, use_store, GlobalStore,
-        Observable, ObservableBool, ObservableF64, ObservableI32, ObservableMap, ObservableOption,
-        ObservableString, ObservableU32, ObservableValue, ObservableVec, ObserverContext, Store,
-        StoreRegistry,
+        add_middleware, any_store_busy, autorun, batch, clear_all_stores, clear_all_with,
+        clear_errors, clear_group, computed, create_selector, create_store, dismiss_error,
+        dispatch, emit, errors, flush, get_context_store, get_keyed, get_store, get_store_async,
+        has_store, list_stores, mark_private, observable, observable_bool, observable_map,
+        observable_number, observable_option, observable_string, observable_vec, on_action,
+        optimistic, provide_store, register_in_group, register_keyed, register_lazy,
+        register_lazy_async, register_lifecycle, register_reducer, register_store, remove_store,
+        report_error, reset_group, resolve_store, retry, start_flow, stats, store_action,
+        store_action_mut, store_count, untracked, use_context_store, use_keyed_store, use_store,
+        with_request_context, Autorun, CancelToken, ChannelSink, Computed, ContextMiddleware,
+        DynStore, EntityStore, ErrorEntry, ErrorSource, EventBus, Flow, FormStore, GlobalStore,
+        HistoryEntry, Injectable, InterceptResult, Observable, ObservableBool, ObservableF64,
+        ObservableI32, ObservableMap, ObservableOption, ObservableString, ObservableU32,
+        ObservableValue, ObservableVec, ObserverContext, Priority, ReadOnlyObservable,
+        ReceiverFeed, Reducer, Resettable, RetryPolicy, RetryState, Selector, Store, StoreContext,
+        StoreLifecycle, StoreRegistry, SyncObserverContext, TrackingScope, ValidationMode,
+    };
+
+    #[cfg(feature = "dioxus")]
+    pub use crate::{
+        bind, bind_value, effect, notify_window_focus, query, resource, use_autorun,
+        use_cancel_token, use_computed, use_errors, use_event, use_local_event, use_named_store,
+        use_observable, use_observable_resource, use_provide_store, use_reactive, use_selector,
+        use_store_for, use_store_from_context, use_throw_errors, use_tracking_scope, Bindable,
+        NamedStoreProvider, ObservableResource, QueryEntry, ResourceState, StoreProvider,
+    };
+
+    #[cfg(feature = "devtools")]
+    pub use crate::ReaxiveDevtools;
+
+    #[cfg(feature = "serde")]
+    pub use crate::{
+        apply_json, diff_json, hydrate_stores, init_store, is_hydrated, persist,
+        register_hydratable, replay_mutations, serializable_store, serialize_stores,
+        snapshot_group, watch_patches, MutationRecorder, PatchOperation, SerializableStore,
     };
 
     pub use crate::{
-        action, create_global_store, multi_store, reaxive, reaxive_store, store_with_actions,
+        action, create_global_store, flow, multi_store, reaxive, reaxive_store, store_with_actions,
     };
 }
 
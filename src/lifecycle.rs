@@ -0,0 +1,139 @@
+use crate::Store;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+
+/// Hooks the registry calls as a store is registered, removed or reset.
+///
+/// Enable them for a store type with [`register_lifecycle`]:
+///
+/// ```ignore
+/// impl StoreLifecycle for ChatStore {
+///     fn on_register(&self) { self.connect(); }
+///     fn on_remove(&self) { self.disconnect(); }
+/// }
+///
+/// register_lifecycle::<ChatStore>();
+/// ```
+pub trait StoreLifecycle: Store {
+    /// Called after the store is added to a registry or context, including lazily.
+    fn on_register(&self) {}
+
+    /// Called after the store is removed, cleared, or replaced by another instance.
+    fn on_remove(&self) {}
+
+    /// Called on the current instance before [`GlobalStore::reset`](crate::GlobalStore::reset) resets it.
+    fn on_reset(&self) {}
+
+    /// Called while the store is still registered, before a registry or context
+    /// is cleared, e.g. by [`clear_all_stores`](crate::clear_all_stores); the place
+    /// to flush persisted data or abort background tasks.
+    fn on_clear(&self) {}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    Register,
+    Remove,
+    Reset,
+    Clear,
+}
+
+type LifecycleHook = fn(&(dyn Any + Send + Sync), LifecycleEvent);
+
+static LIFECYCLE_HOOKS: LazyLock<RwLock<HashMap<TypeId, LifecycleHook>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+fn dispatch<S: StoreLifecycle>(store: &(dyn Any + Send + Sync), event: LifecycleEvent) {
+    if let Some(store) = store.downcast_ref::<S>() {
+        match event {
+            LifecycleEvent::Register => store.on_register(),
+            LifecycleEvent::Remove => store.on_remove(),
+            LifecycleEvent::Reset => store.on_reset(),
+            LifecycleEvent::Clear => store.on_clear(),
+        }
+    }
+}
+
+/// Makes every registry and context call the [`StoreLifecycle`] hooks of `S`.
+pub fn register_lifecycle<S: StoreLifecycle>() {
+    LIFECYCLE_HOOKS
+        .write()
+        .unwrap()
+        .insert(TypeId::of::<S>(), dispatch::<S>);
+}
+
+/// Runs the hook for `event` on `store`; must be called without holding registry locks.
+pub(crate) fn notify_lifecycle(
+    type_id: TypeId,
+    store: &(dyn Any + Send + Sync),
+    event: LifecycleEvent,
+) {
+    let hook = LIFECYCLE_HOOKS.read().unwrap().get(&type_id).copied();
+    if let Some(hook) = hook {
+        hook(store, event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        get_store, register_in_group, register_store, reset_group, GlobalStore, Observable,
+        ObservableValue, Resettable,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static RESETS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone)]
+    struct SessionStore {
+        user: ObservableValue<Option<String>>,
+    }
+
+    impl Default for SessionStore {
+        fn default() -> Self {
+            Self {
+                user: ObservableValue::new(None),
+            }
+        }
+    }
+
+    impl Store for SessionStore {
+        fn id(&self) -> TypeId {
+            TypeId::of::<Self>()
+        }
+    }
+
+    impl Resettable for SessionStore {
+        fn reset_state(&self) {
+            self.user.assign(None);
+        }
+    }
+
+    impl StoreLifecycle for SessionStore {
+        fn on_reset(&self) {
+            // Sees the live store, before its state is reset
+            assert!(self.user.peek().is_some());
+            RESETS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn resets_run_the_hook_on_the_registered_store() {
+        register_lifecycle::<SessionStore>();
+        let store = SessionStore::default();
+        register_store(store.clone());
+
+        store.user.assign(Some("ada".to_string()));
+        SessionStore::reset();
+        assert_eq!(RESETS.load(Ordering::SeqCst), 1);
+        assert_eq!(store.user.peek(), None);
+
+        register_in_group("session", store.clone());
+        store.user.assign(Some("grace".to_string()));
+        reset_group("session");
+        assert_eq!(RESETS.load(Ordering::SeqCst), 2);
+        assert_eq!(get_store::<SessionStore>().unwrap().user.peek(), None);
+    }
+}
@@ -1,41 +1,105 @@
+/// Declares a store whose fields are observables, plus its actions.
+///
+/// A field without an initial value starts from `Default`. A field marked
+/// `#[store]` holds another store instead of an observable, for composing stores.
+/// Fields marked `#[persist]` are [persisted](crate::persist) under
+/// `"StoreName.field"` (feature `serde`); marking the store itself `#[persist]`
+/// persists every field except those marked `#[transient]`. A persisted field
+/// is persisted once, by the first instance, and shared by every instance.
+/// Getters and actions become public methods. Getters take only `&self`, and
+/// `#[cached]` ones keep their result until an observable they read changes.
+/// Actions may return values or be `async`. `is_loading()` tells whether an
+/// async action is in flight, and one taking `#[cancel] cancel: CancelToken`
+/// after `&self` cancels its previous call (see [`ActionTokens`](crate::ActionTokens)).
+/// Async actions without `&self` count in [`pending_actions`](crate::pending_actions) only.
+/// `Err` results of async actions are collected in [`errors`](crate::errors):
+///
+/// ```ignore
+/// multi_store! {
+///     AppStore {
+///         title: String = "Home".to_string(),
+///         #[store] profile: ProfileStore,
+///     }
+///
+///     getters {
+///         #[cached]
+///         fn heading(&self) -> String {
+///             format!("{} - {}", self.title.get(), self.profile.name.get())
+///         }
+///     }
+///
+///     actions {
+///         fn rename(&self, name: String) -> Result<(), String> {
+///             if name.is_empty() {
+///                 return Err("name is required".into());
+///             }
+///             self.profile.name.set(|n| *n = name);
+///             Ok(())
+///         }
+///
+///         async fn reload(&self) {
+///             let profile = api::fetch_profile().await;
+///             self.profile.name.set(|n| *n = profile.name);
+///         }
+///     }
+/// }
+/// ```
 #[macro_export]
 macro_rules! multi_store {
     (
-        $store_name:ident {
+        $(#[$store_kind:ident])? $store_name:ident {
             $(
-                $field_name:ident: $field_type:ty = $initial_value:expr
+                $(#[$field_kind:ident])? $field_name:ident: $field_type:ty $(= $initial_value:expr)?
             ),* $(,)?
         }
 
-        actions {
+        $(getters {
             $(
-                fn $method_name:ident(&self $(, $param_name:ident: $param_type:ty)*) {
-                    $($body:tt)*
-                }
+                $(#[$getter_kind:ident])? fn $getter_name:ident(&$getter_self:ident) -> $getter_type:ty $getter_body:block
             )*
+        })?
+
+        actions {
+            $($actions:tt)*
         }
     ) => {
         #[derive(Clone)]
         pub struct $store_name {
             $(
-                pub $field_name: $crate::ObservableValue<$field_type>,
+                pub $field_name: $crate::__store_field!(@type [$($field_kind)?] $field_type),
             )*
+            #[doc(hidden)]
+            pub __internals: $crate::StoreInternals,
         }
 
+        $($crate::__store_field!(@store_kind $store_kind);)?
+
         impl $store_name {
             pub fn new() -> Self {
-                Self {
-                    $(
-                        $field_name: $crate::observable($initial_value),
-                    )*
-                }
+                $crate::__store_field!(@construct [$($store_kind)?] $store_name {
+                    $([$($field_kind)?] $field_name: $field_type $(= $initial_value)?),*
+                } {
+                    __internals: $crate::StoreInternals::default(),
+                })
             }
 
-            $(
-                pub fn $method_name(&self $(, $param_name: $param_type)*) {
-                    $($body)*
-                }
-            )*
+            $($(
+                $crate::__store_getter!([$($getter_kind)?] $getter_self $getter_name $getter_type, $getter_body);
+            )*)?
+
+            $crate::__store_actions!($($actions)*);
+
+            /// Whether an async action of the store is in flight.
+            #[allow(dead_code)]
+            pub fn is_loading(&self) -> bool {
+                self.pending_count() > 0
+            }
+
+            /// The number of async actions of the store in flight.
+            #[allow(dead_code)]
+            pub fn pending_count(&self) -> usize {
+                self.__internals.actions.pending().get()
+            }
         }
 
         impl $crate::Store for $store_name {
@@ -43,6 +107,16 @@ macro_rules! multi_store {
                 std::any::TypeId::of::<$store_name>()
             }
         }
+
+        impl $crate::Resettable for $store_name {
+            fn reset_state(&self) {
+                $crate::batch(|| {
+                    $(
+                        $crate::__store_field!(@reset [$($field_kind)?] self.$field_name, $field_type, $($initial_value)?);
+                    )*
+                });
+            }
+        }
     };
 }
 
@@ -69,51 +143,150 @@ macro_rules! action {
     };
 }
 
+/// Runs the block now and whenever an observable it read changes; returns the
+/// [`Autorun`](crate::Autorun) that keeps it alive: `let saver = autorun!(save(&draft.text.get()));`
+#[macro_export]
+macro_rules! autorun {
+    ($($body:tt)*) => {
+        $crate::autorun(move || {
+            let _ = { $($body)* };
+        })
+    };
+}
+
+/// Like [`autorun!`], but tied to the current component and disposed when it unmounts:
+///
+/// ```ignore
+/// let draft = use_store::<DraftStore>();
+/// effect!(save_draft(&draft.text.get()));
+/// ```
+#[macro_export]
+macro_rules! effect {
+    ($($body:tt)*) => {
+        $crate::use_autorun(move || {
+            let _ = { $($body)* };
+        })
+    };
+}
+
+/// Starts a [`Flow`](crate::Flow) running the block; `?` on a step stops it:
+///
+/// ```ignore
+/// let onboarding = flow!(|flow| {
+///     flow.take::<AccountCreated>().await?;
+///     flow.until(move || profile.is_complete()).await?;
+///     dispatch(OnboardingDone);
+/// });
+/// ```
+#[macro_export]
+macro_rules! flow {
+    (|$flow:ident| $body:block) => {
+        $crate::start_flow(move |$flow: $crate::Flow| async move {
+            $body;
+            ::core::option::Option::Some(())
+        })
+    };
+}
+
+/// Makes a component re-render when the observables it reads change.
+///
+/// Takes any component signature: generics, lifetimes, where-clauses, props
+/// structs and attributes such as `#[component(no_case_check)]` are kept as written.
 #[macro_export]
 macro_rules! reaxive {
-    (
-        #[component]
-        $(#[$attr:meta])*
-        $vis:vis fn $name:ident($($param:ident: $param_type:ty),* $(,)?) -> Element {
-            $($body:tt)*
-        }
-    ) => {
-        #[component]
-        $(#[$attr])*
-        $vis fn $name($($param: $param_type),*) -> Element {
+    (@component [$($signature:tt)*] { $($body:tt)* }) => {
+        $($signature)* {
             let reaxive_update = dioxus::prelude::use_signal(|| 0u32);
 
             let _observer_context = dioxus::prelude::use_hook(|| {
                 let update_ui = {
                     let mut reaxive_update = reaxive_update.clone();
                     move || {
-                        reaxive_update.set(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos() as u32);
+                        let next = reaxive_update.peek().wrapping_add(1);
+                        reaxive_update.set(next);
                     }
                 };
 
-                $crate::ObserverContext::new(update_ui)
+                $crate::ObserverContext::new_untracked(update_ui)
             });
+            let _tracking = _observer_context.track();
 
             let _ = reaxive_update.read();
 
             $($body)*
         }
     };
+
+    // Parameters, an optional return type and the body: split without recursing.
+    (@signature [$($head:tt)*] ( $($params:tt)* ) $(-> $output:ty)? { $($body:tt)* }) => {
+        $crate::reaxive!(@component [$($head)* ( $($params)* ) $(-> $output)?] { $($body)* });
+    };
+
+    // Generics or a where-clause: the last token tree is the body, which no
+    // single pattern can tell apart from the tokens before it. Those are moved
+    // 32, then 8, then one at a time, so long signatures stay far below the
+    // default `recursion_limit`.
+    (@signature [$($signature:tt)*] $($rest:tt)+) => {
+        $crate::reaxive!(@split [$($signature)*] $($rest)+);
+    };
+
+    (@split [$($signature:tt)*] { $($body:tt)* }) => {
+        $crate::reaxive!(@component [$($signature)*] { $($body)* });
+    };
+
+    (
+        @split [$($signature:tt)*]
+        $t0:tt $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt
+        $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt
+        $t16:tt $t17:tt $t18:tt $t19:tt $t20:tt $t21:tt $t22:tt $t23:tt
+        $t24:tt $t25:tt $t26:tt $t27:tt $t28:tt $t29:tt $t30:tt $t31:tt
+        $($rest:tt)+
+    ) => {
+        $crate::reaxive!(@split [
+            $($signature)*
+            $t0 $t1 $t2 $t3 $t4 $t5 $t6 $t7
+            $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15
+            $t16 $t17 $t18 $t19 $t20 $t21 $t22 $t23
+            $t24 $t25 $t26 $t27 $t28 $t29 $t30 $t31
+        ] $($rest)+);
+    };
+
+    (@split [$($signature:tt)*] $a:tt $b:tt $c:tt $d:tt $e:tt $f:tt $g:tt $h:tt $($rest:tt)+) => {
+        $crate::reaxive!(@split [$($signature)* $a $b $c $d $e $f $g $h] $($rest)+);
+    };
+
+    (@split [$($signature:tt)*] $next:tt $($rest:tt)+) => {
+        $crate::reaxive!(@split [$($signature)* $next] $($rest)+);
+    };
+
+    (
+        $(#[$attr:meta])*
+        $vis:vis async fn $name:ident $($rest:tt)+
+    ) => {
+        $crate::reaxive!(@signature [$(#[$attr])* $vis async fn $name] $($rest)+);
+    };
+
+    (
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident $($rest:tt)+
+    ) => {
+        $crate::reaxive!(@signature [$(#[$attr])* $vis fn $name] $($rest)+);
+    };
 }
 
 #[macro_export]
 macro_rules! store {
     (
-        $store_name:ident {
+        $(#[$store_kind:ident])? $store_name:ident {
             $(
-                $field_name:ident: $field_type:ty = $initial_value:expr
+                $(#[$field_kind:ident])? $field_name:ident: $field_type:ty $(= $initial_value:expr)?
             ),* $(,)?
         }
     ) => {
         $crate::multi_store! {
-            $store_name {
+            $(#[$store_kind])? $store_name {
                 $(
-                    $field_name: $field_type = $initial_value
+                    $(#[$field_kind])? $field_name: $field_type $(= $initial_value)?
                 ),*
             }
 
@@ -122,55 +295,69 @@ macro_rules! store {
     };
 }
 
+/// Like [`multi_store!`], with the actions in an `impl` section.
 #[macro_export]
 macro_rules! store_with_actions {
     (
-        $store_name:ident {
+        $(#[$store_kind:ident])? $store_name:ident {
             $(
-                $field_name:ident: $field_type:ty = $initial_value:expr
+                $(#[$field_kind:ident])? $field_name:ident: $field_type:ty $(= $initial_value:expr)?
             ),* $(,)?
         }
 
-        impl {
+        $(getters {
             $(
-                fn $method_name:ident(&self $(, $param_name:ident: $param_type:ty)*) {
-                    $($body:tt)*
-                }
+                $(#[$getter_kind:ident])? fn $getter_name:ident(&$getter_self:ident) -> $getter_type:ty $getter_body:block
             )*
+        })?
+
+        impl {
+            $($actions:tt)*
         }
     ) => {
         $crate::multi_store! {
-            $store_name {
+            $(#[$store_kind])? $store_name {
                 $(
-                    $field_name: $field_type = $initial_value
+                    $(#[$field_kind])? $field_name: $field_type $(= $initial_value)?
                 ),*
             }
 
-            actions {
+            $(getters {
                 $(
-                    fn $method_name(&self $(, $param_name: $param_type)*) {
-                        $($body)*
-                    }
+                    $(#[$getter_kind])? fn $getter_name(&$getter_self) -> $getter_type $getter_body
                 )*
+            })?
+
+            actions {
+                $($actions)*
             }
         }
     };
 }
 
+/// Declares a store whose `new()` returns the shared global instance.
+///
+/// Fields are declared as in [`multi_store!`], including nested `#[store]` fields
+/// and `#[persist]`/`#[transient]`. Keyed instances share the persisted values.
+/// Stores built with it have the hidden `__internals` field of [`multi_store!`] stores.
 #[macro_export]
 macro_rules! reaxive_store {
     (
-        $name:ident {
+        $(#[$store_kind:ident])? $name:ident {
             $(
-                $field:ident: $type:ty = $default:expr
+                $(#[$kind:ident])? $field:ident: $type:ty $(= $default:expr)?
             ),* $(,)?
         }
     ) => {
+        $($crate::__store_field!(@store_kind $store_kind);)?
+
         #[derive(Clone)]
         pub struct $name {
             $(
-                pub $field: $crate::ObservableValue<$type>,
+                pub $field: $crate::__store_field!(@type [$($kind)?] $type),
             )*
+            #[doc(hidden)]
+            pub __internals: $crate::StoreInternals,
         }
 
         impl $name {
@@ -180,13 +367,18 @@ macro_rules! reaxive_store {
                 $crate::use_store::<Self>()
             }
 
+            /// The independent instance registered under `key`, e.g. one cart per panel
+            pub fn keyed(key: &str) -> Self {
+                $crate::use_keyed_store::<Self>(key)
+            }
+
             /// Internal method for creating actual instances (used by the store system)
             fn create_instance() -> Self {
-                Self {
-                    $(
-                        $field: $crate::observable($default),
-                    )*
-                }
+                $crate::__store_field!(@construct [$($store_kind)?] $name {
+                    $([$($kind)?] $field: $type $(= $default)?),*
+                } {
+                    __internals: $crate::StoreInternals::default(),
+                })
             }
         }
 
@@ -196,13 +388,458 @@ macro_rules! reaxive_store {
             }
         }
 
-        unsafe impl Send for $name {}
-        unsafe impl Sync for $name {}
-
         impl $crate::Store for $name {
             fn id(&self) -> std::any::TypeId {
                 std::any::TypeId::of::<$name>()
             }
         }
+
+        impl $crate::Resettable for $name {
+            fn reset_state(&self) {
+                $crate::batch(|| {
+                    $(
+                        $crate::__store_field!(@reset [$($kind)?] self.$field, $type, $($default)?);
+                    )*
+                });
+            }
+        }
+    };
+}
+
+/// Turns the `actions` of the store macros into public methods.
+///
+/// Every action is matched in one repetition, so stores may have any number of them.
+/// `fn` and `async` are matched as identifiers, as a keyword can't be optional
+/// in a repetition that has to tell both forms apart.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __store_actions {
+    ($(
+        $(#[$attr:meta])*
+        $head:ident $second:ident $($third:ident)? ( $($params:tt)* ) $(-> $ret:ty)? $body:block
+    )*) => {
+        $(
+            $crate::__store_action!([$(#[$attr])*] $head $second $($third)? ( $($params)* ) $(-> $ret)? $body);
+        )*
+    };
+}
+
+/// One action of [`__store_actions!`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __store_action {
+    ([$(#[$attr:meta])*] fn $name:ident($($params:tt)*) $(-> $ret:ty)? $body:block) => {
+        $(#[$attr])*
+        pub fn $name($($params)*) $(-> $ret)? $body
+    };
+
+    // The first parameter after `&self` marked `#[cancel]`, or typed `CancelToken`
+    ([$(#[$attr:meta])*] async fn $name:ident(&$self:ident, #[cancel] $cancel:ident: $cancel_type:ty $(, $($params:tt)*)?) $(-> $ret:ty)? $body:block) => {
+        $(#[$attr])*
+        pub async fn $name(&$self $(, $($params)*)?) $(-> $ret)? {
+            let _pending = $self.__internals.actions.start();
+            let $cancel: $cancel_type = $self.__internals.actions.begin(stringify!($name));
+            let result $(: $ret)? = async { $body }.await;
+            $crate::__report_action!(result, $name);
+            result
+        }
+    };
+
+    ([$(#[$attr:meta])*] async fn $name:ident(&$self:ident, $cancel:ident: CancelToken $($params:tt)*) $(-> $ret:ty)? $body:block) => {
+        $crate::__store_action!(
+            [$(#[$attr])*] async fn $name(&$self, #[cancel] $cancel: $crate::CancelToken $($params)*) $(-> $ret)? $body
+        );
+    };
+
+    ([$(#[$attr:meta])*] async fn $name:ident(&$self:ident $($params:tt)*) $(-> $ret:ty)? $body:block) => {
+        $(#[$attr])*
+        pub async fn $name(&$self $($params)*) $(-> $ret)? {
+            let _pending = $self.__internals.actions.start();
+            let result $(: $ret)? = async { $body }.await;
+            $crate::__report_action!(result, $name);
+            result
+        }
+    };
+
+    ([$(#[$attr:meta])*] async fn $name:ident($($params:tt)*) $(-> $ret:ty)? $body:block) => {
+        $(#[$attr])*
+        pub async fn $name($($params)*) $(-> $ret)? {
+            let _pending = $crate::PendingAction::global();
+            let result $(: $ret)? = async { $body }.await;
+            $crate::__report_action!(result, $name);
+            result
+        }
+    };
+
+    ([$(#[$attr:meta])*] $($action:tt)*) => {
+        compile_error!(concat!(
+            "expected `fn` or `async fn` store action, found `", stringify!($($action)*), "`"
+        ));
+    };
+}
+
+/// Adds the `Err` result of an async store action to [`errors`](crate::errors).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __report_action {
+    ($result:ident, $name:ident) => {{
+        #[allow(unused_imports)]
+        use $crate::__report::{ReportMessage, ReportNothing, ReportTyped};
+        (&&$crate::__report::ActionResult(&$result))
+            .report(std::any::type_name::<Self>(), stringify!($name));
+    }};
+}
+
+/// Turns a getter of the store macros into a public method, cached when marked `#[cached]`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __store_getter {
+    ([] $self:ident $name:ident $type:ty, $body:block) => {
+        pub fn $name(&$self) -> $type $body
+    };
+    ([cached] $self:ident $name:ident $type:ty, $body:block) => {
+        pub fn $name(&$self) -> $type {
+            $self.__internals.getters.get(stringify!($name), || $body)
+        }
+    };
+    ([$kind:ident] $self:ident $name:ident $type:ty, $body:block) => {
+        compile_error!(concat!("unknown getter kind `", stringify!($kind), "`, expected `#[cached]`"));
+    };
+}
+
+/// The type and initial value of a store field declared by the store macros.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __store_field {
+    (@type [] $type:ty) => { $crate::ObservableValue<$type> };
+    (@type [store] $type:ty) => { $type };
+    (@type [persist] $type:ty) => { $crate::ObservableValue<$type> };
+    (@type [transient] $type:ty) => { $crate::ObservableValue<$type> };
+    (@type [$kind:ident] $type:ty) => {
+        compile_error!(concat!(
+            "unknown store field kind `", stringify!($kind),
+            "`, expected `#[store]`, `#[persist]` or `#[transient]`"
+        ))
+    };
+
+    (@construct $store_kind:tt $name:ident {
+        $($field_kind:tt $field:ident: $type:ty $(= $initial:expr)?),*
+    } { $($internals:tt)* }) => {
+        Self {
+            $(
+                $field: $crate::__store_field!(@init $store_kind $field_kind $name.$field, $type, $($initial)?),
+            )*
+            $($internals)*
+        }
+    };
+
+    (@value $type:ty, $initial:expr) => { $initial };
+    (@value $type:ty,) => { <$type as ::std::default::Default>::default() };
+
+    (@init $store_kind:tt [store] $name:ident . $field:ident, $type:ty, $($initial:expr)?) => {
+        $crate::__store_field!(@value $type, $($initial)?)
+    };
+    // Persisted: fields marked `#[persist]`, and unmarked fields of a `#[persist]` store
+    (@init [$($store_kind:ident)?] [persist] $name:ident . $field:ident, $type:ty, $($initial:expr)?) => {
+        $crate::__persisted_field(concat!(stringify!($name), ".", stringify!($field)), || {
+            $crate::__store_field!(@value $type, $($initial)?)
+        })
+    };
+    (@init [persist] [] $name:ident . $field:ident, $type:ty, $($initial:expr)?) => {
+        $crate::__store_field!(@init [] [persist] $name.$field, $type, $($initial)?)
+    };
+    (@init $store_kind:tt [$(transient)?] $name:ident . $field:ident, $type:ty, $($initial:expr)?) => {
+        $crate::observable($crate::__store_field!(@value $type, $($initial)?))
+    };
+    (@init $store_kind:tt [$kind:ident] $name:ident . $field:ident, $type:ty, $($initial:expr)?) => { unreachable!() };
+
+    (@reset [store] $store:ident . $field:ident, $type:ty, $($initial:expr)?) => {
+        $crate::Resettable::reset_state(&$store.$field);
+    };
+    (@reset [$($kind:ident)?] $store:ident . $field:ident, $type:ty, $initial:expr) => {
+        $crate::Observable::assign(&$store.$field, $initial);
+    };
+    (@reset [$($kind:ident)?] $store:ident . $field:ident, $type:ty,) => {
+        $crate::Observable::assign(&$store.$field, <$type as ::std::default::Default>::default());
+    };
+
+    (@store_kind persist) => {};
+    (@store_kind $kind:ident) => {
+        compile_error!(concat!("unknown store kind `", stringify!($kind), "`, expected `#[persist]`"));
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        errors, pending_actions, run_in_request_context, ErrorSource, Observable, Resettable,
+        StoreContext,
     };
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::{Context, Poll, Waker};
+
+    static HEADING_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    crate::multi_store! {
+        ProfileStore {
+            name: String = "Ada".to_string(),
+            visits: u32,
+        }
+
+        getters {
+            #[cached]
+            fn heading(&self) -> String {
+                HEADING_RUNS.fetch_add(1, Ordering::SeqCst);
+                format!("{} ({})", self.name.get(), self.visits.get())
+            }
+        }
+
+        actions {
+            fn visit(&self) -> u32 {
+                self.visits.set(|n| *n += 1);
+                self.visits.peek()
+            }
+
+            async fn rename(&self, name: String) -> Result<bool, String> {
+                yield_now().await;
+                if name.is_empty() {
+                    return Err("name is required".to_string());
+                }
+                let loading = self.is_loading();
+                self.name.assign(name);
+                Ok(loading)
+            }
+
+            async fn search(&self, cancel: CancelToken, _query: &str) -> bool {
+                yield_now().await;
+                cancel.is_cancelled()
+            }
+
+            async fn suggest(&self, #[cancel] token: crate::CancelToken) -> bool {
+                yield_now().await;
+                token.is_cancelled()
+            }
+
+            async fn validate(name: String) -> Result<String, String> {
+                yield_now().await;
+                let busy = pending_actions().peek() > 0;
+                match name.trim() {
+                    "" => Err("name is blank".to_string()),
+                    trimmed if busy => Ok(trimmed.to_string()),
+                    _ => Err("not counted as pending".to_string()),
+                }
+            }
+        }
+    }
+
+    crate::store! {
+        AppStore {
+            title: String = "Home".to_string(),
+            #[store] profile: ProfileStore = ProfileStore::new(),
+        }
+    }
+
+    crate::reaxive_store! {
+        CartStore {
+            items: Vec<String>,
+        }
+    }
+
+    /// Returns `Pending` once, so actions can be observed mid-flight.
+    async fn yield_now() {
+        let mut yielded = false;
+        std::future::poll_fn(|cx| {
+            if std::mem::replace(&mut yielded, true) {
+                return Poll::Ready(());
+            }
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })
+        .await
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn fields_start_from_their_initial_values_and_reset_to_them() {
+        let app = AppStore::new();
+        assert_eq!(app.title.peek(), "Home");
+        assert_eq!(app.profile.name.peek(), "Ada");
+        assert_eq!(app.profile.visits.peek(), 0);
+
+        app.title.assign("Settings".to_string());
+        assert_eq!(app.profile.visit(), 1);
+        app.reset_state();
+        assert_eq!(app.title.peek(), "Home");
+        assert_eq!(app.profile.visits.peek(), 0);
+    }
+
+    #[test]
+    fn cached_getters_recompute_after_a_change() {
+        let profile = ProfileStore::new();
+        let runs = HEADING_RUNS.load(Ordering::SeqCst);
+        assert_eq!(profile.heading(), "Ada (0)");
+        assert_eq!(profile.heading(), "Ada (0)");
+        assert_eq!(HEADING_RUNS.load(Ordering::SeqCst), runs + 1);
+
+        profile.visit();
+        assert_eq!(profile.heading(), "Ada (1)");
+        assert_eq!(HEADING_RUNS.load(Ordering::SeqCst), runs + 2);
+    }
+
+    #[test]
+    fn async_actions_are_pending_until_they_finish() {
+        let profile = ProfileStore::new();
+        assert!(!profile.is_loading());
+
+        assert_eq!(block_on(profile.rename("Grace".to_string())), Ok(true));
+        assert_eq!(profile.name.peek(), "Grace");
+        assert_eq!(profile.pending_count(), 0);
+    }
+
+    #[test]
+    fn failed_async_actions_are_reported() {
+        let profile = ProfileStore::new();
+        let result = block_on(profile.rename(String::new()));
+
+        assert_eq!(result, Err("name is required".to_string()));
+        assert!(errors().peek().iter().any(|entry| {
+            matches!(entry.source, ErrorSource::Action { store, action: "rename" } if store.ends_with("ProfileStore"))
+                && entry.message == "name is required"
+        }));
+    }
+
+    #[test]
+    fn cancellable_actions_cancel_their_previous_call() {
+        let profile = ProfileStore::new();
+        let mut first = pin!(profile.search("a"));
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(first.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(profile.pending_count(), 1);
+
+        assert!(!block_on(profile.search("ab")));
+        assert_eq!(first.as_mut().poll(&mut cx), Poll::Ready(true));
+        assert!(!profile.is_loading());
+    }
+
+    #[test]
+    fn reaxive_stores_are_shared_per_context_and_key() {
+        run_in_request_context(StoreContext::new(), || {
+            CartStore::new().items.push("apple".to_string());
+            assert_eq!(CartStore::new().items.peek(), ["apple"]);
+
+            let keyed = CartStore::keyed("sidebar");
+            assert!(keyed.items.peek().is_empty());
+            keyed.items.push("pear".to_string());
+            assert_eq!(CartStore::keyed("sidebar").items.peek(), ["pear"]);
+        });
+
+        run_in_request_context(StoreContext::new(), || {
+            assert!(CartStore::new().items.peek().is_empty());
+        });
+    }
+
+    #[test]
+    fn cancel_tokens_can_be_marked_with_any_type_path() {
+        let profile = ProfileStore::new();
+        let mut first = pin!(profile.suggest());
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(first.as_mut().poll(&mut cx).is_pending());
+
+        assert!(!block_on(profile.suggest()));
+        assert_eq!(first.as_mut().poll(&mut cx), Poll::Ready(true));
+    }
+
+    #[test]
+    fn async_actions_without_self_are_pending_and_reported() {
+        assert_eq!(
+            block_on(ProfileStore::validate(" Ada ".to_string())),
+            Ok("Ada".to_string())
+        );
+
+        assert!(block_on(ProfileStore::validate(" ".to_string())).is_err());
+        assert!(errors().peek().iter().any(|entry| {
+            matches!(
+                entry.source,
+                ErrorSource::Action {
+                    action: "validate",
+                    ..
+                }
+            ) && entry.message == "name is blank"
+        }));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn persisted_fields_are_persisted_once_and_shared() {
+        crate::reaxive_store! {
+            #[persist]
+            DraftStore {
+                text: String = "draft".to_string(),
+                #[transient]
+                cursor: usize,
+            }
+        }
+
+        let backend = crate::persistence::tests::MemoryBackend::default();
+        crate::set_storage_backend(backend.clone());
+
+        let (first, second) = run_in_request_context(StoreContext::new(), || {
+            (DraftStore::new(), DraftStore::keyed("other"))
+        });
+        let third = run_in_request_context(StoreContext::new(), DraftStore::new);
+        first.text.assign("saved".to_string());
+
+        assert_eq!(second.text.peek(), "saved");
+        assert_eq!(third.text.peek(), "saved");
+        assert!(backend.raw("DraftStore.text").is_some());
+        assert!(backend.raw("DraftStore.cursor").is_none());
+
+        first.cursor.assign(3);
+        assert_eq!(third.cursor.peek(), 0);
+    }
+
+    #[cfg(feature = "dioxus")]
+    mod components {
+        use crate::{Observable, ObservableValue};
+        use dioxus::prelude::*;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::LazyLock;
+
+        static COUNT: LazyLock<ObservableValue<u32>> = LazyLock::new(|| ObservableValue::new(0));
+        static RENDERS: AtomicUsize = AtomicUsize::new(0);
+
+        crate::reaxive! {
+            fn counter<T>() -> Element
+            where
+                T: Default + Send + Sync + 'static,
+            {
+                RENDERS.fetch_add(1, Ordering::SeqCst);
+                let count = COUNT.get();
+                rsx! { "{count}" }
+            }
+        }
+
+        #[test]
+        fn generic_components_rerender_when_what_they_read_changes() {
+            let mut dom = VirtualDom::new(counter::<u8>);
+            dom.rebuild_in_place();
+            assert_eq!(RENDERS.load(Ordering::SeqCst), 1);
+            assert_eq!(COUNT.observer_count(), 1);
+
+            dom.in_runtime(|| COUNT.assign(1));
+            dom.render_immediate_to_vec();
+            assert_eq!(RENDERS.load(Ordering::SeqCst), 2);
+        }
+    }
 }
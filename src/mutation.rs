@@ -0,0 +1,73 @@
+use crate::ObservableValue;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Applies `new_value` immediately, then awaits `operation`.
+///
+/// If the operation fails, or the future is dropped before it completes, the
+/// field is restored to the value it had before, unless something else wrote
+/// to it in the meantime. The error is returned to the caller.
+pub async fn optimistic<T, R, E, Fut>(
+    field: &ObservableValue<T>,
+    new_value: T,
+    operation: Fut,
+) -> Result<R, E>
+where
+    T: Clone + 'static,
+    Fut: Future<Output = Result<R, E>>,
+{
+    optimistic_update(field, move |value| *value = new_value, operation).await
+}
+
+/// Like [`optimistic`], but applies an in-place update instead of a new value.
+pub async fn optimistic_update<T, R, E, F, Fut>(
+    field: &ObservableValue<T>,
+    updater: F,
+    operation: Fut,
+) -> Result<R, E>
+where
+    T: Clone + 'static,
+    F: FnOnce(&mut T),
+    Fut: Future<Output = Result<R, E>>,
+{
+    let (previous, optimistic) = field.set_snapshot(updater);
+    let mut rollback = Rollback {
+        field,
+        previous: (!Arc::ptr_eq(&previous, &optimistic)).then_some(previous),
+        optimistic,
+    };
+
+    let result = operation.await;
+    if result.is_ok() {
+        rollback.previous = None;
+    }
+    result
+}
+
+/// Restores the previous value when dropped while still armed, so a failed
+/// or cancelled operation rolls back. Holding the optimistic snapshot makes
+/// any later write replace it, which is how a newer write is detected.
+struct Rollback<'a, T: Clone + 'static> {
+    field: &'a ObservableValue<T>,
+    previous: Option<Arc<T>>,
+    optimistic: Arc<T>,
+}
+
+impl<T: Clone + 'static> Drop for Rollback<'_, T> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            self.field
+                .assign_if_unchanged(&self.optimistic, T::clone(&previous));
+        }
+    }
+}
+
+impl<T: Clone + 'static> ObservableValue<T> {
+    /// Optimistically assigns `new_value`, rolling back if `operation` fails
+    pub async fn set_optimistic<R, E, Fut>(&self, new_value: T, operation: Fut) -> Result<R, E>
+    where
+        Fut: Future<Output = Result<R, E>>,
+    {
+        optimistic(self, new_value, operation).await
+    }
+}
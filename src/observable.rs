@@ -1,36 +1,399 @@
+use crate::history::History;
+use crate::intercept::Interceptor;
+use crate::scheduler;
+use crate::stats::{self, Counters};
+use crate::sync_observer::SyncObserver;
+use crate::validation::{Validation, WriteError, WriteOutcome};
+#[cfg(feature = "dioxus")]
 use dioxus::prelude::{Readable, Writable};
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::ops::{AddAssign, SubAssign};
-use std::rc::{Rc, Weak};
-use std::sync::{Arc, Mutex};
+use std::borrow::Borrow;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::hash::Hash;
+use std::ops::{AddAssign, MulAssign, SubAssign};
+use std::rc::{self, Rc};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, RwLock, Weak};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, SystemTime};
 
 thread_local! {
-    static CURRENT_OBSERVER: RefCell<Option<Rc<RefCell<dyn FnMut()>>>> = RefCell::new(None);
+    // Innermost entry is the current observer; `None` entries mark untracked scopes
+    static OBSERVER_STACK: RefCell<Vec<Option<Tracker>>> = const { RefCell::new(Vec::new()) };
+    // Observers created on this thread, by id
+    static LOCAL_OBSERVERS: RefCell<HashMap<u64, rc::Weak<Observer>>> = RefCell::new(HashMap::new());
+    // Dropped when the thread exits, which marks its observers' entries dead
+    static THREAD_ALIVE: Arc<()> = Arc::new(());
+}
+
+/// The observers tracking an observable.
+pub(crate) type ObserverList = Mutex<Vec<ObserverEntry>>;
+
+/// Entry in an observable's observer list.
+///
+/// Entries are matched by the observer's id rather than its address, because
+/// a dropped observer's allocation can be reused by a new one.
+pub(crate) enum ObserverEntry {
+    /// A reactive observer; it is not `Send`, so only the thread that created it can notify it.
+    Local {
+        id: u64,
+        thread: ThreadId,
+        thread_alive: Weak<()>,
+    },
+    Sync {
+        id: u64,
+        observer: Weak<SyncObserver>,
+    },
+}
+
+impl ObserverEntry {
+    fn id(&self) -> u64 {
+        match self {
+            Self::Local { id, .. } | Self::Sync { id, .. } => *id,
+        }
+    }
+
+    /// False once the observer is dropped. Local observers of other threads
+    /// are assumed alive until their thread exits.
+    fn is_alive(&self, current: ThreadId) -> bool {
+        match self {
+            Self::Local {
+                thread,
+                thread_alive,
+                ..
+            } if *thread != current => thread_alive.strong_count() > 0,
+            Self::Local { id, .. } => LOCAL_OBSERVERS
+                .try_with(|observers| {
+                    observers
+                        .borrow()
+                        .get(id)
+                        .is_some_and(|observer| observer.strong_count() > 0)
+                })
+                .unwrap_or(false),
+            Self::Sync { observer, .. } => observer.strong_count() > 0,
+        }
+    }
+
+    /// The observer, if it is alive and can be notified from this thread.
+    fn upgrade(&self, current: ThreadId) -> Option<Tracker> {
+        match self {
+            Self::Local { id, thread, .. } if *thread == current => LOCAL_OBSERVERS
+                .try_with(|observers| observers.borrow().get(id).and_then(rc::Weak::upgrade))
+                .ok()
+                .flatten()
+                .map(Tracker::Local),
+            Self::Local { .. } => None,
+            Self::Sync { observer, .. } => observer.upgrade().map(Tracker::Sync),
+        }
+    }
+}
+
+/// Removes observer `id` from `list`, unless the observable is gone.
+pub(crate) fn detach(list: &Weak<ObserverList>, id: u64) {
+    if let Some(list) = list.upgrade() {
+        let current = thread::current().id();
+        list.lock()
+            .unwrap()
+            .retain(|entry| entry.id() != id && entry.is_alive(current));
+    }
+}
+
+static NEXT_OBSERVER_ID: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) fn next_observer_id() -> u64 {
+    NEXT_OBSERVER_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// An observer on the tracking stack: reads made while it is current subscribe it.
+#[derive(Clone)]
+pub(crate) enum Tracker {
+    Local(Rc<Observer>),
+    Sync(Arc<SyncObserver>),
+}
+
+impl Tracker {
+    fn is(&self, other: &Tracker) -> bool {
+        match (self, other) {
+            (Self::Local(a), Self::Local(b)) => Rc::ptr_eq(a, b),
+            (Self::Sync(a), Self::Sync(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
+    fn priority(&self) -> Priority {
+        match self {
+            Self::Local(observer) => observer.priority,
+            Self::Sync(observer) => observer.priority,
+        }
+    }
+
+    fn notify(&self) {
+        match self {
+            Self::Local(observer) => observer.notify(),
+            Self::Sync(observer) => observer.notify(),
+        }
+    }
+
+    fn clear_dependencies(&self) {
+        match self {
+            Self::Local(observer) => observer.clear_dependencies(),
+            Self::Sync(observer) => observer.clear_dependencies(),
+        }
+    }
+
+    fn entry(&self) -> ObserverEntry {
+        match self {
+            Self::Local(observer) => ObserverEntry::Local {
+                id: observer.id,
+                thread: thread::current().id(),
+                thread_alive: THREAD_ALIVE.try_with(Arc::downgrade).unwrap_or_default(),
+            },
+            Self::Sync(observer) => ObserverEntry::Sync {
+                id: observer.id,
+                observer: Arc::downgrade(observer),
+            },
+        }
+    }
+
+    fn add_dependency(&self, list: Weak<ObserverList>) {
+        match self {
+            Self::Local(observer) => observer.dependencies.borrow_mut().push(list),
+            Self::Sync(observer) => observer.add_dependency(list),
+        }
+    }
+}
+
+/// A reaction that re-runs when one of the observables it read changes.
+///
+/// The observer keeps the list of observables it read during its current
+/// tracking pass, so starting a new pass can detach it from observables it
+/// no longer reads.
+pub(crate) struct Observer {
+    id: u64,
+    priority: Priority,
+    callback: RefCell<Box<dyn FnMut()>>,
+    // Set when notified while the callback is running, so it runs once more afterwards
+    pending: Cell<bool>,
+    dependencies: RefCell<Vec<Weak<ObserverList>>>,
+}
+
+/// Re-runs allowed for an observer that keeps invalidating itself.
+pub(crate) const MAX_OBSERVER_RERUNS: usize = 100;
+
+impl Observer {
+    pub(crate) fn new<F: FnMut() + 'static>(callback: F) -> Rc<Self> {
+        Self::with_priority(Priority::Render, callback)
+    }
+
+    pub(crate) fn with_priority<F: FnMut() + 'static>(priority: Priority, callback: F) -> Rc<Self> {
+        let observer = Rc::new(Self {
+            id: next_observer_id(),
+            priority,
+            callback: RefCell::new(Box::new(callback)),
+            pending: Cell::new(false),
+            dependencies: RefCell::new(Vec::new()),
+        });
+        LOCAL_OBSERVERS.with(|observers| {
+            observers
+                .borrow_mut()
+                .insert(observer.id, Rc::downgrade(&observer))
+        });
+        observer
+    }
+
+    /// Runs the callback. A notification arriving while it runs is not
+    /// nested but makes it run again once it returns.
+    pub(crate) fn notify(&self) {
+        let Ok(mut callback) = self.callback.try_borrow_mut() else {
+            self.pending.set(true);
+            return;
+        };
+
+        for _ in 0..=MAX_OBSERVER_RERUNS {
+            callback();
+            if !self.pending.replace(false) {
+                break;
+            }
+        }
+    }
+
+    /// Detaches from every observable read during the previous tracking pass.
+    pub(crate) fn clear_dependencies(&self) {
+        let dependencies = std::mem::take(&mut *self.dependencies.borrow_mut());
+
+        for dependency in dependencies {
+            detach(&dependency, self.id);
+        }
+    }
+
+    pub(crate) fn dependency_count(&self) -> usize {
+        self.dependencies
+            .borrow()
+            .iter()
+            .filter(|dependency| dependency.strong_count() > 0)
+            .count()
+    }
+}
+
+impl Drop for Observer {
+    fn drop(&mut self) {
+        // The thread-local map is already gone if the thread is exiting
+        let _ = LOCAL_OBSERVERS.try_with(|observers| observers.borrow_mut().remove(&self.id));
+        for dependency in self.dependencies.get_mut().drain(..) {
+            detach(&dependency, self.id);
+        }
+    }
+}
+
+fn is_entry(entry: &Option<Tracker>, observer: Option<&Tracker>) -> bool {
+    match (entry, observer) {
+        (Some(entry), Some(observer)) => entry.is(observer),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn is_local_entry(entry: &Option<Tracker>, observer: &Rc<Observer>) -> bool {
+    matches!(entry, Some(Tracker::Local(entry)) if Rc::ptr_eq(entry, observer))
+}
+
+fn push_observer(observer: Option<Tracker>) {
+    OBSERVER_STACK.with(|stack| stack.borrow_mut().push(observer));
+}
+
+/// Removes the innermost entry for `observer`, even if scopes ended out of order.
+fn pop_observer(observer: Option<&Tracker>) {
+    OBSERVER_STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        if let Some(index) = stack.iter().rposition(|entry| is_entry(entry, observer)) {
+            stack.remove(index);
+        }
+    });
+}
+
+pub(crate) fn current_observer() -> Option<Tracker> {
+    OBSERVER_STACK.with(|stack| stack.borrow().last().cloned().flatten())
+}
+
+/// Runs `f` with `observer` as the current observer, restoring the previous one afterwards.
+pub(crate) fn with_observer<R>(observer: Option<Rc<Observer>>, f: impl FnOnce() -> R) -> R {
+    let observer = observer.map(Tracker::Local);
+    push_observer(observer.clone());
+    let _scope = TrackingScope { observer };
+    f()
+}
+
+/// Starts a fresh tracking pass for `tracker` that ends when the scope is dropped.
+pub(crate) fn begin_tracking(tracker: Tracker) -> TrackingScope {
+    tracker.clear_dependencies();
+    push_observer(Some(tracker.clone()));
+    TrackingScope {
+        observer: Some(tracker),
+    }
+}
+
+/// Starts a fresh tracking pass for `observer` and runs `f` inside it.
+pub(crate) fn track<R>(observer: &Rc<Observer>, f: impl FnOnce() -> R) -> R {
+    observer.clear_dependencies();
+    with_observer(Some(observer.clone()), f)
+}
+
+/// Runs `f` without subscribing the current observer to anything it reads.
+pub fn untracked<R>(f: impl FnOnce() -> R) -> R {
+    with_observer(None, f)
+}
+
+/// Whether reads on this thread are currently being tracked.
+pub fn is_tracking() -> bool {
+    current_observer().is_some()
 }
 
 #[derive(Clone)]
 pub struct ObserverContext {
-    observer: Rc<RefCell<dyn FnMut()>>,
+    observer: Rc<Observer>,
 }
 
 impl ObserverContext {
     pub fn new<F: FnMut() + 'static>(update_fn: F) -> Self {
-        let observer = Rc::new(RefCell::new(update_fn));
+        let context = Self::new_untracked(update_fn);
+        context.begin_tracking();
+        context
+    }
 
-        CURRENT_OBSERVER.with(|current| {
-            *current.borrow_mut() = Some(observer.clone());
-        });
+    /// Creates a context without making it the current observer.
+    pub fn new_untracked<F: FnMut() + 'static>(update_fn: F) -> Self {
+        Self {
+            observer: Observer::new(update_fn),
+        }
+    }
 
-        Self { observer }
+    /// Forgets the observables read so far and records new reads from now on.
+    ///
+    /// Call at the start of every render so the component only depends on
+    /// the observables its latest render actually read. Pair with
+    /// [`ObserverContext::end_tracking`], or use [`ObserverContext::track`].
+    pub fn begin_tracking(&self) {
+        self.observer.clear_dependencies();
+        if !self.is_current() {
+            push_observer(Some(Tracker::Local(self.observer.clone())));
+        }
+    }
+
+    /// Ends this context's innermost tracking pass, making the enclosing observer current again.
+    pub fn end_tracking(&self) {
+        pop_observer(Some(&Tracker::Local(self.observer.clone())));
+    }
+
+    /// Starts a tracking pass that ends when the returned scope is dropped.
+    ///
+    /// Scopes nest, so a reactive scope opened inside another one only
+    /// records reads until it is dropped.
+    pub fn track(&self) -> TrackingScope {
+        begin_tracking(Tracker::Local(self.observer.clone()))
+    }
+
+    pub fn is_current(&self) -> bool {
+        is_local_entry(&current_observer(), &self.observer)
+    }
+
+    /// Number of observables read during the current tracking pass.
+    pub fn dependency_count(&self) -> usize {
+        self.observer.dependency_count()
     }
 }
 
 impl Drop for ObserverContext {
     fn drop(&mut self) {
-        CURRENT_OBSERVER.with(|current| {
-            *current.borrow_mut() = None;
+        let on_stack = OBSERVER_STACK.with(|stack| {
+            stack
+                .borrow()
+                .iter()
+                .filter(|entry| is_local_entry(entry, &self.observer))
+                .count()
         });
+
+        // Only stack entries and this handle are left: nothing can end those passes anymore
+        if on_stack > 0 && Rc::strong_count(&self.observer) == on_stack + 1 {
+            OBSERVER_STACK.with(|stack| {
+                stack
+                    .borrow_mut()
+                    .retain(|entry| !is_local_entry(entry, &self.observer))
+            });
+        }
+    }
+}
+
+/// Ends a tracking pass when dropped, making the enclosing observer current again.
+#[must_use]
+pub struct TrackingScope {
+    observer: Option<Tracker>,
+}
+
+impl Drop for TrackingScope {
+    fn drop(&mut self) {
+        pop_observer(self.observer.as_ref());
     }
 }
 
@@ -44,21 +407,252 @@ pub trait Observable<T: Clone + 'static> {
     fn unsubscribe(&self, id: usize);
 }
 
+/// Values that [`ObservableValue::clear`] can empty.
+pub trait Clearable {
+    fn clear(&mut self);
+}
+
+impl<U> Clearable for Vec<U> {
+    fn clear(&mut self) {
+        Vec::clear(self);
+    }
+}
+
+impl<U> Clearable for VecDeque<U> {
+    fn clear(&mut self) {
+        VecDeque::clear(self);
+    }
+}
+
+impl Clearable for String {
+    fn clear(&mut self) {
+        String::clear(self);
+    }
+}
+
+impl<K, V, S> Clearable for HashMap<K, V, S> {
+    fn clear(&mut self) {
+        HashMap::clear(self);
+    }
+}
+
+impl<U, S> Clearable for HashSet<U, S> {
+    fn clear(&mut self) {
+        HashSet::clear(self);
+    }
+}
+
+impl<K, V> Clearable for BTreeMap<K, V> {
+    fn clear(&mut self) {
+        BTreeMap::clear(self);
+    }
+}
+
+impl<U> Clearable for BTreeSet<U> {
+    fn clear(&mut self) {
+        BTreeSet::clear(self);
+    }
+}
+
+/// Entries under ids that are never reused, iterated in insertion order.
+///
+/// A stale or repeated removal can't hit an entry added later.
+pub(crate) struct IdMap<V> {
+    next_id: usize,
+    entries: BTreeMap<usize, V>,
+}
+
+impl<V> Default for IdMap<V> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            entries: BTreeMap::new(),
+        }
+    }
+}
+
+impl<V> IdMap<V> {
+    pub(crate) fn insert(&mut self, value: V) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.insert(id, value);
+        id
+    }
+
+    pub(crate) fn remove(&mut self, id: usize) -> Option<V> {
+        self.entries.remove(&id)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (usize, &V)> {
+        self.entries.iter().map(|(id, value)| (*id, value))
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+type Subscriber<T> = Arc<dyn Fn(&T) + Send + Sync>;
+type Subscribers<T> = IdMap<(Priority, Subscriber<T>)>;
+
+fn remove_subscriber<T>(subscribers: &Mutex<Subscribers<T>>, counters: &Counters, id: usize) {
+    if subscribers.lock().unwrap().remove(id).is_some() {
+        counters.unsubscribed();
+    }
+}
+
+/// Order in which a write reaches subscribers and observers: derived state
+/// first, so effects and renders never see it stale.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Computed values and other derived state.
+    Computed,
+    /// Side effects; the priority of [`Observable::subscribe`].
+    #[default]
+    Effect,
+    /// Component re-renders.
+    Render,
+}
+
+enum Delivery<T> {
+    Subscriber(Subscriber<T>),
+    Observer(Tracker),
+}
+
+/// Delivery state shared by the clones of an observable.
+#[derive(Default)]
+struct NotifyState {
+    // The thread delivering, if any
+    running: Option<ThreadId>,
+    pending: bool,
+}
+
+/// Serializes the writes of an observable, so that reading the value, running
+/// the interceptors and validators, and storing the result is one step. A write
+/// made by an interceptor or validator on the thread already writing goes
+/// through instead of deadlocking.
+#[derive(Default)]
+struct WriteLock {
+    owner: Mutex<Option<ThreadId>>,
+    released: Condvar,
+}
+
+struct WriteGuard<'a>(&'a WriteLock);
+
+impl WriteLock {
+    /// Waits for other threads' writes; `None` if this thread already holds the lock.
+    fn lock(&self) -> Option<WriteGuard<'_>> {
+        let current = thread::current().id();
+        let mut owner = self.owner.lock().unwrap();
+        if *owner == Some(current) {
+            return None;
+        }
+        while owner.is_some() {
+            owner = self.released.wait(owner).unwrap();
+        }
+        *owner = Some(current);
+        Some(WriteGuard(self))
+    }
+}
+
+impl Drop for WriteGuard<'_> {
+    fn drop(&mut self) {
+        *self.0.owner.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        self.0.released.notify_one();
+    }
+}
+
+/// The pending write of [`ObservableValue::set_debounced`], shared by the clones of an observable.
+struct Debounce<T> {
+    // Bumped by every write and debounced write, so a pending value is only
+    // applied if nothing was written after it
+    generation: AtomicU64,
+    state: Mutex<DebounceState<T>>,
+}
+
+struct DebounceState<T> {
+    value: Option<T>,
+    generation: u64,
+    deadline: SystemTime,
+    // Whether a future of `set_debounced` is waiting for the deadline
+    timer: bool,
+}
+
+impl<T> Default for Debounce<T> {
+    fn default() -> Self {
+        Self {
+            generation: AtomicU64::new(0),
+            state: Mutex::new(DebounceState {
+                value: None,
+                generation: 0,
+                deadline: SystemTime::UNIX_EPOCH,
+                timer: false,
+            }),
+        }
+    }
+}
+
+/// Lets another debounced write start a timer if the waiting future is dropped.
+struct DebounceTimer<T>(Option<Arc<Debounce<T>>>);
+
+impl<T> Drop for DebounceTimer<T> {
+    fn drop(&mut self) {
+        if let Some(debounce) = self.0.take() {
+            debounce.state.lock().unwrap().timer = false;
+        }
+    }
+}
+
+/// Marks delivery finished even if a callback panics.
+struct NotifyGuard<'a>(&'a Mutex<NotifyState>);
+
+impl Drop for NotifyGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        state.running = None;
+        state.pending = false;
+    }
+}
+
+/// A value that notifies subscribers and reactive observers when written.
+///
+/// Clones share the value. It is `Send + Sync` whenever `T` is, so stores
+/// made of observables can be shared with other threads.
 #[derive(Clone)]
 pub struct ObservableValue<T: Clone + 'static> {
-    value: Arc<Mutex<T>>,
-    subscribers: Arc<Mutex<HashMap<usize, Box<dyn Fn(&T) + Send + Sync>>>>,
-    next_id: Arc<Mutex<usize>>,
-    local_subscribers: Rc<RefCell<Vec<Weak<RefCell<dyn FnMut()>>>>>,
+    // Readers share the lock, so concurrent reads (SSR, background tasks) don't contend.
+    // The value is an `Arc` snapshot: readers and notifications share it, and
+    // writes only copy it while a snapshot is still held somewhere.
+    value: Arc<RwLock<Arc<T>>>,
+    writer: Arc<WriteLock>,
+    subscribers: Arc<Mutex<Subscribers<T>>>,
+    notify_state: Arc<Mutex<NotifyState>>,
+    observers: Arc<ObserverList>,
+    pub(crate) validation: Arc<Mutex<Option<Validation<T>>>>,
+    pub(crate) interceptors: Arc<Mutex<IdMap<Interceptor<T>>>>,
+    pub(crate) history: Arc<Mutex<Option<History<T>>>>,
+    pub(crate) counters: Arc<Counters>,
+    debounce: Arc<Debounce<T>>,
 }
 
 impl<T: Clone + 'static> ObservableValue<T> {
     pub fn new(initial: T) -> Self {
+        stats::observable_created();
         Self {
-            value: Arc::new(Mutex::new(initial)),
-            subscribers: Arc::new(Mutex::new(HashMap::new())),
-            next_id: Arc::new(Mutex::new(0)),
-            local_subscribers: Rc::new(RefCell::new(Vec::new())),
+            value: Arc::new(RwLock::new(Arc::new(initial))),
+            writer: Arc::new(WriteLock::default()),
+            subscribers: Arc::new(Mutex::new(IdMap::default())),
+            notify_state: Arc::new(Mutex::new(NotifyState::default())),
+            observers: Arc::new(Mutex::new(Vec::new())),
+            validation: Arc::new(Mutex::new(None)),
+            interceptors: Arc::new(Mutex::new(IdMap::default())),
+            history: Arc::new(Mutex::new(None)),
+            counters: Arc::new(Counters::default()),
+            debounce: Arc::new(Debounce::default()),
         }
     }
 
@@ -66,6 +660,22 @@ impl<T: Clone + 'static> ObservableValue<T> {
         self.get()
     }
 
+    /// Read the value without subscribing the current observer
+    pub fn peek(&self) -> T {
+        T::clone(&self.value.read().unwrap())
+    }
+
+    /// Shared snapshot of the value, without cloning it: cheap for large values
+    pub fn get_arc(&self) -> Arc<T> {
+        self.track_access();
+        self.value.read().unwrap().clone()
+    }
+
+    /// Untracked [`ObservableValue::get_arc`]
+    pub fn peek_arc(&self) -> Arc<T> {
+        self.value.read().unwrap().clone()
+    }
+
     pub fn set_value(&self, value: T) {
         self.assign(value);
     }
@@ -85,22 +695,64 @@ impl<T: Clone + 'static> ObservableValue<T> {
         self.unsubscribe(id);
     }
 
+    /// Like [`Observable::subscribe`], delivered before subscribers and
+    /// observers of a later [`Priority`]: `total.subscribe_with_priority(Priority::Computed, ..)`
+    pub fn subscribe_with_priority<F>(&self, priority: Priority, callback: F) -> usize
+    where
+        F: Fn(&T) + Send + Sync + 'static,
+    {
+        let id = self
+            .subscribers
+            .lock()
+            .unwrap()
+            .insert((priority, Arc::new(callback)));
+        self.counters.subscribed();
+        id
+    }
+
+    /// Subscribes for as long as `owner` lives, without keeping it alive:
+    /// `prices.subscribe_weak(&service, |service, prices| service.reprice(prices))`
+    ///
+    /// The subscription removes itself on the first write after `owner` is dropped.
+    pub fn subscribe_weak<O, F>(&self, owner: &Arc<O>, callback: F) -> usize
+    where
+        O: Send + Sync + 'static,
+        F: Fn(&O, &T) + Send + Sync + 'static,
+    {
+        let owner = Arc::downgrade(owner);
+        let subscribers = Arc::downgrade(&self.subscribers);
+        let counters = self.counters.clone();
+        let own_id = Arc::new(OnceLock::new());
+
+        let id = self.subscribe({
+            let own_id = own_id.clone();
+            move |value| match owner.upgrade() {
+                Some(owner) => callback(&owner, value),
+                None => {
+                    if let (Some(subscribers), Some(id)) = (subscribers.upgrade(), own_id.get()) {
+                        remove_subscriber(&subscribers, &counters, *id);
+                    }
+                }
+            }
+        });
+        let _ = own_id.set(id);
+        id
+    }
+
     pub fn map<U, F>(&self, mapper: F) -> U
     where
         F: FnOnce(&T) -> U,
     {
-        self.track_access();
-        let value = self.value.lock().unwrap();
-        mapper(&*value)
+        let value = self.get_arc();
+        mapper(&value)
     }
 
     pub fn when<F>(&self, predicate: F) -> bool
     where
         F: FnOnce(&T) -> bool,
     {
-        self.track_access();
-        let value = self.value.lock().unwrap();
-        predicate(&*value)
+        let value = self.get_arc();
+        predicate(&value)
     }
 
     // Красивые методы для частых операций
@@ -137,6 +789,14 @@ impl<T: Clone + 'static> ObservableValue<T> {
         self.set(|val| *val -= amount);
     }
 
+    /// Multiply by a factor: price.mul(2) instead of price.set(|p| *p *= 2)
+    pub fn mul(&self, factor: T)
+    where
+        T: MulAssign<T>,
+    {
+        self.set(|val| *val *= factor);
+    }
+
     /// Toggle boolean values: flag.toggle() instead of flag.set(|f| *f = !*f)
     pub fn toggle(&self)
     where
@@ -155,91 +815,556 @@ impl<T: Clone + 'static> ObservableValue<T> {
     }
 
     /// Clear collections: items.clear() instead of items.set(|v| v.clear())
-    pub fn clear<U>(&self)
+    pub fn clear(&self)
     where
-        T: AsMut<Vec<U>>,
+        T: Clearable,
     {
-        self.set(|vec| vec.as_mut().clear());
+        self.set(|collection| collection.clear());
     }
 
-    fn notify_subscribers(&self) {
-        let value = self.value.lock().unwrap().clone();
+    /// Assigns `value` once no other debounced write follows within `delay`,
+    /// e.g. to feed text input to expensive computed values:
+    /// `spawn(store.query.set_debounced(text, Duration::from_millis(300)))`
+    ///
+    /// A later debounced write replaces the pending one and restarts the
+    /// delay, and any other write cancels it. Only the future of the first
+    /// call of a burst waits, until the pending value is applied or cancelled;
+    /// the others complete at once.
+    pub fn set_debounced(&self, value: T, delay: Duration) -> impl Future<Output = ()> + 'static {
+        let debounce = self.debounce.clone();
+        let start_timer = {
+            let mut state = debounce.state.lock().unwrap();
+            state.value = Some(value);
+            state.generation = debounce.generation.fetch_add(1, Ordering::SeqCst) + 1;
+            state.deadline = crate::time::now() + delay;
+            !std::mem::replace(&mut state.timer, true)
+        };
+
+        // Created now, so the flag is also cleared if the future is dropped unpolled
+        let timer = start_timer.then(|| DebounceTimer(Some(debounce.clone())));
+        let observable = self.clone();
+        async move {
+            let Some(mut timer) = timer else {
+                return;
+            };
+            let value = loop {
+                let wait = {
+                    let mut state = debounce.state.lock().unwrap();
+                    let wait = state
+                        .deadline
+                        .duration_since(crate::time::now())
+                        .unwrap_or_default();
+                    if wait.is_zero() {
+                        state.timer = false;
+                        timer.0 = None;
+                        let current =
+                            state.generation == debounce.generation.load(Ordering::SeqCst);
+                        break state.value.take().filter(|_| current);
+                    }
+                    wait
+                };
+                crate::time::sleep(wait).await;
+            };
 
-        let subscribers = self.subscribers.lock().unwrap();
-        for callback in subscribers.values() {
-            callback(&value);
+            if let Some(value) = value {
+                observable.assign(value);
+            }
         }
+    }
 
-        let mut local_subs = self.local_subscribers.borrow_mut();
-        local_subs.retain(|weak| {
-            if let Some(strong) = weak.upgrade() {
-                if let Ok(mut cb) = strong.try_borrow_mut() {
-                    cb();
-                }
-                true
+    /// Like [`Observable::set`], but only notifies when `updater` reports a change.
+    pub(crate) fn set_if<F>(&self, updater: F) -> bool
+    where
+        F: FnOnce(&mut T) -> bool,
+    {
+        let changed = {
+            let _guard = self.writer.lock();
+            if self.has_validators() || self.has_interceptors() {
+                let mut candidate = self.peek();
+                updater(&mut candidate) && self.commit(candidate, false).is_ok()
             } else {
-                false
+                let mut value = self.value.write().unwrap();
+                updater(Arc::make_mut(&mut value))
             }
-        });
+        };
+        if changed {
+            self.schedule_notify();
+        }
+        changed
     }
 
-    fn track_access(&self) {
-        CURRENT_OBSERVER.with(|observer| {
-            if let Some(ref update_fn) = *observer.borrow() {
-                let mut local_subs = self.local_subscribers.borrow_mut();
-                let weak_ref = Rc::downgrade(update_fn);
-
-                let update_ptr = weak_ref.as_ptr();
-                if !local_subs
-                    .iter()
-                    .any(|sub| std::ptr::addr_eq(sub.as_ptr(), update_ptr))
-                {
-                    local_subs.push(weak_ref);
+    /// Runs [`Observable::set`] and returns the value before and after it, with
+    /// no other write in between.
+    pub(crate) fn set_snapshot<F>(&self, updater: F) -> (Arc<T>, Arc<T>)
+    where
+        F: FnOnce(&mut T),
+    {
+        let _guard = self.writer.lock();
+        let previous = self.peek_arc();
+        self.set(updater);
+        (previous, self.peek_arc())
+    }
+
+    /// Assigns `value` only if the observable still holds the snapshot `expected`,
+    /// i.e. nothing was written since it was taken.
+    pub(crate) fn assign_if_unchanged(&self, expected: &Arc<T>, value: T) -> bool {
+        let stored = {
+            let _guard = self.writer.lock();
+            Arc::ptr_eq(&self.value.read().unwrap(), expected) && self.commit(value, false).is_ok()
+        };
+        if stored {
+            self.schedule_notify();
+        }
+        stored
+    }
+
+    /// Intercepts, validates and stores `value`, then notifies if it was stored.
+    pub(crate) fn write(&self, value: T, strict: bool) -> Result<WriteOutcome, WriteError> {
+        let outcome = {
+            let _guard = self.writer.lock();
+            self.commit(value, strict)
+        };
+        if outcome.is_ok() {
+            self.schedule_notify();
+        }
+        outcome
+    }
+
+    /// Stores `value` if the interceptors and validators let it through.
+    /// The caller holds the write lock and notifies.
+    fn commit(&self, value: T, strict: bool) -> Result<WriteOutcome, WriteError> {
+        let (value, replaced) = self.intercept_write(value).ok_or(WriteError::Intercepted)?;
+        self.check_write(&value, strict)
+            .map_err(WriteError::Invalid)?;
+        *self.value.write().unwrap() = Arc::new(value);
+        Ok(if replaced {
+            WriteOutcome::Replaced
+        } else {
+            WriteOutcome::Applied
+        })
+    }
+
+    /// Records the write in the history, if enabled. Then notifies now, or marks the
+    /// observable dirty when the [`scheduler`](crate::scheduler) defers delivery;
+    /// a dirty observable is delivered once, with its latest value.
+    ///
+    /// Computed values are invalidated at once even then, so reading one
+    /// before the flush never returns a stale result.
+    fn schedule_notify(&self) {
+        // Cancels the pending debounced write, if any
+        self.debounce.generation.fetch_add(1, Ordering::SeqCst);
+        self.record_history();
+        if !scheduler::is_deferred() {
+            self.notify_subscribers(true);
+            return;
+        }
+
+        self.invalidate_computed();
+        let observable = self.clone();
+        scheduler::enqueue_once(
+            Arc::as_ptr(&self.notify_state) as usize,
+            Box::new(move || observable.notify_subscribers(false)),
+        );
+    }
+
+    /// Notifies the computed values tracking this observable on this thread.
+    fn invalidate_computed(&self) {
+        let current = thread::current().id();
+        let computed: Vec<_> = self
+            .observers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|entry| entry.upgrade(current))
+            .filter(|observer| observer.priority() == Priority::Computed)
+            .collect();
+        for observer in computed {
+            observer.notify();
+        }
+    }
+
+    /// Delivers the current value to subscribers, then to reactive observers.
+    ///
+    /// Callbacks run on a snapshot with no lock held, so they may read or
+    /// write this observable. Deliveries never nest: a write made while one
+    /// is in progress (from a callback or another thread) is applied at once
+    /// and delivered in one more pass after the current one, with the latest value.
+    /// The reactive observers of a writing thread other than the delivering one
+    /// can only be notified there, so that thread notifies them itself.
+    ///
+    /// Deferred deliveries pass `computed: false`, as [`invalidate_computed`](Self::invalidate_computed)
+    /// already ran on write.
+    fn notify_subscribers(&self, computed: bool) {
+        let current = thread::current().id();
+        {
+            let mut state = self.notify_state.lock().unwrap();
+            match state.running {
+                Some(thread) => {
+                    state.pending = true;
+                    drop(state);
+                    if thread != current {
+                        self.deliver(computed, true);
+                    }
+                    return;
                 }
+                None => state.running = Some(current),
             }
-        });
+        }
+        let _guard = NotifyGuard(&self.notify_state);
+
+        let mut computed = computed;
+        loop {
+            self.deliver(computed, false);
+            computed = true;
+
+            let mut state = self.notify_state.lock().unwrap();
+            if !std::mem::take(&mut state.pending) {
+                break;
+            }
+        }
+    }
+
+    /// Delivers to the subscribers and the observers reachable from this thread,
+    /// or with `local_only`, to this thread's reactive observers alone.
+    fn deliver(&self, computed: bool, local_only: bool) {
+        let current = thread::current().id();
+        let value = self.peek_arc();
+
+        let mut deliveries: Vec<_> = Vec::new();
+        if !local_only {
+            self.counters.notified();
+            deliveries.extend(self.subscribers.lock().unwrap().iter().map(
+                |(_, (priority, callback))| (*priority, Delivery::Subscriber(callback.clone())),
+            ));
+        }
+        {
+            let mut observers = self.observers.lock().unwrap();
+            observers.retain(|entry| entry.is_alive(current));
+            deliveries.extend(observers.iter().filter_map(|entry| {
+                if local_only && !matches!(entry, ObserverEntry::Local { .. }) {
+                    return None;
+                }
+                let observer = entry.upgrade(current)?;
+                let priority = observer.priority();
+                (computed || priority != Priority::Computed)
+                    .then_some((priority, Delivery::Observer(observer)))
+            }));
+        }
+        // Stable, so equal priorities keep subscription order
+        deliveries.sort_by_key(|(priority, _)| *priority);
+
+        for (_, delivery) in deliveries {
+            match delivery {
+                Delivery::Subscriber(callback) => {
+                    self.counters.called_subscriber();
+                    callback(&value);
+                }
+                Delivery::Observer(observer) => {
+                    self.counters.triggered_rerender();
+                    observer.notify();
+                }
+            }
+        }
+    }
+
+    fn track_access(&self) {
+        let Some(observer) = current_observer() else {
+            return;
+        };
+
+        let entry = observer.entry();
+        {
+            let current = thread::current().id();
+            let mut observers = self.observers.lock().unwrap();
+            observers.retain(|entry| entry.is_alive(current));
+            if observers.iter().any(|existing| existing.id() == entry.id()) {
+                return;
+            }
+            observers.push(entry);
+        }
+        observer.add_dependency(Arc::downgrade(&self.observers));
+    }
+
+    /// Number of live reactive observers (components, computed values) tracking this observable
+    pub fn observer_count(&self) -> usize {
+        let current = thread::current().id();
+        self.observers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|entry| entry.is_alive(current))
+            .count()
     }
 }
 
 impl<T: Clone + 'static> Observable<T> for ObservableValue<T> {
     fn get(&self) -> T {
-        self.track_access();
-        self.value.lock().unwrap().clone()
+        T::clone(&self.get_arc())
     }
 
     fn assign(&self, value: T) {
-        *self.value.lock().unwrap() = value;
-        self.notify_subscribers();
+        let _ = self.write(value, false);
     }
 
     fn set<F>(&self, updater: F)
     where
         F: FnOnce(&mut T),
     {
-        {
-            let mut value = self.value.lock().unwrap();
-            updater(&mut *value);
-        }
-        self.notify_subscribers();
+        self.set_if(|value| {
+            updater(value);
+            true
+        });
     }
 
     fn subscribe<F: Fn(&T) + Send + Sync + 'static>(&self, callback: F) -> usize {
-        let id = {
-            let mut next_id = self.next_id.lock().unwrap();
-            let id = *next_id;
-            *next_id += 1;
-            id
-        };
-
-        self.subscribers
-            .lock()
-            .unwrap()
-            .insert(id, Box::new(callback));
-        id
+        self.subscribe_with_priority(Priority::Effect, callback)
     }
 
     fn unsubscribe(&self, id: usize) {
-        self.subscribers.lock().unwrap().remove(&id);
+        remove_subscriber(&self.subscribers, &self.counters, id);
+    }
+}
+
+/// Shows the current value and the number of subscribers, without subscribing the current observer.
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for ObservableValue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObservableValue")
+            .field("value", &*self.peek_arc())
+            .field("subscribers", &self.subscribers.lock().unwrap().len())
+            .finish()
+    }
+}
+
+/// Formats the current value. Like [`Observable::get`], it subscribes the
+/// current observer, so `"{store.count}"` in a reactive component re-renders on change.
+impl<T: Clone + fmt::Display + 'static> fmt::Display for ObservableValue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.get_arc(), f)
+    }
+}
+
+macro_rules! impl_numeric_observable {
+    ($($type:ty),*) => {
+        $(
+            impl ObservableValue<$type> {
+                /// Adds one in a single update: count.increment()
+                pub fn increment(&self) {
+                    self.set(|val| *val += 1 as $type);
+                }
+
+                /// Subtracts one in a single update: count.decrement()
+                pub fn decrement(&self) {
+                    self.set(|val| *val -= 1 as $type);
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric_observable!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+impl ObservableValue<bool> {
+    pub fn set_true(&self) {
+        self.assign(true);
+    }
+
+    pub fn set_false(&self) {
+        self.assign(false);
+    }
+
+    /// The current value, subscribing the current observer: `if modal.is_true() { ... }`
+    pub fn is_true(&self) -> bool {
+        *self.get_arc()
+    }
+
+    pub fn is_false(&self) -> bool {
+        !self.is_true()
+    }
+}
+
+impl ObservableValue<String> {
+    pub fn push_str(&self, text: &str) {
+        self.set(|value| value.push_str(text));
+    }
+
+    /// Replaces the text: `query.set_from("rust")` instead of `query.assign("rust".to_string())`
+    pub fn set_from(&self, value: impl Into<String>) {
+        self.assign(value.into());
+    }
+
+    /// Whether the text is empty, subscribing the current observer without cloning the text.
+    pub fn is_empty(&self) -> bool {
+        self.get_arc().is_empty()
+    }
+
+    /// Length in bytes, subscribing the current observer without cloning the text.
+    pub fn len(&self) -> usize {
+        self.get_arc().len()
+    }
+}
+
+impl<T: Clone + 'static> ObservableValue<Option<T>> {
+    /// Takes the value out, leaving `None`; doesn't notify if it was already `None`.
+    pub fn take(&self) -> Option<T> {
+        if self.peek_arc().is_none() {
+            return None;
+        }
+        let mut taken = None;
+        self.set(|value| taken = value.take());
+        taken
+    }
+
+    /// Puts `value` in, returning the previous one.
+    pub fn replace(&self, value: T) -> Option<T> {
+        let mut previous = None;
+        self.set(|current| previous = current.replace(value));
+        previous
+    }
+
+    /// Tracked, without cloning the value: `if selected.is_some() { ... }`
+    pub fn is_some(&self) -> bool {
+        self.get_arc().is_some()
+    }
+
+    pub fn is_none(&self) -> bool {
+        !self.is_some()
+    }
+
+    /// The current value, inserting the one built by `init` first if there is none.
+    pub fn get_or_insert_with<F: FnOnce() -> T>(&self, init: F) -> T {
+        if let Some(value) = self.get_arc().as_ref() {
+            return value.clone();
+        }
+        let mut inserted = None;
+        self.set(|value| inserted = Some(value.get_or_insert_with(init).clone()));
+        inserted.unwrap()
+    }
+
+    /// Tracked `Option::map_or` on a reference: `user.map_or(0, |user| user.age)`
+    pub fn map_or<U, F: FnOnce(&T) -> U>(&self, default: U, f: F) -> U {
+        self.get_arc().as_ref().as_ref().map_or(default, f)
+    }
+}
+
+impl<T: Clone + 'static> ObservableValue<Vec<T>> {
+    /// Keeps the items matching `keep`, notifying once and only if some were removed.
+    pub fn retain<F: FnMut(&T) -> bool>(&self, keep: F) {
+        self.set_if(|items| {
+            let len = items.len();
+            items.retain(keep);
+            items.len() != len
+        });
+    }
+
+    pub fn sort_by<F: FnMut(&T, &T) -> std::cmp::Ordering>(&self, compare: F) {
+        self.set(|items| items.sort_by(compare));
+    }
+
+    /// The first item matching `predicate`, cloning only that item. Tracked.
+    pub fn find<F: FnMut(&T) -> bool>(&self, mut predicate: F) -> Option<T> {
+        self.get_arc().iter().find(|item| predicate(item)).cloned()
+    }
+
+    /// Index of the first item matching `predicate`. Tracked.
+    pub fn position<F: FnMut(&T) -> bool>(&self, predicate: F) -> Option<usize> {
+        self.get_arc().iter().position(predicate)
+    }
+
+    /// Iterates over the current items, cloning each one as it is reached.
+    ///
+    /// Later writes don't affect the iteration. Tracked.
+    pub fn iter_snapshot(&self) -> impl Iterator<Item = T> {
+        let items = self.get_arc();
+        (0..items.len()).map(move |index| items[index].clone())
+    }
+}
+
+impl<K, V> ObservableValue<HashMap<K, V>>
+where
+    K: Hash + Eq + Clone + 'static,
+    V: Clone + 'static,
+{
+    /// Tracked, without cloning the map.
+    pub fn len(&self) -> usize {
+        self.get_arc().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.get_arc().is_empty()
+    }
+
+    pub fn contains_key<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.get_arc().contains_key(key)
+    }
+
+    /// The value under `key`, cloning only that value. Tracked.
+    pub fn get_cloned<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.get_arc().get(key).cloned()
+    }
+
+    pub fn keys_snapshot(&self) -> Vec<K> {
+        self.get_arc().keys().cloned().collect()
+    }
+
+    pub fn values_snapshot(&self) -> Vec<V> {
+        self.get_arc().values().cloned().collect()
+    }
+}
+
+/// `count += 1` on a handle: `let mut count = store.count.clone(); count += 1;`
+impl<T: Clone + AddAssign + 'static> AddAssign<T> for ObservableValue<T> {
+    fn add_assign(&mut self, amount: T) {
+        self.add(amount);
+    }
+}
+
+impl<T: Clone + SubAssign + 'static> SubAssign<T> for ObservableValue<T> {
+    fn sub_assign(&mut self, amount: T) {
+        self.sub(amount);
+    }
+}
+
+impl<T: Clone + MulAssign + 'static> MulAssign<T> for ObservableValue<T> {
+    fn mul_assign(&mut self, factor: T) {
+        self.mul(factor);
+    }
+}
+
+/// Compares current values, subscribing the current observer to both observables.
+impl<T: Clone + PartialEq + 'static> PartialEq for ObservableValue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        *self.get_arc() == *other.get_arc()
+    }
+}
+
+/// Compares the current value, subscribing the current observer: `assert_eq!(store.count, 3)`
+impl<T: Clone + PartialEq + 'static> PartialEq<T> for ObservableValue<T> {
+    fn eq(&self, other: &T) -> bool {
+        *self.get_arc() == *other
+    }
+}
+
+/// Serializes the current value, without subscribing the current observer.
+#[cfg(feature = "serde")]
+impl<T: Clone + serde::Serialize + 'static> serde::Serialize for ObservableValue<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.peek_arc().serialize(serializer)
+    }
+}
+
+/// Deserializes into a new observable with no subscribers.
+#[cfg(feature = "serde")]
+impl<'de, T: Clone + serde::Deserialize<'de> + 'static> serde::Deserialize<'de>
+    for ObservableValue<T>
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(ObservableValue::new)
     }
 }
 
@@ -278,30 +1403,164 @@ pub type ObservableString = ObservableValue<String>;
 pub type ObservableI32 = ObservableValue<i32>;
 pub type ObservableU32 = ObservableValue<u32>;
 pub type ObservableF64 = ObservableValue<f64>;
-pub type ObservableVec<T> = ObservableValue<Vec<T>>;
 pub type ObservableOption<T> = ObservableValue<Option<T>>;
+pub type ObservableVec<T> = ObservableValue<Vec<T>>;
 pub type ObservableMap<K, V> = ObservableValue<HashMap<K, V>>;
 
+#[cfg(feature = "dioxus")]
 pub fn use_reactive() -> impl Fn() {
     let mut reactive_update = dioxus::prelude::use_signal(|| 0u32);
 
-    dioxus::prelude::use_hook(|| {
+    let context = dioxus::prelude::use_hook(|| {
         let update_ui = {
             let mut reactive_update = reactive_update.clone();
             move || {
-                reactive_update.set(
-                    std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_nanos() as u32,
-                );
+                let next = reactive_update.peek().wrapping_add(1);
+                reactive_update.set(next);
             }
         };
 
-        ObserverContext::new(update_ui)
+        ObserverContext::new_untracked(update_ui)
     });
 
+    context.begin_tracking();
+    dioxus::prelude::use_after_render(move || context.end_tracking());
+
     let _trigger = reactive_update.read();
 
     || {}
 }
+
+/// Re-renders the component when an observable read before the returned scope is dropped changes.
+#[cfg(feature = "dioxus")]
+pub fn use_tracking_scope() -> TrackingScope {
+    let reactive_update = dioxus::prelude::use_signal(|| 0u32);
+
+    let context = dioxus::prelude::use_hook(|| {
+        let update_ui = {
+            let mut reactive_update = reactive_update;
+            move || {
+                let next = reactive_update.peek().wrapping_add(1);
+                reactive_update.set(next);
+            }
+        };
+
+        ObserverContext::new_untracked(update_ui)
+    });
+    let tracking = context.track();
+
+    let _ = reactive_update.read();
+
+    tracking
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InterceptResult, WriteError, WriteOutcome};
+    use std::sync::mpsc;
+
+    #[test]
+    fn concurrent_updates_through_validators_are_not_lost() {
+        let count = ObservableValue::new(0u32).with_validator(|_| Ok(()));
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let count = count.clone();
+                thread::spawn(move || {
+                    for _ in 0..500 {
+                        count.set(|n| *n += 1);
+                    }
+                })
+            })
+            .collect();
+        for handle in threads {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(count.peek(), 4000);
+    }
+
+    #[test]
+    fn try_assign_reports_what_happened_to_the_write() {
+        let volume = ObservableValue::new(10u32)
+            .with_validator(|v| (*v != 13).then_some(()).ok_or("unlucky".to_string()));
+        volume.intercept(|v| match *v {
+            0 => InterceptResult::Reject,
+            v if v > 100 => InterceptResult::Replace(100),
+            _ => InterceptResult::Accept,
+        });
+
+        assert!(matches!(volume.try_assign(0), Err(WriteError::Intercepted)));
+        assert!(
+            matches!(volume.try_assign(13), Err(WriteError::Invalid(errors)) if errors == ["unlucky"])
+        );
+        assert_eq!(volume.peek(), 10);
+
+        assert!(matches!(volume.try_assign(250), Ok(WriteOutcome::Replaced)));
+        assert_eq!(volume.peek(), 100);
+        assert!(matches!(volume.try_assign(42), Ok(WriteOutcome::Applied)));
+        assert_eq!(volume.peek(), 42);
+    }
+
+    #[test]
+    fn writers_notify_their_own_observers_while_another_thread_delivers() {
+        let value = ObservableValue::new(0u32);
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let gate = Mutex::new(Some((entered_tx, release_rx)));
+        value.subscribe(move |_| {
+            if let Some((entered, release)) = gate.lock().unwrap().take() {
+                entered.send(()).unwrap();
+                release.recv().unwrap();
+            }
+        });
+
+        let renders = Rc::new(Cell::new(0));
+        let context = ObserverContext::new({
+            let renders = renders.clone();
+            move || renders.set(renders.get() + 1)
+        });
+        value.get();
+        context.end_tracking();
+
+        let writer = {
+            let value = value.clone();
+            thread::spawn(move || value.assign(1))
+        };
+        entered_rx.recv().unwrap();
+
+        value.assign(2);
+        assert_eq!(renders.get(), 1);
+
+        release_tx.send(()).unwrap();
+        writer.join().unwrap();
+        assert_eq!(value.peek(), 2);
+    }
+
+    #[test]
+    fn observers_of_exited_threads_are_pruned() {
+        let value = ObservableValue::new(0u32);
+        let (tracked_tx, tracked_rx) = mpsc::channel();
+        let (exit_tx, exit_rx) = mpsc::channel::<()>();
+        let observer = {
+            let value = value.clone();
+            thread::spawn(move || {
+                // Leaked, so only the thread exiting can tell the entry is dead
+                let context = ObserverContext::new(|| {});
+                value.get();
+                context.end_tracking();
+                std::mem::forget(context);
+                tracked_tx.send(()).unwrap();
+                exit_rx.recv().unwrap();
+            })
+        };
+
+        tracked_rx.recv().unwrap();
+        assert_eq!(value.observer_count(), 1);
+
+        exit_tx.send(()).unwrap();
+        observer.join().unwrap();
+        assert_eq!(value.observer_count(), 0);
+        value.assign(1);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{use_store, SerializableStore, SyncObserverContext};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+
+/// One operation of an RFC 6902 JSON Patch document.
+///
+/// Serializes as `{"op": "replace", "path": "/count", "value": 2}`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
+
+/// The JSON Patch turning `before` into `after`.
+///
+/// Objects are compared key by key and arrays index by index, so an item
+/// inserted at the front of an array shows up as replacements plus an `add`.
+pub fn diff_json(before: &Value, after: &Value) -> Vec<PatchOperation> {
+    let mut patch = Vec::new();
+    diff_into(&mut patch, String::new(), before, after);
+    patch
+}
+
+fn diff_into(patch: &mut Vec<PatchOperation>, path: String, before: &Value, after: &Value) {
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            for (key, value) in before {
+                let path = format!("{path}/{}", escape(key));
+                match after.get(key) {
+                    Some(new) => diff_into(patch, path, value, new),
+                    None => patch.push(PatchOperation::Remove { path }),
+                }
+            }
+            for (key, value) in after {
+                if !before.contains_key(key) {
+                    patch.push(PatchOperation::Add {
+                        path: format!("{path}/{}", escape(key)),
+                        value: value.clone(),
+                    });
+                }
+            }
+        }
+        (Value::Array(before), Value::Array(after)) => {
+            for (index, (old, new)) in before.iter().zip(after).enumerate() {
+                diff_into(patch, format!("{path}/{index}"), old, new);
+            }
+            for (index, value) in after.iter().enumerate().skip(before.len()) {
+                patch.push(PatchOperation::Add {
+                    path: format!("{path}/{index}"),
+                    value: value.clone(),
+                });
+            }
+            // From the end, so the remaining indices stay valid
+            for index in (after.len()..before.len()).rev() {
+                patch.push(PatchOperation::Remove {
+                    path: format!("{path}/{index}"),
+                });
+            }
+        }
+        _ if before != after => patch.push(PatchOperation::Replace {
+            path,
+            value: after.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Escapes a key as a JSON Pointer reference token.
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Calls its callback with a JSON Patch for every change of a store until dropped.
+///
+/// Created with [`watch_patches`].
+#[must_use = "the patches stop when the watcher is dropped"]
+pub struct PatchWatcher {
+    _watcher: SyncObserverContext,
+}
+
+/// Calls `on_patch` with the JSON Patch since the previous call whenever the
+/// global instance of `S` changes, e.g. to sync deltas to a server:
+///
+/// ```ignore
+/// let watcher = watch_patches::<CartStore>(|patch| {
+///     send_to_server(serde_json::to_string(&patch).unwrap());
+/// });
+/// ```
+///
+/// Paths are relative to the store snapshot, like `/items/0/quantity`. Only
+/// fields read by [`SerializableStore::track`] are watched.
+pub fn watch_patches<S: SerializableStore + Default + Send + Sync>(
+    on_patch: impl Fn(Vec<PatchOperation>) + Send + Sync + 'static,
+) -> PatchWatcher {
+    let last = Mutex::new(use_store::<S>().snapshot().unwrap_or(Value::Null));
+
+    let watcher = SyncObserverContext::new(move || {
+        let Ok(current) = use_store::<S>().snapshot() else {
+            return;
+        };
+        let patch = {
+            let mut last = last.lock().unwrap();
+            let patch = diff_json(&last, &current);
+            *last = current;
+            patch
+        };
+        if !patch.is_empty() {
+            on_patch(patch);
+        }
+    });
+    watcher.run(|| use_store::<S>().track());
+
+    PatchWatcher { _watcher: watcher }
+}
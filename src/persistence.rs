@@ -0,0 +1,297 @@
+use crate::{Observable, ObservableValue};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+
+/// Failure to write a persisted value.
+#[derive(Debug)]
+pub enum PersistError {
+    Serialize(serde_json::Error),
+    /// The storage rejected the write, e.g. a full localStorage or an unwritable directory.
+    Storage(String),
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Serialize(error) => write!(f, "failed to serialize persisted value: {error}"),
+            Self::Storage(error) => write!(f, "failed to write persisted value: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for PersistError {}
+
+impl From<serde_json::Error> for PersistError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Serialize(error)
+    }
+}
+
+/// Where persisted values are stored, as one JSON string per key.
+///
+/// Implement it to keep state in a remote config service, a custom format, etc.,
+/// then install it with [`set_storage_backend`] or pass it to [`persist_with`].
+pub trait StorageBackend: Send + Sync {
+    fn load(&self, key: &str) -> Option<String>;
+    fn save(&self, key: &str, json: &str) -> Result<(), PersistError>;
+    fn remove(&self, key: &str);
+}
+
+/// `localStorage` on the web, JSON files in the [persist dir](set_persist_dir) on native.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultBackend;
+
+impl StorageBackend for DefaultBackend {
+    fn load(&self, key: &str) -> Option<String> {
+        platform::read(key)
+    }
+
+    fn save(&self, key: &str, json: &str) -> Result<(), PersistError> {
+        platform::write(key, json)
+    }
+
+    fn remove(&self, key: &str) {
+        platform::remove(key);
+    }
+}
+
+impl<B: StorageBackend + ?Sized> StorageBackend for Arc<B> {
+    fn load(&self, key: &str) -> Option<String> {
+        (**self).load(key)
+    }
+
+    fn save(&self, key: &str, json: &str) -> Result<(), PersistError> {
+        (**self).save(key, json)
+    }
+
+    fn remove(&self, key: &str) {
+        (**self).remove(key);
+    }
+}
+
+static BACKEND: LazyLock<RwLock<Arc<dyn StorageBackend>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(DefaultBackend)));
+
+/// Backend used by [`persist`], [`load_persisted`], [`save_persisted`] and [`remove_persisted`].
+pub fn set_storage_backend(backend: impl StorageBackend + 'static) {
+    *BACKEND.write().unwrap() = Arc::new(backend);
+}
+
+pub fn storage_backend() -> Arc<dyn StorageBackend> {
+    BACKEND.read().unwrap().clone()
+}
+
+/// The value saved under `key` in `backend`, if any and if it still deserializes as `T`.
+pub fn load_persisted_from<T: DeserializeOwned>(
+    backend: &impl StorageBackend,
+    key: &str,
+) -> Option<T> {
+    let stored = backend.load(key)?;
+    #[cfg(feature = "encryption")]
+    let stored = crate::encryption::open(key, stored)?;
+    serde_json::from_str(&stored).ok()
+}
+
+pub fn save_persisted_to<T: Serialize>(
+    backend: &impl StorageBackend,
+    key: &str,
+    value: &T,
+) -> Result<(), PersistError> {
+    let json = serde_json::to_string(value)?;
+    #[cfg(feature = "encryption")]
+    let json = crate::encryption::seal(key, json)?;
+    backend.save(key, &json)
+}
+
+/// The value saved under `key`, if any and if it still deserializes as `T`.
+pub fn load_persisted<T: DeserializeOwned>(key: &str) -> Option<T> {
+    load_persisted_from(&storage_backend(), key)
+}
+
+pub fn save_persisted<T: Serialize>(key: &str, value: &T) -> Result<(), PersistError> {
+    save_persisted_to(&storage_backend(), key, value)
+}
+
+pub fn remove_persisted(key: &str) {
+    storage_backend().remove(key);
+}
+
+/// Restores `observable` from the value saved under `key`, then saves it after every change.
+///
+/// Uses the backend installed with [`set_storage_backend`] at the time of each write.
+/// Returns the id of the saving subscription.
+pub fn persist<T>(observable: &ObservableValue<T>, key: &str) -> usize
+where
+    T: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    if let Some(value) = load_persisted::<T>(key) {
+        observable.assign(value);
+    }
+
+    let key = key.to_string();
+    observable.subscribe(move |value: &T| {
+        let _ = save_persisted(&key, value);
+    })
+}
+
+/// Like [`persist`], but always stores to `backend`.
+pub fn persist_with<T, B>(observable: &ObservableValue<T>, key: &str, backend: B) -> usize
+where
+    T: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+    B: StorageBackend + 'static,
+{
+    if let Some(value) = load_persisted_from::<T>(&backend, key) {
+        observable.assign(value);
+    }
+
+    let key = key.to_string();
+    observable.subscribe(move |value: &T| {
+        let _ = save_persisted_to(&backend, &key, value);
+    })
+}
+
+static PERSISTED_FIELDS: LazyLock<Mutex<HashMap<&'static str, Box<dyn Any + Send + Sync>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The observable persisted under `key` for the persisted fields of the store
+/// macros, created from `initial` and [persisted](persist) by the first
+/// instance, so every instance shares it and it is persisted once.
+#[doc(hidden)]
+pub fn __persisted_field<T>(key: &'static str, initial: impl FnOnce() -> T) -> ObservableValue<T>
+where
+    T: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    let shared = |fields: &HashMap<&'static str, Box<dyn Any + Send + Sync>>| {
+        fields
+            .get(key)
+            .and_then(|field| field.downcast_ref::<ObservableValue<T>>())
+            .cloned()
+    };
+    if let Some(field) = shared(&PERSISTED_FIELDS.lock().unwrap()) {
+        return field;
+    }
+
+    // Built without the lock, as `initial` may create other stores
+    let field = ObservableValue::new(initial());
+    let mut fields = PERSISTED_FIELDS.lock().unwrap();
+    if let Some(field) = shared(&fields) {
+        return field;
+    }
+    persist(&field, key);
+    fields.insert(key, Box::new(field.clone()));
+    field
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use platform::set_persist_dir;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use super::PersistError;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::{LazyLock, RwLock};
+
+    static PERSIST_DIR: LazyLock<RwLock<PathBuf>> =
+        LazyLock::new(|| RwLock::new(PathBuf::from(".reaxive")));
+
+    /// Directory persisted values are written to, one JSON file per key; `.reaxive` by default.
+    pub fn set_persist_dir(dir: impl Into<PathBuf>) {
+        *PERSIST_DIR.write().unwrap() = dir.into();
+    }
+
+    /// The file of `key`, with every byte other than ASCII alphanumerics, `-`
+    /// and `_` percent-encoded so distinct keys never share a file.
+    fn path(key: &str) -> PathBuf {
+        let mut name = String::with_capacity(key.len());
+        for byte in key.bytes() {
+            if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+                name.push(byte as char);
+            } else {
+                name.push_str(&format!("%{byte:02X}"));
+            }
+        }
+        PERSIST_DIR.read().unwrap().join(format!("{name}.json"))
+    }
+
+    pub(super) fn read(key: &str) -> Option<String> {
+        fs::read_to_string(path(key)).ok()
+    }
+
+    pub(super) fn write(key: &str, json: &str) -> Result<(), PersistError> {
+        let path = path(key);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|error| PersistError::Storage(error.to_string()))?;
+        }
+        fs::write(path, json).map_err(|error| PersistError::Storage(error.to_string()))
+    }
+
+    pub(super) fn remove(key: &str) {
+        let _ = fs::remove_file(path(key));
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    use super::PersistError;
+    use web_sys::Storage;
+
+    fn local_storage() -> Option<Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    pub(super) fn read(key: &str) -> Option<String> {
+        local_storage()?.get_item(key).ok()?
+    }
+
+    pub(super) fn write(key: &str, json: &str) -> Result<(), PersistError> {
+        local_storage()
+            .ok_or_else(|| PersistError::Storage("localStorage is unavailable".into()))?
+            .set_item(key, json)
+            .map_err(|error| PersistError::Storage(format!("{error:?}")))
+    }
+
+    pub(super) fn remove(key: &str) {
+        if let Some(storage) = local_storage() {
+            let _ = storage.remove_item(key);
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Keeps persisted values in memory, so tests don't touch the persist dir.
+    #[derive(Clone, Default)]
+    pub(crate) struct MemoryBackend(Arc<Mutex<HashMap<String, String>>>);
+
+    impl MemoryBackend {
+        pub(crate) fn raw(&self, key: &str) -> Option<String> {
+            self.0.lock().unwrap().get(key).cloned()
+        }
+
+        pub(crate) fn put(&self, key: &str, json: String) {
+            self.0.lock().unwrap().insert(key.to_string(), json);
+        }
+    }
+
+    impl StorageBackend for MemoryBackend {
+        fn load(&self, key: &str) -> Option<String> {
+            self.raw(key)
+        }
+
+        fn save(&self, key: &str, json: &str) -> Result<(), PersistError> {
+            self.put(key, json.to_string());
+            Ok(())
+        }
+
+        fn remove(&self, key: &str) {
+            self.0.lock().unwrap().remove(key);
+        }
+    }
+}
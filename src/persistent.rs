@@ -0,0 +1,162 @@
+use crate::{Clearable, Observable, ObservableValue};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// `PersistentVec`/`PersistentMap` hold `im` collections: `Arc::make_mut`
+// then copies a shared snapshot in O(1) and later edits only copy the
+// touched nodes.
+
+pub use im;
+
+pub type PersistentVec<T> = ObservableValue<im::Vector<T>>;
+pub type PersistentMap<K, V> = ObservableValue<im::HashMap<K, V>>;
+
+/// Backed by `im::Vector`, so snapshots and clones share structure with the current value.
+pub fn persistent_vec<T: Clone + 'static>(initial: Vec<T>) -> PersistentVec<T> {
+    ObservableValue::new(initial.into())
+}
+
+/// Backed by `im::HashMap`, so snapshots and clones share structure with the current value.
+pub fn persistent_map<K, V>(initial: HashMap<K, V>) -> PersistentMap<K, V>
+where
+    K: Hash + Eq + Clone + 'static,
+    V: Clone + 'static,
+{
+    ObservableValue::new(initial.into())
+}
+
+impl<T: Clone> Clearable for im::Vector<T> {
+    fn clear(&mut self) {
+        *self = im::Vector::new();
+    }
+}
+
+impl<K, V, S> Clearable for im::HashMap<K, V, S>
+where
+    Self: Default,
+{
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl<T: Clone + 'static> ObservableValue<im::Vector<T>> {
+    pub fn push_back(&self, item: T) {
+        self.set(|items| items.push_back(item));
+    }
+
+    pub fn push_front(&self, item: T) {
+        self.set(|items| items.push_front(item));
+    }
+
+    pub fn pop_back(&self) -> Option<T> {
+        let mut popped = None;
+        self.set(|items| popped = items.pop_back());
+        popped
+    }
+
+    pub fn pop_front(&self) -> Option<T> {
+        let mut popped = None;
+        self.set(|items| popped = items.pop_front());
+        popped
+    }
+
+    /// Inserts `item` at `index`; panics if `index > len`
+    pub fn insert_at(&self, index: usize, item: T) {
+        self.set(|items| items.insert(index, item));
+    }
+
+    /// Removes the item at `index`, or returns `None` without notifying if out of bounds.
+    pub fn remove_at(&self, index: usize) -> Option<T> {
+        let mut removed = None;
+        self.set_if(|items| {
+            if index < items.len() {
+                removed = Some(items.remove(index));
+            }
+            removed.is_some()
+        });
+        removed
+    }
+
+    /// Keeps the items matching `keep`, notifying once and only if some were removed.
+    pub fn retain<F: FnMut(&T) -> bool>(&self, keep: F) {
+        self.set_if(|items| {
+            let len = items.len();
+            items.retain(keep);
+            items.len() != len
+        });
+    }
+
+    pub fn sort_by<F: Fn(&T, &T) -> std::cmp::Ordering>(&self, compare: F) {
+        self.set(|items| items.sort_by(compare));
+    }
+
+    /// The first item matching `predicate`, cloning only that item. Tracked.
+    pub fn find<F: FnMut(&T) -> bool>(&self, mut predicate: F) -> Option<T> {
+        self.get_arc().iter().find(|item| predicate(item)).cloned()
+    }
+
+    /// Index of the first item matching `predicate`. Tracked.
+    pub fn position<F: FnMut(&T) -> bool>(&self, predicate: F) -> Option<usize> {
+        self.get_arc().iter().position(predicate)
+    }
+
+    /// Iterates over the current items, cloning each one as it is reached.
+    ///
+    /// Later writes don't affect the iteration. Tracked.
+    pub fn iter_snapshot(&self) -> impl Iterator<Item = T> {
+        let items = self.get_arc();
+        (0..items.len()).map(move |index| items[index].clone())
+    }
+}
+
+impl<K, V> ObservableValue<im::HashMap<K, V>>
+where
+    K: Hash + Eq + Clone + 'static,
+    V: Clone + 'static,
+{
+    /// Tracked, without cloning the map.
+    pub fn len(&self) -> usize {
+        self.get_arc().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.get_arc().is_empty()
+    }
+
+    pub fn contains_key<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.get_arc().contains_key(key)
+    }
+
+    /// The value under `key`, cloning only that value. Tracked.
+    pub fn get_cloned<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.get_arc().get(key).cloned()
+    }
+
+    pub fn keys_snapshot(&self) -> Vec<K> {
+        self.get_arc().keys().cloned().collect()
+    }
+
+    pub fn values_snapshot(&self) -> Vec<V> {
+        self.get_arc().values().cloned().collect()
+    }
+
+    pub fn insert_entry(&self, key: K, value: V) -> Option<V> {
+        let mut previous = None;
+        self.set(|map| previous = map.insert(key, value));
+        previous
+    }
+
+    pub fn remove_entry(&self, key: &K) -> Option<V> {
+        let mut removed = None;
+        self.set(|map| removed = map.remove(key));
+        removed
+    }
+}
@@ -0,0 +1,274 @@
+use crate::{ObservableResource, ObservableValue, ResourceState};
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::rc::Rc;
+use std::time::Duration;
+
+struct CachedQuery {
+    entry: Rc<dyn Any>,
+    type_name: &'static str,
+    invalidate: Rc<dyn Fn()>,
+}
+
+/// A query key used with other data or error types than the entry cached for it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryTypeMismatch {
+    pub key: String,
+    pub cached: &'static str,
+    pub requested: &'static str,
+}
+
+impl fmt::Display for QueryTypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "query `{}` is cached as {}, not {}",
+            self.key, self.cached, self.requested
+        )
+    }
+}
+
+impl std::error::Error for QueryTypeMismatch {}
+
+thread_local! {
+    static QUERY_CACHE: RefCell<HashMap<String, CachedQuery>> = RefCell::new(HashMap::new());
+}
+
+/// A cached query result shared by every caller that uses the same key.
+#[derive(Clone)]
+pub struct QueryEntry<T: Clone + 'static, E: Clone + 'static> {
+    key: Rc<str>,
+    resource: ObservableResource<T, E>,
+    stale: Rc<Cell<bool>>,
+    // Set from the moment a fetch is spawned, before the resource sees it
+    fetching: Rc<Cell<bool>>,
+}
+
+impl<T: Clone + 'static, E: Clone + 'static> QueryEntry<T, E> {
+    fn new<F, Fut>(key: &str, fetcher: F) -> Self
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        Self {
+            key: Rc::from(key),
+            resource: ObservableResource::new(fetcher),
+            stale: Rc::new(Cell::new(true)),
+            fetching: Rc::new(Cell::new(false)),
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn state(&self) -> ResourceState<T, E> {
+        self.resource.state()
+    }
+
+    pub fn value(&self) -> Option<T> {
+        self.resource.value()
+    }
+
+    pub fn error(&self) -> Option<E> {
+        self.resource.error()
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.resource.is_loading()
+    }
+
+    pub fn is_fetching(&self) -> bool {
+        self.fetching.get() || self.resource.is_fetching()
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.stale.get()
+    }
+
+    pub fn observable(&self) -> &ObservableValue<ResourceState<T, E>> {
+        self.resource.observable()
+    }
+
+    pub fn resource(&self) -> &ObservableResource<T, E> {
+        &self.resource
+    }
+
+    /// Runs the fetcher, even if another fetch for this key is in flight.
+    pub async fn fetch(&self) {
+        self.stale.set(false);
+        self.resource.fetch().await;
+    }
+
+    /// Starts a fetch on the Dioxus runtime unless one is already running.
+    pub fn refetch(&self) {
+        if self.is_fetching() {
+            return;
+        }
+        self.spawn_fetch();
+    }
+
+    /// Marks the entry stale and refetches it unless a fetch is running; a
+    /// running fetch leaves it stale, so the next [`query`] fetches again.
+    pub fn invalidate(&self) {
+        self.stale.set(true);
+        self.refetch();
+    }
+
+    /// Writes data directly into the cache and marks it fresh.
+    pub fn set_data(&self, value: T) {
+        self.stale.set(false);
+        self.resource.set_ready(value);
+    }
+
+    /// Keeps the entry fresh by refetching every `interval`.
+    pub fn refetch_interval(self, interval: Duration) -> Self {
+        let resource = self.resource.clone().refetch_interval(interval);
+        Self { resource, ..self }
+    }
+
+    /// Refetches the entry whenever the window regains focus.
+    pub fn refetch_on_focus(self, enabled: bool) -> Self {
+        let resource = self.resource.clone().refetch_on_focus(enabled);
+        Self { resource, ..self }
+    }
+
+    fn spawn_fetch(&self) {
+        self.fetching.set(true);
+        let guard = FetchingGuard(self.fetching.clone());
+        let entry = self.clone();
+        dioxus::prelude::spawn(async move {
+            let _guard = guard;
+            entry.fetch().await;
+        });
+    }
+}
+
+/// Clears the fetching flag of an entry when its spawned fetch ends or is dropped.
+struct FetchingGuard(Rc<Cell<bool>>);
+
+impl Drop for FetchingGuard {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+/// Returns the cached entry for `key`, fetching it if it is missing or stale.
+///
+/// Concurrent calls with the same key share one in-flight request. Fails if
+/// `key` is cached with other data or error types.
+pub fn query<T, E, F, Fut>(
+    key: impl Into<String>,
+    fetcher: F,
+) -> Result<QueryEntry<T, E>, QueryTypeMismatch>
+where
+    T: Clone + 'static,
+    E: Clone + 'static,
+    F: Fn() -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+{
+    let key = key.into();
+    let entry = match get_query::<T, E>(&key)? {
+        Some(entry) => entry,
+        None => {
+            let entry = QueryEntry::new(&key, fetcher);
+            let invalidate = {
+                let entry = entry.clone();
+                Rc::new(move || entry.invalidate()) as Rc<dyn Fn()>
+            };
+
+            QUERY_CACHE.with(|cache| {
+                cache.borrow_mut().insert(
+                    key.clone(),
+                    CachedQuery {
+                        entry: Rc::new(entry.clone()),
+                        type_name: std::any::type_name::<QueryEntry<T, E>>(),
+                        invalidate,
+                    },
+                );
+            });
+            entry
+        }
+    };
+
+    if entry.is_stale() {
+        entry.refetch();
+    }
+
+    Ok(entry)
+}
+
+pub fn get_query<T: Clone + 'static, E: Clone + 'static>(
+    key: &str,
+) -> Result<Option<QueryEntry<T, E>>, QueryTypeMismatch> {
+    QUERY_CACHE.with(|cache| {
+        let cache = cache.borrow();
+        let Some(cached) = cache.get(key) else {
+            return Ok(None);
+        };
+        match cached.entry.downcast_ref::<QueryEntry<T, E>>() {
+            Some(entry) => Ok(Some(entry.clone())),
+            None => Err(QueryTypeMismatch {
+                key: key.to_string(),
+                cached: cached.type_name,
+                requested: std::any::type_name::<QueryEntry<T, E>>(),
+            }),
+        }
+    })
+}
+
+/// Writes data for `key` if it is cached, returning whether it was found.
+pub fn set_query_data<T: Clone + 'static, E: Clone + 'static>(
+    key: &str,
+    value: T,
+) -> Result<bool, QueryTypeMismatch> {
+    match get_query::<T, E>(key)? {
+        Some(entry) => {
+            entry.set_data(value);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Marks the query stale and refetches it.
+pub fn invalidate(key: &str) {
+    invalidate_where(|candidate| candidate == key);
+}
+
+/// Marks every query whose key starts with `prefix` stale and refetches them.
+pub fn invalidate_prefix(prefix: &str) {
+    invalidate_where(|candidate| candidate.starts_with(prefix));
+}
+
+fn invalidate_where(matches: impl Fn(&str) -> bool) {
+    let invalidators: Vec<Rc<dyn Fn()>> = QUERY_CACHE.with(|cache| {
+        cache
+            .borrow()
+            .iter()
+            .filter(|(key, _)| matches(key))
+            .map(|(_, cached)| cached.invalidate.clone())
+            .collect()
+    });
+
+    for invalidate in invalidators {
+        invalidate();
+    }
+}
+
+pub fn remove_query(key: &str) {
+    QUERY_CACHE.with(|cache| {
+        cache.borrow_mut().remove(key);
+    });
+}
+
+pub fn clear_queries() {
+    QUERY_CACHE.with(|cache| cache.borrow_mut().clear());
+}
+
+pub fn query_keys() -> Vec<String> {
+    QUERY_CACHE.with(|cache| cache.borrow().keys().cloned().collect())
+}
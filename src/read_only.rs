@@ -0,0 +1,85 @@
+use crate::{Observable, ObservableValue};
+use std::fmt;
+use std::sync::Arc;
+
+/// A handle that can read and subscribe to an observable but not write it.
+///
+/// Lets a store hand state to components or plugins without giving them
+/// mutation rights: `pub fn items(&self) -> ReadOnlyObservable<Vec<Item>> { self.items.read_only() }`
+pub struct ReadOnlyObservable<T: Clone + 'static> {
+    inner: ObservableValue<T>,
+}
+
+impl<T: Clone + 'static> Clone for ReadOnlyObservable<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Clone + 'static> ObservableValue<T> {
+    pub fn read_only(&self) -> ReadOnlyObservable<T> {
+        ReadOnlyObservable {
+            inner: self.clone(),
+        }
+    }
+}
+
+impl<T: Clone + 'static> From<ObservableValue<T>> for ReadOnlyObservable<T> {
+    fn from(inner: ObservableValue<T>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T: Clone + 'static> ReadOnlyObservable<T> {
+    /// The current value, subscribing the current observer.
+    pub fn get(&self) -> T {
+        self.inner.get()
+    }
+
+    /// Read the value without subscribing the current observer
+    pub fn peek(&self) -> T {
+        self.inner.peek()
+    }
+
+    pub fn get_arc(&self) -> Arc<T> {
+        self.inner.get_arc()
+    }
+
+    pub fn map<U, F: FnOnce(&T) -> U>(&self, mapper: F) -> U {
+        self.inner.map(mapper)
+    }
+
+    pub fn subscribe<F: Fn(&T) + Send + Sync + 'static>(&self, callback: F) -> usize {
+        self.inner.subscribe(callback)
+    }
+
+    pub fn unsubscribe(&self, id: usize) {
+        self.inner.unsubscribe(id);
+    }
+
+    pub fn observer_count(&self) -> usize {
+        self.inner.observer_count()
+    }
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for ReadOnlyObservable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ReadOnlyObservable")
+            .field(&*self.inner.peek_arc())
+            .finish()
+    }
+}
+
+impl<T: Clone + fmt::Display + 'static> fmt::Display for ReadOnlyObservable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> PartialEq<T> for ReadOnlyObservable<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.inner == *other
+    }
+}
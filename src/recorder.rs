@@ -0,0 +1,133 @@
+use crate::hydration::hydrate_store;
+use crate::{
+    register_hydratable, time, untracked, use_store, SerializableStore, SyncObserverContext,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+
+/// One field write recorded by a [`MutationRecorder`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Mutation {
+    /// [`SerializableStore::store_key`] of the store.
+    pub store: String,
+    pub field: String,
+    /// The new value of the field.
+    pub value: Value,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+#[derive(Default)]
+struct RecorderState {
+    log: Mutex<Vec<Mutation>>,
+    watchers: Mutex<Vec<SyncObserverContext>>,
+}
+
+/// Records every field change of the watched stores into an ordered log
+/// that can be saved and replayed later, e.g. to reproduce a bug report:
+///
+/// ```ignore
+/// let recorder = MutationRecorder::new();
+/// recorder.watch::<CartStore>();
+/// // ...
+/// let log = recorder.to_json()?;
+///
+/// // later, in a fresh app
+/// register_hydratable::<CartStore>();
+/// replay_mutations(&serde_json::from_str(&log)?)?;
+/// ```
+#[derive(Clone, Default)]
+pub struct MutationRecorder {
+    state: Arc<RecorderState>,
+}
+
+impl MutationRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts recording the changes of the global instance of `S`.
+    ///
+    /// Only fields read by [`SerializableStore::track`] are watched, which
+    /// [`serializable_store!`](crate::serializable_store) does for every listed field.
+    pub fn watch<S: SerializableStore + Default + Send + Sync>(&self) {
+        register_hydratable::<S>();
+        let last = Mutex::new(field_values(use_store::<S>().snapshot().ok()));
+
+        let state = Arc::downgrade(&self.state);
+        let watcher = SyncObserverContext::new(move || {
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+            let current = untracked(|| field_values(use_store::<S>().snapshot().ok()));
+            let mut last = last.lock().unwrap();
+            let timestamp = timestamp();
+
+            let mut log = state.log.lock().unwrap();
+            for (field, value) in &current {
+                if last.get(field) != Some(value) {
+                    log.push(Mutation {
+                        store: S::store_key().to_string(),
+                        field: field.clone(),
+                        value: value.clone(),
+                        timestamp,
+                    });
+                }
+            }
+            *last = current;
+        });
+        watcher.run(|| use_store::<S>().track());
+
+        self.state.watchers.lock().unwrap().push(watcher);
+    }
+
+    /// Stops recording; the log is kept.
+    pub fn stop(&self) {
+        self.state.watchers.lock().unwrap().clear();
+    }
+
+    pub fn mutations(&self) -> Vec<Mutation> {
+        self.state.log.lock().unwrap().clone()
+    }
+
+    pub fn clear(&self) {
+        self.state.log.lock().unwrap().clear();
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&*self.state.log.lock().unwrap())
+    }
+}
+
+fn field_values(snapshot: Option<Value>) -> Map<String, Value> {
+    match snapshot {
+        Some(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    }
+}
+
+fn timestamp() -> u64 {
+    time::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Applies `mutations` in order to the global stores, creating them as needed.
+///
+/// Stores must be registered with [`register_hydratable`]; mutations of other
+/// stores are skipped. Returns how many mutations were applied.
+pub fn replay_mutations(mutations: &[Mutation]) -> Result<usize, serde_json::Error> {
+    let mut applied = 0;
+    for mutation in mutations {
+        let mut fields = Map::new();
+        fields.insert(mutation.field.clone(), mutation.value.clone());
+        if let Some(result) = hydrate_store(&mutation.store, Value::Object(fields)) {
+            result?;
+            applied += 1;
+        }
+    }
+    Ok(applied)
+}
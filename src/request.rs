@@ -0,0 +1,94 @@
+use crate::StoreContext;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+thread_local! {
+    static REQUEST_CONTEXTS: RefCell<Vec<(u64, StoreContext)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_TOKEN: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Keeps a context current on this thread until dropped.
+///
+/// Guards may be dropped out of order: each removes its own entry, identified
+/// by a token, wherever it is in the stack.
+pub(crate) struct RequestGuard {
+    token: u64,
+}
+
+impl RequestGuard {
+    pub(crate) fn enter(context: StoreContext) -> Self {
+        let token = NEXT_TOKEN.with(|next| next.replace(next.get() + 1));
+        REQUEST_CONTEXTS.with(|contexts| contexts.borrow_mut().push((token, context)));
+        RequestGuard { token }
+    }
+}
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        REQUEST_CONTEXTS.with(|contexts| {
+            let mut contexts = contexts.borrow_mut();
+            if let Some(index) = contexts.iter().rposition(|(token, _)| *token == self.token) {
+                contexts.remove(index);
+            }
+        });
+    }
+}
+
+/// The store context of the request being handled on this task, if any.
+///
+/// While one is active, `use_store`, `get_store` and the other global store
+/// functions resolve against it instead of the process-wide registry.
+pub fn current_request_context() -> Option<StoreContext> {
+    REQUEST_CONTEXTS.with(|contexts| contexts.borrow().last().map(|(_, context)| context.clone()))
+}
+
+/// A future that makes its store context current every time it is polled.
+///
+/// Works like a task-local: the context follows the future across `.await`
+/// points and executor threads, and never leaks into other tasks.
+pub struct RequestScoped<F> {
+    context: StoreContext,
+    future: Pin<Box<F>>,
+}
+
+impl<F> RequestScoped<F> {
+    pub fn context(&self) -> &StoreContext {
+        &self.context
+    }
+}
+
+impl<F: Future> Future for RequestScoped<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let _guard = RequestGuard::enter(self.context.clone());
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// Runs `future` with `context` as its request context.
+///
+/// Wrap each request handler (or server function body) with it:
+///
+/// ```ignore
+/// with_request_context(StoreContext::with_name("request"), handler(req)).await
+/// ```
+pub fn with_request_context<F: Future>(context: StoreContext, future: F) -> RequestScoped<F> {
+    RequestScoped {
+        context,
+        future: Box::pin(future),
+    }
+}
+
+/// Runs `future` with a fresh, empty request context.
+pub fn request_scope<F: Future>(future: F) -> RequestScoped<F> {
+    with_request_context(StoreContext::with_name("request"), future)
+}
+
+/// Synchronous counterpart of [`with_request_context`].
+pub fn run_in_request_context<R>(context: StoreContext, f: impl FnOnce() -> R) -> R {
+    let _guard = RequestGuard::enter(context);
+    f()
+}
@@ -0,0 +1,32 @@
+use crate::{Observable, ObservableValue};
+
+/// State that can be put back to its initial values in place.
+///
+/// Unlike replacing the store, resetting keeps the instance that components
+/// and other stores hold, so their subscribers are notified. The store macros
+/// implement it; for hand-written stores, derive it (feature `macros`):
+///
+/// ```ignore
+/// #[derive(Clone, Default, Resettable)]
+/// struct CartStore {
+///     items: ObservableValue<Vec<Item>>,
+///     #[resettable(store)]
+///     checkout: CheckoutStore,
+///     #[resettable(skip)]
+///     api: ApiClient,
+/// }
+/// ```
+///
+/// The derive resets each observable to its value in `Self::default()`.
+pub trait Resettable {
+    /// Puts every field back to its initial value, notifying subscribers.
+    fn reset_state(&self);
+}
+
+#[cfg(feature = "macros")]
+pub use reaxive_macros::Resettable;
+
+#[doc(hidden)]
+pub fn __reset_field<T: Clone + 'static>(field: &ObservableValue<T>, initial: &ObservableValue<T>) {
+    field.assign(initial.peek());
+}
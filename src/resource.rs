@@ -0,0 +1,325 @@
+use crate::{report_error, ErrorSource, Observable, ObservableValue};
+use std::cell::{Cell, RefCell};
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResourceState<T, E> {
+    Loading,
+    Ready(T),
+    Error(E),
+}
+
+impl<T, E> ResourceState<T, E> {
+    pub fn is_loading(&self) -> bool {
+        matches!(self, ResourceState::Loading)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        matches!(self, ResourceState::Ready(_))
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, ResourceState::Error(_))
+    }
+
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            ResourceState::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn error(&self) -> Option<&E> {
+        match self {
+            ResourceState::Error(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+type Fetcher<T, E> = Box<dyn Fn() -> Pin<Box<dyn Future<Output = Result<T, E>>>>>;
+type ErrorHandler<E> = Box<dyn Fn(&E)>;
+
+struct ResourceInner<T: Clone + 'static, E: Clone + 'static> {
+    state: ObservableValue<ResourceState<T, E>>,
+    fetcher: Fetcher<T, E>,
+    generation: Cell<u64>,
+    in_flight: Cell<usize>,
+    refetch_interval: Cell<Option<Duration>>,
+    polling: Cell<bool>,
+    refetch_on_focus: Cell<bool>,
+    focus_listener: Cell<bool>,
+    on_error: RefCell<Option<ErrorHandler<E>>>,
+}
+
+/// Observable wrapper around an async fetcher.
+///
+/// Reading `state()` inside a reactive component subscribes it, so the
+/// component re-renders when the fetch starts, succeeds or fails.
+pub struct ObservableResource<T: Clone + 'static, E: Clone + 'static> {
+    inner: Rc<ResourceInner<T, E>>,
+}
+
+impl<T: Clone + 'static, E: Clone + 'static> Clone for ObservableResource<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Clone + 'static, E: Clone + 'static> ObservableResource<T, E> {
+    pub fn new<F, Fut>(fetcher: F) -> Self
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        Self {
+            inner: Rc::new(ResourceInner {
+                state: ObservableValue::new(ResourceState::Loading),
+                fetcher: Box::new(move || Box::pin(fetcher())),
+                generation: Cell::new(0),
+                in_flight: Cell::new(0),
+                refetch_interval: Cell::new(None),
+                polling: Cell::new(false),
+                refetch_on_focus: Cell::new(false),
+                focus_listener: Cell::new(false),
+                on_error: RefCell::new(None),
+            }),
+        }
+    }
+
+    pub fn state(&self) -> ResourceState<T, E> {
+        self.inner.state.get()
+    }
+
+    pub fn value(&self) -> Option<T> {
+        self.inner.state.map(|state| state.value().cloned())
+    }
+
+    pub fn error(&self) -> Option<E> {
+        self.inner.state.map(|state| state.error().cloned())
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.inner.state.when(|state| state.is_loading())
+    }
+
+    pub fn is_fetching(&self) -> bool {
+        self.inner.in_flight.get() > 0
+    }
+
+    /// Runs the fetcher and stores its result.
+    ///
+    /// If another fetch is started before this one completes, the older
+    /// result is discarded so a slow response never overwrites a newer one.
+    pub async fn fetch(&self) {
+        let inner = &self.inner;
+        let generation = inner.generation.get() + 1;
+        inner.generation.set(generation);
+
+        if !inner.state.when(|state| state.is_loading()) {
+            inner.state.assign(ResourceState::Loading);
+        }
+
+        let in_flight = InFlight::start(&inner.in_flight);
+        let result = (inner.fetcher)().await;
+        drop(in_flight);
+
+        if inner.generation.get() == generation {
+            if let (Err(error), Some(on_error)) = (&result, &*inner.on_error.borrow()) {
+                on_error(error);
+            }
+            inner.state.assign(match result {
+                Ok(value) => ResourceState::Ready(value),
+                Err(error) => ResourceState::Error(error),
+            });
+        }
+    }
+
+    /// Starts a new fetch on the current Dioxus runtime.
+    pub fn refetch(&self) -> dioxus::prelude::Task {
+        let resource = self.clone();
+        dioxus::prelude::spawn(async move {
+            resource.fetch().await;
+        })
+    }
+
+    /// Replaces the current state without running the fetcher.
+    pub fn set_ready(&self, value: T) {
+        self.inner.generation.set(self.inner.generation.get() + 1);
+        self.inner.state.assign(ResourceState::Ready(value));
+    }
+
+    pub fn observable(&self) -> &ObservableValue<ResourceState<T, E>> {
+        &self.inner.state
+    }
+
+    /// Refetches every `interval` until the resource is dropped.
+    ///
+    /// The polling task is spawned on the Dioxus runtime the first time an
+    /// interval is set; later calls only change the interval.
+    pub fn refetch_interval(self, interval: Duration) -> Self {
+        self.inner.refetch_interval.set(Some(interval));
+        if !self.inner.polling.replace(true) {
+            self.spawn_polling();
+        }
+        self
+    }
+
+    pub fn stop_polling(&self) {
+        self.inner.refetch_interval.set(None);
+    }
+
+    /// Refetches whenever the window regains focus, see [`notify_window_focus`].
+    pub fn refetch_on_focus(self, enabled: bool) -> Self {
+        self.inner.refetch_on_focus.set(enabled);
+        if enabled && !self.inner.focus_listener.replace(true) {
+            let weak = Rc::downgrade(&self.inner);
+            register_focus_listener(Rc::new(move || match weak.upgrade() {
+                Some(inner) => {
+                    if inner.refetch_on_focus.get() {
+                        ObservableResource { inner }.refetch();
+                    }
+                    true
+                }
+                None => false,
+            }));
+        }
+        self
+    }
+
+    /// Also adds every failed fetch to the global [`errors`](crate::errors).
+    pub fn report_errors(self) -> Self
+    where
+        E: Display + Send + Sync,
+    {
+        *self.inner.on_error.borrow_mut() = Some(Box::new(|error: &E| {
+            report_error(ErrorSource::Resource, error.clone());
+        }));
+        self
+    }
+
+    fn spawn_polling(&self) {
+        let weak: Weak<ResourceInner<T, E>> = Rc::downgrade(&self.inner);
+        dioxus::prelude::spawn_forever(async move {
+            loop {
+                let interval = match weak.upgrade() {
+                    Some(inner) => match inner.refetch_interval.get() {
+                        Some(interval) => interval,
+                        None => {
+                            inner.polling.set(false);
+                            break;
+                        }
+                    },
+                    None => break,
+                };
+
+                crate::time::sleep(interval).await;
+
+                let Some(inner) = weak.upgrade() else {
+                    break;
+                };
+                if inner.refetch_interval.get().is_some() && inner.in_flight.get() == 0 {
+                    ObservableResource { inner }.fetch().await;
+                }
+            }
+        });
+    }
+}
+
+/// Counts a fetch as in flight until dropped, even when its task is cancelled.
+struct InFlight<'a>(&'a Cell<usize>);
+
+impl<'a> InFlight<'a> {
+    fn start(count: &'a Cell<usize>) -> Self {
+        count.set(count.get() + 1);
+        Self(count)
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() - 1);
+    }
+}
+
+type FocusListener = Rc<dyn Fn() -> bool>;
+
+thread_local! {
+    static FOCUS_LISTENERS: RefCell<Vec<FocusListener>> = const { RefCell::new(Vec::new()) };
+}
+
+fn register_focus_listener(listener: FocusListener) {
+    #[cfg(target_arch = "wasm32")]
+    install_window_focus_listener();
+
+    FOCUS_LISTENERS.with(|listeners| listeners.borrow_mut().push(listener));
+}
+
+/// Refetches every live resource created with `refetch_on_focus(true)`.
+///
+/// In the browser this runs automatically on the window `focus` event;
+/// desktop apps can call it from their own window event handling.
+pub fn notify_window_focus() {
+    let listeners = FOCUS_LISTENERS.with(|listeners| listeners.borrow().clone());
+    let alive: Vec<bool> = listeners.iter().map(|listener| listener()).collect();
+
+    FOCUS_LISTENERS.with(|listeners| {
+        let mut listeners = listeners.borrow_mut();
+        let mut index = 0;
+        listeners.retain(|_| {
+            let keep = alive.get(index).copied().unwrap_or(true);
+            index += 1;
+            keep
+        });
+    });
+}
+
+#[cfg(target_arch = "wasm32")]
+fn install_window_focus_listener() {
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    thread_local! {
+        static INSTALLED: Cell<bool> = const { Cell::new(false) };
+    }
+
+    if INSTALLED.with(|installed| installed.replace(true)) {
+        return;
+    }
+
+    if let Some(window) = web_sys::window() {
+        let callback = Closure::<dyn FnMut()>::new(notify_window_focus);
+        let _ = window.add_event_listener_with_callback("focus", callback.as_ref().unchecked_ref());
+        callback.forget();
+    }
+}
+
+pub fn resource<T, E, F, Fut>(fetcher: F) -> ObservableResource<T, E>
+where
+    T: Clone + 'static,
+    E: Clone + 'static,
+    F: Fn() -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+{
+    ObservableResource::new(fetcher)
+}
+
+/// Creates a resource once per component and starts the first fetch.
+pub fn use_observable_resource<T, E, F, Fut>(fetcher: F) -> ObservableResource<T, E>
+where
+    T: Clone + 'static,
+    E: Clone + 'static,
+    F: Fn() -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+{
+    dioxus::prelude::use_hook(|| {
+        let resource = ObservableResource::new(fetcher);
+        resource.refetch();
+        resource
+    })
+}
@@ -0,0 +1,186 @@
+use crate::{Observable, ObservableValue};
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Exponential backoff configuration for [`retry`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub multiplier: f64,
+    pub max_delay: Duration,
+    /// Fraction of each delay that is randomized, from `0.0` to `1.0`.
+    pub jitter: f64,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..Self::default()
+        }
+    }
+
+    pub fn exponential(max_attempts: u32, initial_delay: Duration) -> Self {
+        Self {
+            initial_delay,
+            ..Self::new(max_attempts)
+        }
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn no_jitter(self) -> Self {
+        self.with_jitter(0.0)
+    }
+
+    /// Delay to wait after the given failed attempt (starting at 1).
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let base = self.initial_delay.as_secs_f64() * self.multiplier.powi(exponent);
+        let capped = base.min(self.max_delay.as_secs_f64());
+
+        let jittered = if self.jitter > 0.0 {
+            let spread = capped * self.jitter;
+            capped - spread + spread * random_unit()
+        } else {
+            capped
+        };
+
+        // Rounding can push delays near `Duration::MAX` past it.
+        Duration::try_from_secs_f64(jittered.max(0.0))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(200),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(10),
+            jitter: 0.5,
+        }
+    }
+}
+
+fn random_unit() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0x9e37_79b9_7f4a_7c15);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RetryStatus {
+    Idle,
+    Running,
+    Waiting(Duration),
+    Succeeded,
+    Failed,
+}
+
+/// Progress of a [`retry_with_state`] call, meant to be rendered in the UI.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryState {
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub status: RetryStatus,
+}
+
+impl RetryState {
+    pub fn is_retrying(&self) -> bool {
+        self.attempt > 1 && matches!(self.status, RetryStatus::Running | RetryStatus::Waiting(_))
+    }
+}
+
+impl Default for RetryState {
+    fn default() -> Self {
+        Self {
+            attempt: 0,
+            max_attempts: 0,
+            status: RetryStatus::Idle,
+        }
+    }
+}
+
+/// Calls `operation` until it succeeds or the policy runs out of attempts.
+///
+/// Returns the last error if every attempt fails.
+pub async fn retry<T, E, F, Fut>(policy: &RetryPolicy, operation: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    run_retry(policy, None, operation).await
+}
+
+/// Like [`retry`], but reports each attempt through `state`.
+pub async fn retry_with_state<T, E, F, Fut>(
+    policy: &RetryPolicy,
+    state: &ObservableValue<RetryState>,
+    operation: F,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    run_retry(policy, Some(state), operation).await
+}
+
+async fn run_retry<T, E, F, Fut>(
+    policy: &RetryPolicy,
+    state: Option<&ObservableValue<RetryState>>,
+    mut operation: F,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let report = |attempt: u32, status: RetryStatus| {
+        if let Some(state) = state {
+            state.assign(RetryState {
+                attempt,
+                max_attempts: policy.max_attempts,
+                status,
+            });
+        }
+    };
+
+    let mut attempt = 1;
+    loop {
+        report(attempt, RetryStatus::Running);
+
+        match operation().await {
+            Ok(value) => {
+                report(attempt, RetryStatus::Succeeded);
+                return Ok(value);
+            }
+            Err(error) if attempt >= policy.max_attempts => {
+                report(attempt, RetryStatus::Failed);
+                return Err(error);
+            }
+            Err(_) => {
+                let delay = policy.delay_for(attempt);
+                report(attempt, RetryStatus::Waiting(delay));
+                crate::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Route-scoped stores (feature `router`): `use reaxive::router::*;`
+//!
+//! Page-level state such as filters, scroll positions or wizard steps lives in
+//! a context that starts empty whenever another route is matched, so it resets
+//! naturally when the user navigates away:
+//!
+//! ```ignore
+//! #[component]
+//! fn PageLayout() -> Element {
+//!     rsx! {
+//!         RouteStoreProvider::<Route> {
+//!             Outlet::<Route> {}
+//!         }
+//!     }
+//! }
+//!
+//! #[component]
+//! fn Search() -> Element {
+//!     let filters = use_route_store::<FiltersStore>();
+//!     // ...
+//! }
+//! ```
+
+use crate::{Store, StoreContext};
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::mem::Discriminant;
+use std::rc::Rc;
+
+/// The context of the nearest [`RouteStoreProvider`], even through other providers.
+#[derive(Clone)]
+struct RouteContext(StoreContext);
+
+#[derive(Props, Clone, PartialEq)]
+pub struct RouteStoreProviderProps<R: Routable + Clone + PartialEq + 'static> {
+    /// Called with the fresh context of every matched route pattern, to pre-register stores.
+    stores: Option<Callback<StoreContext>>,
+    children: Element,
+    #[props(default)]
+    route: PhantomData<R>,
+}
+
+/// The context of the current route pattern, and the contexts it replaced
+/// that are cleared after the render.
+struct RouteScope<R> {
+    pattern: Discriminant<R>,
+    context: StoreContext,
+    replaced: Vec<StoreContext>,
+}
+
+fn route_context(
+    parent: &Option<StoreContext>,
+    stores: Option<Callback<StoreContext>>,
+) -> StoreContext {
+    let context = StoreContext::with_name("route");
+    let context = match parent {
+        Some(parent) => context.with_parent(parent.clone()),
+        None => context,
+    };
+    if let Some(stores) = stores {
+        stores.call(context.clone());
+    }
+    context
+}
+
+/// Provides a [`StoreContext`] per route pattern of `R`: navigating to another
+/// route variant starts with empty stores, while a change of its parameters or
+/// query keeps them. The previous context is cleared after the render that left
+/// it, and the current one when the provider unmounts.
+///
+/// Lookups that miss fall back to the enclosing provider, as with
+/// [`StoreProvider`](crate::StoreProvider).
+#[allow(non_snake_case)]
+pub fn RouteStoreProvider<R: Routable + Clone + PartialEq + 'static>(
+    props: RouteStoreProviderProps<R>,
+) -> Element {
+    // Each variant of a routable enum is one route pattern
+    let pattern = std::mem::discriminant(&use_route::<R>());
+    let parent = use_hook(try_consume_context::<StoreContext>);
+    let scope = use_hook(|| {
+        Rc::new(RefCell::new(RouteScope {
+            pattern,
+            context: route_context(&parent, props.stores),
+            replaced: Vec::new(),
+        }))
+    });
+
+    let context = {
+        let mut scope = scope.borrow_mut();
+        if scope.pattern != pattern {
+            let context = route_context(&parent, props.stores);
+            let previous = std::mem::replace(&mut scope.context, context);
+            scope.replaced.push(previous);
+            scope.pattern = pattern;
+            provide_context(RouteContext(scope.context.clone()));
+            provide_context(scope.context.clone());
+        }
+        scope.context.clone()
+    };
+
+    use_context_provider(|| RouteContext(context.clone()));
+    use_context_provider(|| context.clone());
+    use_after_render({
+        let scope = scope.clone();
+        move || {
+            let replaced = std::mem::take(&mut scope.borrow_mut().replaced);
+            for context in replaced {
+                context.clear();
+            }
+        }
+    });
+    use_drop(move || scope.borrow().context.clear());
+
+    rsx! {
+        {props.children}
+    }
+}
+
+/// The instance of `S` in the nearest [`RouteStoreProvider`], created for the
+/// current route if missing.
+///
+/// # Panics
+///
+/// If no [`RouteStoreProvider`] encloses the component.
+pub fn use_route_store<S: Store + Default + Send + Sync>() -> S {
+    let RouteContext(context) = try_consume_context::<RouteContext>()
+        .expect("use_route_store needs an enclosing RouteStoreProvider");
+    context.get_or_create::<S>()
+}
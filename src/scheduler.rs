@@ -0,0 +1,186 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+
+/// When writes are delivered to subscribers and observers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlushMode {
+    /// Every write notifies before returning.
+    #[default]
+    Sync,
+    /// Writes mark the observable dirty; all dirty observables are delivered
+    /// once, with their latest value, in the next microtask.
+    Microtask,
+    /// Like [`FlushMode::Microtask`], on the next animation frame.
+    Frame,
+    /// Dirty observables are only delivered by [`flush`].
+    Manual,
+}
+
+/// Sets how writes made on this thread are delivered; pending notifications
+/// keep their schedule. Other threads keep their own mode, `Sync` by default,
+/// so background threads without an event loop still deliver their writes.
+///
+/// Outside the browser there is no event loop to hook into, so `Microtask`
+/// and `Frame` behave like `Manual`: call [`flush`] from your own loop.
+pub fn set_flush_mode(mode: FlushMode) {
+    FLUSH_MODE.with(|current| current.set(mode));
+}
+
+/// The [`FlushMode`] of this thread.
+pub fn flush_mode() -> FlushMode {
+    FLUSH_MODE.with(Cell::get)
+}
+
+type Delivery = Box<dyn FnOnce()>;
+
+thread_local! {
+    static FLUSH_MODE: Cell<FlushMode> = const { Cell::new(FlushMode::Sync) };
+    static DIRTY: RefCell<Vec<Delivery>> = const { RefCell::new(Vec::new()) };
+    // Keys of the observables in `DIRTY`, so each is queued once per thread
+    static QUEUED: RefCell<HashSet<usize>> = RefCell::new(HashSet::new());
+    static BATCH_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static FLUSH_SCHEDULED: Cell<bool> = const { Cell::new(false) };
+    static FLUSHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether a write on this thread should be queued instead of delivered at once.
+pub(crate) fn is_deferred() -> bool {
+    flush_mode() != FlushMode::Sync || BATCH_DEPTH.with(Cell::get) > 0
+}
+
+/// Queues the delivery of the dirty observable `key` on this thread, unless
+/// it is queued already.
+pub(crate) fn enqueue_once(key: usize, delivery: Delivery) {
+    if !QUEUED.with(|queued| queued.borrow_mut().insert(key)) {
+        return;
+    }
+    let queued = QueuedKey(key);
+    enqueue(Box::new(move || {
+        drop(queued);
+        delivery();
+    }));
+}
+
+/// Removes its key from `QUEUED` once the delivery runs, or is dropped by a panicking flush.
+struct QueuedKey(usize);
+
+impl Drop for QueuedKey {
+    fn drop(&mut self) {
+        let _ = QUEUED.try_with(|queued| queued.borrow_mut().remove(&self.0));
+    }
+}
+
+fn enqueue(delivery: Delivery) {
+    DIRTY.with(|dirty| dirty.borrow_mut().push(delivery));
+
+    if BATCH_DEPTH.with(Cell::get) == 0 && !FLUSHING.with(Cell::get) {
+        schedule_flush();
+    }
+}
+
+fn schedule_flush() {
+    if FLUSH_SCHEDULED.with(|scheduled| scheduled.replace(true)) {
+        return;
+    }
+
+    match flush_mode() {
+        // Only reached when a batch ended without flushing, e.g. after a panic.
+        FlushMode::Sync => flush(),
+        FlushMode::Microtask => platform::queue_microtask(flush),
+        FlushMode::Frame => platform::request_frame(flush),
+        FlushMode::Manual => {}
+    }
+}
+
+/// Delivers every dirty observable of this thread once, including the ones
+/// dirtied by the deliveries themselves.
+pub fn flush() {
+    if FLUSHING.with(|flushing| flushing.replace(true)) {
+        return;
+    }
+    FLUSH_SCHEDULED.with(|scheduled| scheduled.set(false));
+    let _guard = FlushGuard;
+
+    loop {
+        let dirty = DIRTY.with(|dirty| std::mem::take(&mut *dirty.borrow_mut()));
+        if dirty.is_empty() {
+            break;
+        }
+        for deliver in dirty {
+            deliver();
+        }
+    }
+}
+
+struct FlushGuard;
+
+impl Drop for FlushGuard {
+    fn drop(&mut self) {
+        FLUSHING.with(|flushing| flushing.set(false));
+    }
+}
+
+/// Number of observables waiting for the next flush on this thread.
+pub fn pending_notifications() -> usize {
+    DIRTY.with(|dirty| dirty.borrow().len())
+}
+
+/// Runs `f`, delivering its writes once when it returns, whatever the [`FlushMode`]:
+///
+/// ```ignore
+/// batch(|| {
+///     store.x.assign(10);
+///     store.y.assign(20);
+/// }); // subscribers of x and y run once, here
+/// ```
+pub fn batch<R>(f: impl FnOnce() -> R) -> R {
+    BATCH_DEPTH.with(|depth| depth.set(depth.get() + 1));
+    let _guard = BatchGuard;
+    f()
+}
+
+struct BatchGuard;
+
+impl Drop for BatchGuard {
+    fn drop(&mut self) {
+        let depth = BATCH_DEPTH.with(|depth| {
+            depth.set(depth.get() - 1);
+            depth.get()
+        });
+        if depth == 0 && !std::thread::panicking() {
+            if flush_mode() == FlushMode::Sync {
+                flush();
+            } else if pending_notifications() > 0 {
+                schedule_flush();
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    pub(super) fn queue_microtask(f: fn()) {
+        // Futures spawned with `spawn_local` are polled from a microtask.
+        wasm_bindgen_futures::spawn_local(async move { f() });
+    }
+
+    pub(super) fn request_frame(f: fn()) {
+        let callback = Closure::once_into_js(move || f());
+        match web_sys::window() {
+            Some(window) => {
+                let _ = window.request_animation_frame(callback.unchecked_ref());
+            }
+            None => queue_microtask(f),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    pub(super) fn queue_microtask(_f: fn()) {}
+
+    pub(super) fn request_frame(_f: fn()) {}
+}
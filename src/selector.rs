@@ -0,0 +1,54 @@
+use crate::{use_store, Computed, Store};
+
+/// A memoized projection of a global store.
+///
+/// The selector only re-runs when an observable it read changes, and clones
+/// share the cached result, so one selector can be used by many components.
+pub struct Selector<S: Store, T: Clone + 'static> {
+    computed: Computed<T>,
+    _store: std::marker::PhantomData<S>,
+}
+
+impl<S: Store, T: Clone + 'static> Clone for Selector<S, T> {
+    fn clone(&self) -> Self {
+        Self {
+            computed: self.computed.clone(),
+            _store: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S: Store + Default + Send + Sync, T: Clone + 'static> Selector<S, T> {
+    pub fn new<F>(select: F) -> Self
+    where
+        F: Fn(&S) -> T + 'static,
+    {
+        Self {
+            computed: Computed::new(move || select(&use_store::<S>())),
+            _store: std::marker::PhantomData,
+        }
+    }
+
+    pub fn get(&self) -> T {
+        self.computed.get()
+    }
+
+    pub fn map<U, F: FnOnce(&T) -> U>(&self, mapper: F) -> U {
+        self.computed.map(mapper)
+    }
+
+    pub fn computed(&self) -> &Computed<T> {
+        &self.computed
+    }
+}
+
+/// Creates a memoized selector over the global instance of `S`:
+/// `create_selector(|store: &AppStore| store.user.get().name)`
+pub fn create_selector<S, T, F>(select: F) -> Selector<S, T>
+where
+    S: Store + Default + Send + Sync,
+    T: Clone + 'static,
+    F: Fn(&S) -> T + 'static,
+{
+    Selector::new(select)
+}
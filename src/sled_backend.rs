@@ -0,0 +1,81 @@
+//! sled storage for persisted values (feature `sled`, native only).
+//!
+//! Suited to desktop apps with large state: every persisted observable is its
+//! own key, so a change rewrites only that value instead of a whole file. The
+//! part of a key before the first `.` picks the sled tree, giving each store
+//! its own namespace: `"cart.items"` is the key `items` in the tree `cart`.
+//!
+//! ```ignore
+//! SledBackend::open("app-data")?.install();
+//! persist(&cart.items, "cart.items");
+//! ```
+
+use crate::{set_storage_backend, PersistError, StorageBackend};
+use std::path::Path;
+
+/// Persistence backend over a sled database.
+#[derive(Clone)]
+pub struct SledBackend {
+    db: sled::Db,
+}
+
+impl SledBackend {
+    /// Opens (or creates) the database in the directory `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PersistError> {
+        let db = sled::open(path).map_err(storage_error)?;
+        Ok(Self { db })
+    }
+
+    /// Makes [`persist`](crate::persist) and the other persistence functions use this database.
+    pub fn install(self) {
+        set_storage_backend(self);
+    }
+
+    /// Waits until every write so far is on disk; sled otherwise flushes periodically.
+    pub fn flush(&self) -> Result<(), PersistError> {
+        self.db.flush().map(|_| ()).map_err(storage_error)
+    }
+
+    /// Removes every value persisted under `namespace`.
+    pub fn clear_namespace(&self, namespace: &str) -> Result<(), PersistError> {
+        self.db
+            .drop_tree(namespace)
+            .map(|_| ())
+            .map_err(storage_error)
+    }
+
+    /// The tree holding `key`, and the key within it.
+    fn locate<'k>(&self, key: &'k str) -> Result<(sled::Tree, &'k str), PersistError> {
+        match key.split_once('.') {
+            Some((namespace, key)) => {
+                let tree = self.db.open_tree(namespace).map_err(storage_error)?;
+                Ok((tree, key))
+            }
+            None => Ok(((*self.db).clone(), key)),
+        }
+    }
+}
+
+impl StorageBackend for SledBackend {
+    fn load(&self, key: &str) -> Option<String> {
+        let (tree, key) = self.locate(key).ok()?;
+        let value = tree.get(key).ok()??;
+        String::from_utf8(value.to_vec()).ok()
+    }
+
+    fn save(&self, key: &str, json: &str) -> Result<(), PersistError> {
+        let (tree, key) = self.locate(key)?;
+        tree.insert(key, json.as_bytes()).map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) {
+        if let Ok((tree, key)) = self.locate(key) {
+            let _ = tree.remove(key);
+        }
+    }
+}
+
+fn storage_error(error: sled::Error) -> PersistError {
+    PersistError::Storage(error.to_string())
+}
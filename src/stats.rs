@@ -0,0 +1,162 @@
+use crate::ObservableValue;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, Weak};
+
+/// Activity counters of one observable.
+#[derive(Default)]
+pub(crate) struct Counters {
+    notifications: AtomicU64,
+    subscriber_calls: AtomicU64,
+    rerenders: AtomicU64,
+    subscribers: AtomicUsize,
+    /// Whether these counters are listed in [`LABELED`], to unlist them on drop.
+    labeled: AtomicBool,
+}
+
+impl Drop for Counters {
+    fn drop(&mut self) {
+        if self.labeled.load(Ordering::Relaxed) {
+            LABELED
+                .lock()
+                .unwrap()
+                .retain(|(_, counters)| counters.strong_count() > 0);
+        }
+    }
+}
+
+impl Counters {
+    pub(crate) fn notified(&self) {
+        self.notifications.fetch_add(1, Ordering::Relaxed);
+        GLOBAL.notifications.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn called_subscriber(&self) {
+        self.subscriber_calls.fetch_add(1, Ordering::Relaxed);
+        GLOBAL.subscriber_calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn triggered_rerender(&self) {
+        self.rerenders.fetch_add(1, Ordering::Relaxed);
+        GLOBAL.rerenders.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn subscribed(&self) {
+        self.subscribers.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn unsubscribed(&self) {
+        self.subscribers.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self, label: Option<String>, observers: usize) -> ObservableStats {
+        ObservableStats {
+            label,
+            notifications: self.notifications.load(Ordering::Relaxed),
+            subscriber_calls: self.subscriber_calls.load(Ordering::Relaxed),
+            rerenders: self.rerenders.load(Ordering::Relaxed),
+            subscribers: self.subscribers.load(Ordering::Relaxed),
+            observers,
+        }
+    }
+
+    fn reset(&self) {
+        self.notifications.store(0, Ordering::Relaxed);
+        self.subscriber_calls.store(0, Ordering::Relaxed);
+        self.rerenders.store(0, Ordering::Relaxed);
+    }
+}
+
+type Labeled = Vec<(String, Weak<Counters>)>;
+
+static GLOBAL: LazyLock<Counters> = LazyLock::new(Counters::default);
+static OBSERVABLES_CREATED: AtomicU64 = AtomicU64::new(0);
+static LABELED: LazyLock<Mutex<Labeled>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+pub(crate) fn observable_created() {
+    OBSERVABLES_CREATED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counters of a single observable.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObservableStats {
+    pub label: Option<String>,
+    /// Writes that notified subscribers.
+    pub notifications: u64,
+    /// `subscribe` callbacks invoked.
+    pub subscriber_calls: u64,
+    /// Reactive components and computed values re-run because of a write.
+    pub rerenders: u64,
+    pub subscribers: usize,
+    /// Live reactive observers; only counted for [`ObservableValue::stats`].
+    pub observers: usize,
+}
+
+/// Process-wide counters, returned by [`stats`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub observables_created: u64,
+    pub notifications: u64,
+    pub subscriber_calls: u64,
+    pub rerenders: u64,
+    /// Every live labeled observable, most notified first.
+    pub observables: Vec<ObservableStats>,
+}
+
+/// Snapshot of the global counters; label observables with
+/// [`ObservableValue::with_label`] to see them individually.
+pub fn stats() -> Stats {
+    // Upgraded outside the lock: dropping the last reference unlists the counters.
+    let labeled = LABELED.lock().unwrap().clone();
+    let mut observables: Vec<_> = labeled
+        .iter()
+        .filter_map(|(label, counters)| {
+            counters
+                .upgrade()
+                .map(|counters| counters.snapshot(Some(label.clone()), 0))
+        })
+        .collect();
+    observables.sort_by_key(|observable| std::cmp::Reverse(observable.notifications));
+
+    Stats {
+        observables_created: OBSERVABLES_CREATED.load(Ordering::Relaxed),
+        notifications: GLOBAL.notifications.load(Ordering::Relaxed),
+        subscriber_calls: GLOBAL.subscriber_calls.load(Ordering::Relaxed),
+        rerenders: GLOBAL.rerenders.load(Ordering::Relaxed),
+        observables,
+    }
+}
+
+/// Zeroes the global counters and those of labeled observables.
+pub fn reset_stats() {
+    OBSERVABLES_CREATED.store(0, Ordering::Relaxed);
+    GLOBAL.reset();
+    let labeled = LABELED.lock().unwrap().clone();
+    for (_, counters) in labeled {
+        if let Some(counters) = counters.upgrade() {
+            counters.reset();
+        }
+    }
+}
+
+impl<T: Clone + 'static> ObservableValue<T> {
+    /// Names the observable in [`stats`] output: `observable(vec![]).with_label("cart.items")`
+    pub fn with_label(self, label: impl Into<String>) -> Self {
+        self.counters.labeled.store(true, Ordering::Relaxed);
+        LABELED
+            .lock()
+            .unwrap()
+            .push((label.into(), Arc::downgrade(&self.counters)));
+        self
+    }
+
+    /// Counters of this observable
+    pub fn stats(&self) -> ObservableStats {
+        let label = LABELED
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(_, counters)| counters.as_ptr() == Arc::as_ptr(&self.counters))
+            .map(|(label, _)| label.clone());
+        self.counters.snapshot(label, self.observer_count())
+    }
+}
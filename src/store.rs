@@ -1,66 +1,506 @@
-use crate::ObservableValue;
+use crate::context::scoped_context;
+use crate::inject::{construct_in, expect_resolved, resolve_in};
+use crate::lifecycle::notify_lifecycle;
+use crate::visibility::{assert_not_private, provided};
+use crate::{is_private, ActionTokens, GetterCache, Injectable, LifecycleEvent, Resettable};
 use std::any::{Any, TypeId};
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::{Arc, LazyLock, Mutex};
+use std::time::SystemTime;
 
 pub trait Store: Clone + 'static {
     fn id(&self) -> TypeId;
     fn name(&self) -> &'static str {
         std::any::type_name::<Self>()
     }
+
+    /// Called when a registry or context registers this instance, before the
+    /// lifecycle hooks; the store macros attach persistence here.
+    #[doc(hidden)]
+    fn __registered(&self) {}
+}
+
+/// A store reached through a trait object, so another implementation can be
+/// registered in its place, e.g. a mock in tests:
+///
+/// ```ignore
+/// trait Api: Send + Sync {
+///     fn fetch_user(&self, id: u32) -> User;
+/// }
+///
+/// register_store(DynStore::<dyn Api>::new(Arc::new(HttpApi::new())));
+/// let api = get_store::<DynStore<dyn Api>>().unwrap();
+/// api.fetch_user(1);
+/// ```
+pub struct DynStore<T: ?Sized>(Arc<T>);
+
+impl<T: ?Sized> DynStore<T> {
+    pub fn new(implementation: Arc<T>) -> Self {
+        Self(implementation)
+    }
+}
+
+impl<T: ?Sized> Clone for DynStore<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> std::ops::Deref for DynStore<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> From<Arc<T>> for DynStore<T> {
+    fn from(implementation: Arc<T>) -> Self {
+        Self(implementation)
+    }
+}
+
+impl<T: ?Sized + 'static> Store for DynStore<T> {
+    fn id(&self) -> TypeId {
+        TypeId::of::<Self>()
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+}
+
+/// State the stores declared with [`multi_store!`](crate::multi_store) keep
+/// besides their fields, shared by clones.
+#[doc(hidden)]
+#[derive(Clone, Default)]
+pub struct StoreInternals {
+    pub getters: GetterCache,
+    pub actions: ActionTokens,
+}
+
+/// A registered store and when it was registered.
+#[derive(Clone)]
+pub(crate) struct StoreEntry {
+    pub(crate) store: Arc<dyn Any + Send + Sync>,
+    name: &'static str,
+    registered_at: SystemTime,
+    registered: fn(&(dyn Any + Send + Sync)),
+}
+
+impl StoreEntry {
+    pub(crate) fn new<S: Store + Send + Sync>(store: S) -> Self {
+        Self {
+            name: store.name(),
+            store: Arc::new(store),
+            registered_at: crate::time::now(),
+            registered: |store| {
+                if let Some(store) = store.downcast_ref::<S>() {
+                    store.__registered();
+                }
+            },
+        }
+    }
+
+    pub(crate) fn downcast<S: Store>(&self) -> Option<S> {
+        self.store.downcast_ref::<S>().cloned()
+    }
+
+    fn info(&self, type_id: TypeId, key: Option<String>) -> StoreInfo {
+        StoreInfo {
+            type_name: self.name,
+            type_id,
+            key,
+            registered_at: self.registered_at,
+            store: self.store.clone(),
+        }
+    }
+}
+
+pub(crate) type Stores = HashMap<TypeId, StoreEntry>;
+pub(crate) type KeyedStores = HashMap<(TypeId, String), StoreEntry>;
+pub(crate) type StoreFactory = Arc<dyn Fn() -> StoreEntry + Send + Sync>;
+pub(crate) type AsyncStoreFactory =
+    Arc<dyn Fn() -> Pin<Box<dyn Future<Output = StoreEntry>>> + Send + Sync>;
+
+/// A constructor registered with `register_lazy` or `register_lazy_async`.
+#[derive(Clone)]
+pub(crate) enum LazyFactory {
+    Sync(StoreFactory),
+    Async(AsyncStoreFactory),
+}
+
+pub(crate) type Factories = HashMap<TypeId, LazyFactory>;
+
+pub(crate) fn store_factory<S, F>(factory: F) -> LazyFactory
+where
+    S: Store + Send + Sync,
+    F: Fn() -> S + Send + Sync + 'static,
+{
+    LazyFactory::Sync(Arc::new(move || StoreEntry::new(factory())))
+}
+
+pub(crate) fn async_store_factory<S, F, Fut>(factory: F) -> LazyFactory
+where
+    S: Store + Send + Sync,
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = S> + 'static,
+{
+    LazyFactory::Async(Arc::new(move || {
+        let store = factory();
+        Box::pin(async move { StoreEntry::new(store.await) })
+    }))
+}
+
+/// Describes a registered store; returned by [`StoreRegistry::list`].
+#[derive(Clone)]
+pub struct StoreInfo {
+    pub type_name: &'static str,
+    pub type_id: TypeId,
+    /// The key of a keyed instance, `None` for the single instance of its type.
+    pub key: Option<String>,
+    pub registered_at: SystemTime,
+    store: Arc<dyn Any + Send + Sync>,
+}
+
+impl StoreInfo {
+    pub fn name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn downcast<S: Store>(&self) -> Option<S> {
+        self.store.downcast_ref::<S>().cloned()
+    }
+
+    /// The store's state as JSON, if its type was registered with
+    /// [`register_hydratable`](crate::register_hydratable).
+    #[cfg(feature = "serde")]
+    pub fn serialize(&self) -> Option<Result<serde_json::Value, serde_json::Error>> {
+        crate::hydration::snapshot_store(self.type_id, self.store.as_ref())
+    }
+
+    /// Applies JSON state to the live store, like [`SerializableStore::restore`](crate::SerializableStore::restore).
+    #[cfg(feature = "serde")]
+    pub fn restore(&self, value: serde_json::Value) -> Option<Result<(), serde_json::Error>> {
+        crate::hydration::restore_store(self.type_id, self.store.as_ref(), value)
+    }
+
+    /// Puts the fields of the live store back to their default values.
+    #[cfg(feature = "serde")]
+    pub fn reset(&self) -> Option<Result<(), serde_json::Error>> {
+        crate::hydration::reset_store(self.type_id, self.store.as_ref())
+    }
+
+    /// Subscribes the current observer to the serialized fields.
+    #[cfg(feature = "serde")]
+    pub fn track(&self) {
+        crate::hydration::track_store(self.type_id, self.store.as_ref());
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn field_stats(&self) -> Vec<(&'static str, crate::ObservableStats)> {
+        crate::hydration::store_field_stats(self.type_id, self.store.as_ref())
+    }
+}
+
+/// Two infos are equal when they describe the same store instance.
+impl PartialEq for StoreInfo {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.store, &other.store) && self.key == other.key
+    }
+}
+
+impl std::fmt::Debug for StoreInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoreInfo")
+            .field("type_name", &self.type_name)
+            .field("key", &self.key)
+            .field("registered_at", &self.registered_at)
+            .finish()
+    }
+}
+
+/// Every store in `stores` and `keyed`, oldest registration first.
+pub(crate) fn list_entries(stores: &Stores, keyed: &KeyedStores) -> Vec<StoreInfo> {
+    let mut infos: Vec<_> = stores
+        .iter()
+        .map(|(type_id, entry)| entry.info(*type_id, None))
+        .chain(
+            keyed
+                .iter()
+                .map(|((type_id, key), entry)| entry.info(*type_id, Some(key.clone()))),
+        )
+        .collect();
+    infos.sort_by_key(|info| info.registered_at);
+    infos
+}
+
+/// Builds `S` from its lazy factory, if one is registered and is not async.
+///
+/// The factory runs without holding any lock, so it may resolve other stores.
+/// If another thread registered `S` meanwhile, that instance wins.
+pub(crate) fn build_lazy<S: Store>(
+    stores: &Mutex<Stores>,
+    factories: &Mutex<Factories>,
+) -> Option<S> {
+    let type_id = TypeId::of::<S>();
+    let Some(LazyFactory::Sync(factory)) = factories.lock().unwrap().get(&type_id).cloned() else {
+        return None;
+    };
+    insert_built(stores, type_id, factory()).downcast::<S>()
+}
+
+/// Like [`build_lazy`], awaiting an async factory.
+pub(crate) async fn build_lazy_async<S: Store>(
+    stores: &Mutex<Stores>,
+    factories: &Mutex<Factories>,
+) -> Option<S> {
+    let type_id = TypeId::of::<S>();
+    let factory = factories.lock().unwrap().get(&type_id).cloned()?;
+    let built = match factory {
+        LazyFactory::Sync(factory) => factory(),
+        LazyFactory::Async(factory) => factory().await,
+    };
+    insert_built(stores, type_id, built).downcast::<S>()
+}
+
+/// Registers `built` unless a store of `type_id` exists, returning the registered entry.
+pub(crate) fn insert_built(
+    stores: &Mutex<Stores>,
+    type_id: TypeId,
+    built: StoreEntry,
+) -> StoreEntry {
+    let (entry, inserted) = match stores.lock().unwrap().entry(type_id) {
+        Entry::Occupied(entry) => (entry.get().clone(), false),
+        Entry::Vacant(entry) => (entry.insert(built).clone(), true),
+    };
+    if inserted {
+        (entry.registered)(entry.store.as_ref());
+        notify_lifecycle(type_id, entry.store.as_ref(), LifecycleEvent::Register);
+    }
+    entry
+}
+
+/// Fires the lifecycle hooks for a store that replaced `previous` under `type_id`.
+pub(crate) fn notify_replaced(type_id: TypeId, previous: Option<StoreEntry>, entry: &StoreEntry) {
+    if let Some(previous) = previous {
+        notify_lifecycle(type_id, previous.store.as_ref(), LifecycleEvent::Remove);
+    }
+    (entry.registered)(entry.store.as_ref());
+    notify_lifecycle(type_id, entry.store.as_ref(), LifecycleEvent::Register);
+}
+
+pub(crate) fn notify_removed<K>(stores: impl IntoIterator<Item = (K, StoreEntry)>)
+where
+    K: std::borrow::Borrow<TypeId>,
+{
+    for (type_id, entry) in stores {
+        notify_lifecycle(
+            *type_id.borrow(),
+            entry.store.as_ref(),
+            LifecycleEvent::Remove,
+        );
+    }
+}
+
+/// Runs the clear hooks of each store, then `before_remove`, ahead of a clear.
+pub(crate) fn notify_clearing(stores: &[StoreInfo], mut before_remove: impl FnMut(&StoreInfo)) {
+    for info in stores {
+        notify_lifecycle(info.type_id, info.store.as_ref(), LifecycleEvent::Clear);
+        before_remove(info);
+    }
 }
 
 #[derive(Clone)]
 pub struct StoreRegistry {
-    stores: Arc<Mutex<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>>,
+    stores: Arc<Mutex<Stores>>,
+    keyed: Arc<Mutex<KeyedStores>>,
+    factories: Arc<Mutex<Factories>>,
 }
 
 impl StoreRegistry {
     pub fn new() -> Self {
         Self {
             stores: Arc::new(Mutex::new(HashMap::new())),
+            keyed: Arc::new(Mutex::new(HashMap::new())),
+            factories: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
     pub fn register<S: Store + Send + Sync + 'static>(&self, store: S) {
         let type_id = store.id();
-        self.stores.lock().unwrap().insert(type_id, Arc::new(store));
+        let entry = StoreEntry::new(store);
+        let previous = self.stores.lock().unwrap().insert(type_id, entry.clone());
+        notify_replaced(type_id, previous, &entry);
+    }
+
+    /// Registers `store` unless an `S` is registered already, returning the registered one.
+    #[cfg(feature = "serde")]
+    pub(crate) fn register_if_absent<S: Store + Send + Sync>(&self, store: S) -> S {
+        insert_built(
+            &self.stores,
+            TypeId::of::<S>(),
+            StoreEntry::new(store.clone()),
+        )
+        .downcast::<S>()
+        .unwrap_or(store)
+    }
+
+    /// Registers a constructor that builds `S` the first time it is looked up.
+    ///
+    /// Lets stores without `Default`, or that need configuration, be resolved lazily.
+    pub fn register_lazy<S, F>(&self, factory: F)
+    where
+        S: Store + Send + Sync,
+        F: Fn() -> S + Send + Sync + 'static,
+    {
+        self.factories
+            .lock()
+            .unwrap()
+            .insert(TypeId::of::<S>(), store_factory(factory));
+    }
+
+    /// Registers an async constructor, awaited by [`get_async`](Self::get_async)
+    /// the first time `S` is looked up. [`get`](Self::get) does not run it.
+    pub fn register_lazy_async<S, F, Fut>(&self, factory: F)
+    where
+        S: Store + Send + Sync,
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = S> + 'static,
+    {
+        self.factories
+            .lock()
+            .unwrap()
+            .insert(TypeId::of::<S>(), async_store_factory(factory));
+    }
+
+    /// Like [`get`](Self::get), also awaiting an async lazy factory.
+    pub async fn get_async<S: Store>(&self) -> Option<S> {
+        if let Some(store) = self.get::<S>() {
+            return Some(store);
+        }
+        build_lazy_async(&self.stores, &self.factories).await
     }
 
     pub fn get<S: Store + 'static>(&self) -> Option<S> {
-        self.stores
+        let existing = self
+            .stores
             .lock()
             .unwrap()
             .get(&TypeId::of::<S>())
-            .and_then(|store| store.downcast_ref::<S>())
-            .cloned()
+            .and_then(StoreEntry::downcast::<S>);
+        existing.or_else(|| build_lazy(&self.stores, &self.factories))
     }
 
-    pub fn get_or_create<S: Store + Default + Send + Sync + 'static>(&self) -> S {
-        if let Some(store) = self.get::<S>() {
+    /// The registered `S`, or a new one constructed from its
+    /// [`Injectable`](crate::Injectable) dependencies in this registry.
+    pub fn get_or_create<S: Injectable>(&self) -> S {
+        expect_resolved(resolve_in::<S, _>(self))
+    }
+
+    pub fn has<S: Store + 'static>(&self) -> bool {
+        self.stores.lock().unwrap().contains_key(&TypeId::of::<S>())
+    }
+
+    pub fn remove<S: Store + 'static>(&self) {
+        let removed = self.stores.lock().unwrap().remove_entry(&TypeId::of::<S>());
+        notify_removed(removed);
+    }
+
+    /// Registers one of several independent instances of `S`, under `key`.
+    pub fn register_keyed<S: Store + Send + Sync + 'static>(
+        &self,
+        key: impl Into<String>,
+        store: S,
+    ) {
+        let type_id = TypeId::of::<S>();
+        let entry = StoreEntry::new(store);
+        let previous = self
+            .keyed
+            .lock()
+            .unwrap()
+            .insert((type_id, key.into()), entry.clone());
+        notify_replaced(type_id, previous, &entry);
+    }
+
+    pub fn get_keyed<S: Store + 'static>(&self, key: &str) -> Option<S> {
+        self.keyed
+            .lock()
+            .unwrap()
+            .get(&(TypeId::of::<S>(), key.to_string()))
+            .and_then(StoreEntry::downcast::<S>)
+    }
+
+    pub fn get_or_create_keyed<S: Injectable>(&self, key: &str) -> S {
+        if let Some(store) = self.get_keyed::<S>(key) {
             store
         } else {
-            let store = S::default();
-            self.register(store.clone());
+            let store = expect_resolved(construct_in::<S, _>(self));
+            self.register_keyed(key, store.clone());
             store
         }
     }
 
-    pub fn has<S: Store + 'static>(&self) -> bool {
-        self.stores.lock().unwrap().contains_key(&TypeId::of::<S>())
+    pub fn has_keyed<S: Store + 'static>(&self, key: &str) -> bool {
+        self.keyed
+            .lock()
+            .unwrap()
+            .contains_key(&(TypeId::of::<S>(), key.to_string()))
     }
 
-    pub fn remove<S: Store + 'static>(&self) {
-        self.stores.lock().unwrap().remove(&TypeId::of::<S>());
+    pub fn remove_keyed<S: Store + 'static>(&self, key: &str) {
+        let removed = self
+            .keyed
+            .lock()
+            .unwrap()
+            .remove(&(TypeId::of::<S>(), key.to_string()));
+        notify_removed(removed.map(|entry| (TypeId::of::<S>(), entry)));
+    }
+
+    /// Keys of every registered instance of `S`.
+    pub fn keys<S: Store + 'static>(&self) -> Vec<String> {
+        self.keyed
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|(type_id, _)| *type_id == TypeId::of::<S>())
+            .map(|(_, key)| key.clone())
+            .collect()
     }
 
     pub fn clear(&self) {
-        self.stores.lock().unwrap().clear();
+        self.clear_with(|_| {});
+    }
+
+    /// Like [`clear`](Self::clear), calling `before_remove` on every store first.
+    pub fn clear_with(&self, before_remove: impl FnMut(&StoreInfo)) {
+        notify_clearing(&self.list(), before_remove);
+
+        let stores = std::mem::take(&mut *self.stores.lock().unwrap());
+        let keyed = std::mem::take(&mut *self.keyed.lock().unwrap());
+        self.factories.lock().unwrap().clear();
+
+        notify_removed(stores);
+        notify_removed(
+            keyed
+                .into_iter()
+                .map(|((type_id, _), store)| (type_id, store)),
+        );
     }
 
     pub fn count(&self) -> usize {
         self.stores.lock().unwrap().len()
     }
+
+    /// Every registered store, keyed instances included, oldest first.
+    pub fn list(&self) -> Vec<StoreInfo> {
+        let stores = self.stores.lock().unwrap().clone();
+        let keyed = self.keyed.lock().unwrap().clone();
+        list_entries(&stores, &keyed)
+    }
 }
 
 static GLOBAL_STORE_REGISTRY: LazyLock<Mutex<StoreRegistry>> =
@@ -73,64 +513,202 @@ pub fn get_global_registry() -> std::sync::MutexGuard<'static, StoreRegistry> {
 }
 
 pub fn create_store<S: Store + Send + Sync + 'static>(store: S) -> S {
-    let registry = get_global_registry();
-    registry.register(store.clone());
+    register_store(store.clone());
     store
 }
 
-pub fn use_store<S: Store + Default + Send + Sync + 'static>() -> S {
-    let registry = get_global_registry();
+pub fn use_store<S: Injectable>() -> S {
+    if let Some(context) = scoped_context() {
+        return context.get_or_create::<S>();
+    }
+    if is_private::<S>() {
+        if let Some(store) = provided::<S>() {
+            return store;
+        }
+        assert_not_private::<S>();
+    }
+    let registry = get_global_registry().clone();
     registry.get_or_create::<S>()
 }
 
 pub fn get_store<S: Store + 'static>() -> Option<S> {
-    let registry = get_global_registry();
+    if let Some(context) = scoped_context() {
+        return context.get::<S>();
+    }
+    if is_private::<S>() {
+        return provided::<S>();
+    }
+    let registry = get_global_registry().clone();
     registry.get::<S>()
 }
 
 pub fn register_store<S: Store + Send + Sync + 'static>(store: S) {
-    let registry = get_global_registry();
+    if let Some(context) = scoped_context() {
+        return context.register(store);
+    }
+    let registry = get_global_registry().clone();
     registry.register(store);
 }
 
+/// Registers `store` unless an `S` is registered already, in the current
+/// context or globally, returning the registered one.
+#[cfg(feature = "serde")]
+pub(crate) fn register_store_if_absent<S: Store + Send + Sync + 'static>(store: S) -> S {
+    if let Some(context) = scoped_context() {
+        return context.register_if_absent(store);
+    }
+    let registry = get_global_registry().clone();
+    registry.register_if_absent(store)
+}
+
+/// Registers a constructor used to build `S` on first use:
+/// `register_lazy(|| ApiStore::with_config(config.clone()))`
+pub fn register_lazy<S, F>(factory: F)
+where
+    S: Store + Send + Sync,
+    F: Fn() -> S + Send + Sync + 'static,
+{
+    if let Some(context) = scoped_context() {
+        return context.register_lazy(factory);
+    }
+    let registry = get_global_registry().clone();
+    registry.register_lazy(factory);
+}
+
+/// Registers an async constructor used to build `S` on first use through
+/// [`get_store_async`], e.g. for a store loading its configuration:
+/// `register_lazy_async(|| async { SettingsStore::load().await })`
+pub fn register_lazy_async<S, F, Fut>(factory: F)
+where
+    S: Store + Send + Sync,
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = S> + 'static,
+{
+    if let Some(context) = scoped_context() {
+        return context.register_lazy_async(factory);
+    }
+    let registry = get_global_registry().clone();
+    registry.register_lazy_async(factory);
+}
+
+/// Like [`get_store`], building `S` with its async lazy factory if needed.
+///
+/// Concurrent first lookups may each run the factory; the first registered instance wins.
+pub async fn get_store_async<S: Store + 'static>() -> Option<S> {
+    if let Some(context) = scoped_context() {
+        return context.get_async::<S>().await;
+    }
+    if is_private::<S>() {
+        return provided::<S>();
+    }
+    let registry = get_global_registry().clone();
+    registry.get_async::<S>().await
+}
+
 pub fn remove_store<S: Store + 'static>() {
-    let registry = get_global_registry();
+    if let Some(context) = scoped_context() {
+        return context.remove::<S>();
+    }
+    let registry = get_global_registry().clone();
     registry.remove::<S>();
 }
 
 pub fn has_store<S: Store + 'static>() -> bool {
-    let registry = get_global_registry();
+    if let Some(context) = scoped_context() {
+        return context.has::<S>();
+    }
+    let registry = get_global_registry().clone();
     registry.has::<S>()
 }
 
 pub fn clear_all_stores() {
-    let registry = get_global_registry();
-    registry.clear();
+    clear_all_with(|_| {});
+}
+
+/// Clears all stores, calling `before_remove` on each one while it is still
+/// registered, after its [`StoreLifecycle::on_clear`](crate::StoreLifecycle::on_clear) hook:
+///
+/// ```ignore
+/// clear_all_with(|info| log::info!("dropping {}", info.name()));
+/// ```
+pub fn clear_all_with(before_remove: impl FnMut(&StoreInfo)) {
+    if let Some(context) = scoped_context() {
+        return context.clear_with(before_remove);
+    }
+    let registry = get_global_registry().clone();
+    registry.clear_with(before_remove);
 }
 
 pub fn store_count() -> usize {
-    let registry = get_global_registry();
+    if let Some(context) = scoped_context() {
+        return context.count();
+    }
+    let registry = get_global_registry().clone();
     registry.count()
 }
 
+pub fn register_keyed<S: Store + Send + Sync + 'static>(key: impl Into<String>, store: S) {
+    if let Some(context) = scoped_context() {
+        return context.register_keyed(key, store);
+    }
+    let registry = get_global_registry().clone();
+    registry.register_keyed(key, store);
+}
+
+pub fn get_keyed<S: Store + 'static>(key: &str) -> Option<S> {
+    if let Some(context) = scoped_context() {
+        return context.get_keyed::<S>(key);
+    }
+    let registry = get_global_registry().clone();
+    registry.get_keyed::<S>(key)
+}
+
+/// The instance of `S` registered under `key`, constructed on first use.
+pub fn use_keyed_store<S: Injectable>(key: &str) -> S {
+    if let Some(context) = scoped_context() {
+        return context.get_or_create_keyed::<S>(key);
+    }
+    let registry = get_global_registry().clone();
+    registry.get_or_create_keyed::<S>(key)
+}
+
+pub fn has_keyed<S: Store + 'static>(key: &str) -> bool {
+    if let Some(context) = scoped_context() {
+        return context.has_keyed::<S>(key);
+    }
+    let registry = get_global_registry().clone();
+    registry.has_keyed::<S>(key)
+}
+
+pub fn remove_keyed<S: Store + 'static>(key: &str) {
+    if let Some(context) = scoped_context() {
+        return context.remove_keyed::<S>(key);
+    }
+    let registry = get_global_registry().clone();
+    registry.remove_keyed::<S>(key);
+}
+
+/// Every store in the current request or per-thread context, or in the global registry.
+pub fn list_stores() -> Vec<StoreInfo> {
+    if let Some(context) = scoped_context() {
+        return context.list();
+    }
+    let registry = get_global_registry().clone();
+    registry.list()
+}
+
 pub fn store_action<S: Store + 'static, F, R>(action: F) -> Option<R>
 where
     F: FnOnce(&S) -> R,
 {
-    let registry = get_global_registry();
-    registry.get::<S>().map(|store| action(&store))
+    get_store::<S>().map(|store| action(&store))
 }
 
 pub fn store_action_mut<S: Store + 'static, F, R>(action: F) -> Option<R>
 where
     F: FnOnce(&mut S) -> R,
 {
-    let registry = get_global_registry();
-    if let Some(mut store) = registry.get::<S>() {
-        Some(action(&mut store))
-    } else {
-        None
-    }
+    get_store::<S>().map(|mut store| action(&mut store))
 }
 
 pub trait GlobalStore: Store + Default + Send + Sync {
@@ -138,42 +716,77 @@ pub trait GlobalStore: Store + Default + Send + Sync {
         use_store::<Self>()
     }
 
-    fn reset() {
-        let new_store = Self::default();
-        register_store(new_store);
+    /// Puts the global instance back to its initial values in place, so every
+    /// holder of it is notified, calling
+    /// [`StoreLifecycle::on_reset`](crate::StoreLifecycle::on_reset) on it first.
+    fn reset()
+    where
+        Self: Resettable,
+    {
+        if let Some(current) = get_store::<Self>() {
+            notify_lifecycle(TypeId::of::<Self>(), &current, LifecycleEvent::Reset);
+            current.reset_state();
+        }
     }
 }
 
 impl<T: Store + Default + Send + Sync> GlobalStore for T {}
 
+/// A store holding a single observable `value`: `simple_store!(SidebarStore, bool, true);`
+///
+/// With `persist = "key"` (feature `serde`), the value is restored from and
+/// saved to persistent storage: `simple_store!(ThemeStore, Theme, Theme::Light, persist = "theme");`
+/// Each instance is persisted once, when a registry or context registers it, so
+/// instances in separate contexts or test environments stay independent.
 #[macro_export]
 macro_rules! simple_store {
+    ($name:ident, $type:ty, $default:expr, persist = $key:expr) => {
+        $crate::simple_store!(@define $name, $type, $default, {
+            #[doc(hidden)]
+            pub __persisted: ::std::sync::Arc<::std::sync::Once>,
+        }, {
+            __persisted: ::std::sync::Arc::new(::std::sync::Once::new()),
+        }, {
+            fn __registered(&self) {
+                self.__persisted.call_once(|| {
+                    $crate::persist(&self.value, $key);
+                });
+            }
+        });
+    };
+
     ($name:ident, $type:ty, $default:expr) => {
+        $crate::simple_store!(@define $name, $type, $default, {}, {}, {});
+    };
+
+    (@define $name:ident, $type:ty, $default:expr, { $($field:tt)* }, { $($init:tt)* }, { $($store:tt)* }) => {
         #[derive(Clone)]
         pub struct $name {
             pub value: $crate::ObservableValue<$type>,
+            $($field)*
         }
 
         impl $name {
             pub fn new() -> Self {
                 Self {
                     value: $crate::observable($default),
+                    $($init)*
                 }
             }
 
             pub fn get(&self) -> $type {
-                self.value.get()
+                $crate::Observable::get(&self.value)
             }
 
             pub fn set(&self, value: $type) {
-                self.value.set(value);
+                $crate::Observable::assign(&self.value, value);
             }
 
             pub fn update<F>(&self, updater: F)
             where
                 F: FnOnce(&mut $type),
             {
-                self.value.update(updater);
+                $crate::Observable::set(&self.value, updater);
             }
         }
 
@@ -183,13 +796,18 @@ macro_rules! simple_store {
             }
         }
 
-        unsafe impl Send for $name {}
-        unsafe impl Sync for $name {}
-
         impl $crate::Store for $name {
             fn id(&self) -> std::any::TypeId {
                 std::any::TypeId::of::<$name>()
             }
+
+            $($store)*
+        }
+
+        impl $crate::Resettable for $name {
+            fn reset_state(&self) {
+                $crate::Observable::assign(&self.value, $default);
+            }
         }
     };
 }
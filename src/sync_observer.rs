@@ -0,0 +1,140 @@
+use crate::observable::{
+    begin_tracking, current_observer, detach, next_observer_id, ObserverList, Priority, Tracker,
+    TrackingScope, MAX_OBSERVER_RERUNS,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, TryLockError, Weak};
+
+/// A reaction that can be notified from any thread.
+///
+/// Unlike reactive observers, which only hear about writes made on the thread
+/// that created them, it runs on whichever thread writes to one of its dependencies.
+pub(crate) struct SyncObserver {
+    pub(crate) id: u64,
+    pub(crate) priority: Priority,
+    callback: Mutex<Box<dyn FnMut() + Send>>,
+    // Set by every notification; cleared by the thread that runs the callback
+    pending: AtomicBool,
+    dependencies: Mutex<Vec<Weak<ObserverList>>>,
+}
+
+impl SyncObserver {
+    fn new<F: FnMut() + Send + 'static>(priority: Priority, callback: F) -> Arc<Self> {
+        Arc::new(Self {
+            id: next_observer_id(),
+            priority,
+            callback: Mutex::new(Box::new(callback)),
+            pending: AtomicBool::new(false),
+            dependencies: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Runs the callback, unless it is already running (on this thread or
+    /// another), in which case it runs once more after it returns.
+    pub(crate) fn notify(&self) {
+        self.pending.store(true, Ordering::SeqCst);
+
+        for _ in 0..=MAX_OBSERVER_RERUNS {
+            let mut callback = match self.callback.try_lock() {
+                Ok(callback) => callback,
+                Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+                Err(TryLockError::WouldBlock) => return,
+            };
+            if !self.pending.swap(false, Ordering::SeqCst) {
+                drop(callback);
+                // A notification that found the callback locked relies on us to run it
+                if self.pending.load(Ordering::SeqCst) {
+                    continue;
+                }
+                return;
+            }
+            callback();
+        }
+    }
+
+    pub(crate) fn add_dependency(&self, list: Weak<ObserverList>) {
+        self.dependencies.lock().unwrap().push(list);
+    }
+
+    pub(crate) fn clear_dependencies(&self) {
+        let dependencies = std::mem::take(&mut *self.dependencies.lock().unwrap());
+        for dependency in dependencies {
+            detach(&dependency, self.id);
+        }
+    }
+
+    fn dependency_count(&self) -> usize {
+        self.dependencies
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|dependency| dependency.strong_count() > 0)
+            .count()
+    }
+}
+
+impl Drop for SyncObserver {
+    fn drop(&mut self) {
+        let dependencies = self
+            .dependencies
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner());
+        for dependency in dependencies.drain(..) {
+            detach(&dependency, self.id);
+        }
+    }
+}
+
+/// A `Send + Sync` observer for native multithreaded apps.
+///
+/// Reads made on any thread while one of its tracking passes is active
+/// subscribe it, and writes from any thread notify it:
+///
+/// ```ignore
+/// let context = SyncObserverContext::new(move || tx.send(()).unwrap());
+/// std::thread::spawn(move || {
+///     let _scope = context.track();
+///     render(store.items.get());
+/// });
+/// ```
+///
+/// The callback runs on the writing thread, so it should be cheap: usually
+/// it only wakes the thread that re-runs the tracked code.
+#[derive(Clone)]
+pub struct SyncObserverContext {
+    observer: Arc<SyncObserver>,
+}
+
+impl SyncObserverContext {
+    pub fn new<F: FnMut() + Send + 'static>(update_fn: F) -> Self {
+        Self::with_priority(Priority::Effect, update_fn)
+    }
+
+    pub fn with_priority<F: FnMut() + Send + 'static>(priority: Priority, update_fn: F) -> Self {
+        Self {
+            observer: SyncObserver::new(priority, update_fn),
+        }
+    }
+
+    /// Forgets the observables read so far and records this thread's reads
+    /// until the returned scope is dropped.
+    pub fn track(&self) -> TrackingScope {
+        begin_tracking(Tracker::Sync(self.observer.clone()))
+    }
+
+    /// Runs `f` in a fresh tracking pass.
+    pub fn run<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _scope = self.track();
+        f()
+    }
+
+    /// Whether this context records the reads of the current thread.
+    pub fn is_current(&self) -> bool {
+        matches!(current_observer(), Some(Tracker::Sync(observer)) if Arc::ptr_eq(&observer, &self.observer))
+    }
+
+    /// Number of observables read during the current tracking pass.
+    pub fn dependency_count(&self) -> usize {
+        self.observer.dependency_count()
+    }
+}
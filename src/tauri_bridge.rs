@@ -0,0 +1,250 @@
+//! Mirrors stores between a Tauri backend and its webview frontend (feature `tauri`).
+//!
+//! Call [`mirror_store`] for the same stores on both sides and install
+//! [`plugin`] in the backend. A change on one side is sent as a JSON snapshot
+//! and restored on the other:
+//!
+//! - frontend changes are emitted as the `reaxive:update` event,
+//! - backend changes are emitted as the `reaxive:state` event,
+//! - the `reaxive:pull` event makes the backend emit the state of every mirrored store.
+//!
+//! The frontend needs `app.withGlobalTauri` enabled in `tauri.conf.json`.
+
+use crate::{untracked, use_store, SerializableStore, SyncObserverContext};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// Event carrying frontend changes to the backend.
+pub const UPDATE_EVENT: &str = "reaxive:update";
+/// Event carrying backend changes to the frontend.
+pub const STATE_EVENT: &str = "reaxive:state";
+/// Event asking the backend for the state of every mirrored store.
+pub const PULL_EVENT: &str = "reaxive:pull";
+
+/// Payload of the update and state events.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StorePayload {
+    pub store: String,
+    pub state: Value,
+}
+
+struct Mirror {
+    key: &'static str,
+    snapshot: fn() -> Result<Value, serde_json::Error>,
+    restore: fn(Value) -> Result<(), serde_json::Error>,
+    // Last state received from the other side, so applying it isn't sent back
+    remote: Arc<Mutex<Option<Value>>>,
+    _observer: SyncObserverContext,
+}
+
+static MIRRORS: LazyLock<Mutex<Vec<Mirror>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+fn snapshot_mirror<S: SerializableStore + Default + Send + Sync>(
+) -> Result<Value, serde_json::Error> {
+    use_store::<S>().snapshot()
+}
+
+fn restore_mirror<S: SerializableStore + Default + Send + Sync>(
+    value: Value,
+) -> Result<(), serde_json::Error> {
+    use_store::<S>().restore(value)
+}
+
+/// Keeps the global instance of `S` in sync with the other side of the bridge.
+///
+/// Only fields read by [`SerializableStore::track`] are watched, which
+/// [`serializable_store!`](crate::serializable_store) does for every listed field.
+pub fn mirror_store<S: SerializableStore + Default + Send + Sync>() {
+    let key = S::store_key();
+    if MIRRORS
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|mirror| mirror.key == key)
+    {
+        return;
+    }
+
+    let remote = Arc::new(Mutex::new(None::<Value>));
+    let observer = SyncObserverContext::new({
+        let remote = remote.clone();
+        move || {
+            // Runs on the writer's thread; its current observer must not subscribe to the store.
+            let Ok(state) = untracked(snapshot_mirror::<S>) else {
+                return;
+            };
+            {
+                let mut remote = remote.lock().unwrap();
+                if remote.as_ref() == Some(&state) {
+                    return;
+                }
+                *remote = None;
+            }
+            platform::send(StorePayload {
+                store: key.to_string(),
+                state,
+            });
+        }
+    });
+    observer.run(|| use_store::<S>().track());
+
+    MIRRORS.lock().unwrap().push(Mirror {
+        key,
+        snapshot: snapshot_mirror::<S>,
+        restore: restore_mirror::<S>,
+        remote,
+        _observer: observer,
+    });
+    platform::connect();
+}
+
+/// Applies a snapshot received from the other side; unknown stores are ignored.
+fn apply_remote(payload: StorePayload) -> Result<bool, serde_json::Error> {
+    let mirror = MIRRORS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|mirror| mirror.key == payload.store)
+        .map(|mirror| (mirror.restore, mirror.remote.clone()));
+
+    let Some((restore, remote)) = mirror else {
+        return Ok(false);
+    };
+    *remote.lock().unwrap() = Some(payload.state.clone());
+    // Mirrors observe the restored state once, not after each field
+    crate::batch(|| restore(payload.state))?;
+    Ok(true)
+}
+
+/// Snapshots of every mirrored store.
+#[cfg(not(target_arch = "wasm32"))]
+fn snapshot_all() -> Vec<StorePayload> {
+    let mirrors: Vec<_> = MIRRORS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|mirror| (mirror.key, mirror.snapshot))
+        .collect();
+
+    mirrors
+        .into_iter()
+        .filter_map(|(key, snapshot)| {
+            Some(StorePayload {
+                store: key.to_string(),
+                state: snapshot().ok()?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use platform::plugin;
+
+#[cfg(target_arch = "wasm32")]
+pub use platform::pull_stores;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use super::*;
+    use std::sync::OnceLock;
+    use tauri::plugin::{Builder, TauriPlugin};
+    use tauri::{Emitter, Listener, Runtime};
+
+    type Emit = Box<dyn Fn(&StorePayload) + Send + Sync>;
+
+    static EMIT: OnceLock<Emit> = OnceLock::new();
+
+    /// The backend half of the bridge: `tauri::Builder::default().plugin(reaxive::tauri_bridge::plugin())`
+    pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+        Builder::new("reaxive")
+            .setup(|app, _api| {
+                let emitter = app.clone();
+                let _ = EMIT.set(Box::new(move |payload| {
+                    let _ = emitter.emit(STATE_EVENT, payload);
+                }));
+
+                app.listen_any(UPDATE_EVENT, |event| {
+                    if let Ok(payload) = serde_json::from_str::<StorePayload>(event.payload()) {
+                        let _ = apply_remote(payload);
+                    }
+                });
+                app.listen_any(PULL_EVENT, |_| {
+                    for payload in snapshot_all() {
+                        send(payload);
+                    }
+                });
+                Ok(())
+            })
+            .build()
+    }
+
+    pub(super) fn send(payload: StorePayload) {
+        if let Some(emit) = EMIT.get() {
+            emit(&payload);
+        }
+    }
+
+    pub(super) fn connect() {}
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    use super::*;
+    use js_sys::{Array, Function, Reflect, JSON};
+    use std::cell::Cell;
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::{JsCast, JsValue};
+
+    /// Calls `window.__TAURI__.<module>.<function>(args)`.
+    fn call(module: &str, function: &str, args: &[JsValue]) -> Result<JsValue, JsValue> {
+        let window = web_sys::window().ok_or("no window")?;
+        let tauri = Reflect::get(&window, &"__TAURI__".into())?;
+        let module = Reflect::get(&tauri, &module.into())?;
+        let function: Function = Reflect::get(&module, &function.into())?.dyn_into()?;
+        function.apply(&module, &args.iter().collect::<Array>())
+    }
+
+    fn to_js(value: &impl Serialize) -> Result<JsValue, JsValue> {
+        let json = serde_json::to_string(value).map_err(|error| error.to_string())?;
+        JSON::parse(&json)
+    }
+
+    fn from_js<T: serde::de::DeserializeOwned>(value: &JsValue) -> Result<T, JsValue> {
+        let json: String = JSON::stringify(value)?.into();
+        serde_json::from_str(&json).map_err(|error| error.to_string().into())
+    }
+
+    pub(super) fn send(payload: StorePayload) {
+        if let Ok(payload) = to_js(&payload) {
+            let _ = call("event", "emit", &[UPDATE_EVENT.into(), payload]);
+        }
+    }
+
+    pub(super) fn connect() {
+        thread_local! {
+            static LISTENING: Cell<bool> = const { Cell::new(false) };
+        }
+        if LISTENING.with(|listening| listening.replace(true)) {
+            return;
+        }
+
+        let handler = Closure::<dyn FnMut(JsValue)>::new(|event: JsValue| {
+            let payload = Reflect::get(&event, &"payload".into()).unwrap_or(JsValue::NULL);
+            if let Ok(payload) = from_js::<StorePayload>(&payload) {
+                let _ = apply_remote(payload);
+            }
+        });
+        let _ = call(
+            "event",
+            "listen",
+            &[STATE_EVENT.into(), handler.into_js_value()],
+        );
+    }
+
+    /// Asks the backend for the state of every mirrored store; call once at startup.
+    pub fn pull_stores() {
+        connect();
+        let _ = call("event", "emit", &[PULL_EVENT.into()]);
+    }
+}
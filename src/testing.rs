@@ -0,0 +1,575 @@
+//! Helpers for testing stores (feature `testing`).
+
+use crate::request::RequestGuard;
+use crate::time::VirtualClock;
+use crate::{DynStore, Store, StoreContext};
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Wake, Waker};
+use std::time::{Duration, SystemTime};
+
+/// An isolated set of stores for one test.
+///
+/// While the environment is alive, `use_store`, `get_store` and the other
+/// global store functions resolve against its own context on the current
+/// thread instead of the shared global registry, so tests running in
+/// parallel don't see each other's stores. Everything is cleared on drop.
+///
+/// ```ignore
+/// #[test]
+/// fn increments() {
+///     let _env = TestEnv::new();
+///     CounterStore::new().increment();
+///     assert_eq!(CounterStore::new().count.get(), 1);
+/// }
+/// ```
+pub struct TestEnv {
+    context: StoreContext,
+    _scope: RequestGuard,
+}
+
+impl TestEnv {
+    pub fn new() -> Self {
+        let context = StoreContext::with_name("test");
+        Self {
+            _scope: RequestGuard::enter(context.clone()),
+            context,
+        }
+    }
+
+    /// Runs `f` inside a fresh environment.
+    pub fn run<R>(f: impl FnOnce(&TestEnv) -> R) -> R {
+        let env = Self::new();
+        f(&env)
+    }
+
+    pub fn context(&self) -> &StoreContext {
+        &self.context
+    }
+
+    pub fn register<S: Store + Send + Sync>(&self, store: S) {
+        self.context.register(store);
+    }
+
+    /// Makes every lookup of `S` in this environment resolve to `store`,
+    /// e.g. an API store configured with a fake client.
+    pub fn override_store<S: Store + Send + Sync>(&self, store: S) -> S {
+        self.context.register(store.clone());
+        store
+    }
+
+    /// Makes every lookup of [`DynStore<T>`] in this environment resolve to
+    /// `implementation`, which can be a mock type implementing the trait `T`:
+    ///
+    /// ```ignore
+    /// let api = Arc::new(MockApi::default());
+    /// env.override_dyn::<dyn Api>(api.clone());
+    /// ```
+    pub fn override_dyn<T>(&self, implementation: Arc<T>) -> DynStore<T>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.override_store(DynStore::new(implementation))
+    }
+
+    pub fn store<S: Store + Default + Send + Sync>(&self) -> S {
+        self.context.get_or_create::<S>()
+    }
+
+    /// Removes every store, so the next lookup starts from fresh instances.
+    pub fn reset(&self) {
+        self.context.clear();
+    }
+}
+
+impl Default for TestEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TestEnv {
+    fn drop(&mut self) {
+        self.context.clear();
+    }
+}
+
+/// A recorded call to a mocked action.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Call {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+/// Records the actions called on a mock store, for later assertions.
+///
+/// ```ignore
+/// #[derive(Default)]
+/// struct MockApi {
+///     calls: CallLog,
+/// }
+///
+/// impl Api for MockApi {
+///     fn fetch_user(&self, id: u32) -> User {
+///         self.calls.record("fetch_user", [id]);
+///         User::default()
+///     }
+/// }
+///
+/// let api = Arc::new(MockApi::default());
+/// env.override_dyn::<dyn Api>(api.clone());
+/// // ... render or drive the code under test ...
+/// api.calls.assert_called("fetch_user");
+/// ```
+#[derive(Clone, Default)]
+pub struct CallLog {
+    calls: Arc<Mutex<Vec<Call>>>,
+}
+
+impl CallLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a call to `name`, keeping the `Debug` form of its arguments.
+    pub fn record<A: Debug>(&self, name: impl Into<String>, args: impl IntoIterator<Item = A>) {
+        self.calls.lock().unwrap().push(Call {
+            name: name.into(),
+            args: args.into_iter().map(|arg| format!("{arg:?}")).collect(),
+        });
+    }
+
+    pub fn calls(&self) -> Vec<Call> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Arguments of every call to `name`, in call order.
+    pub fn calls_to(&self, name: &str) -> Vec<Vec<String>> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|call| call.name == name)
+            .map(|call| call.args.clone())
+            .collect()
+    }
+
+    pub fn was_called(&self, name: &str) -> bool {
+        self.call_count(name) > 0
+    }
+
+    pub fn call_count(&self, name: &str) -> usize {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|call| call.name == name)
+            .count()
+    }
+
+    pub fn clear(&self) {
+        self.calls.lock().unwrap().clear();
+    }
+
+    #[track_caller]
+    pub fn assert_called(&self, name: &str) {
+        assert!(
+            self.was_called(name),
+            "expected `{name}` to be called; calls: {:?}",
+            self.calls()
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_not_called(&self, name: &str) {
+        assert!(
+            !self.was_called(name),
+            "expected `{name}` not to be called; calls: {:?}",
+            self.calls()
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_called_times(&self, name: &str, times: usize) {
+        let count = self.call_count(name);
+        assert_eq!(
+            count,
+            times,
+            "expected `{name}` to be called {times} time(s), got {count}; calls: {:?}",
+            self.calls()
+        );
+    }
+}
+
+/// Replacement for values removed by snapshot redactions.
+#[cfg(feature = "serde")]
+pub const REDACTED: &str = "[redacted]";
+
+/// Replaces the values at dotted `path`s (`*` matches any key or index) with [`REDACTED`].
+#[cfg(feature = "serde")]
+pub fn redact(value: &mut serde_json::Value, path: &str) {
+    fn walk(value: &mut serde_json::Value, segments: &[&str]) {
+        let Some((segment, rest)) = segments.split_first() else {
+            *value = serde_json::Value::String(REDACTED.to_string());
+            return;
+        };
+
+        match value {
+            serde_json::Value::Object(map) if *segment == "*" => {
+                map.values_mut().for_each(|value| walk(value, rest))
+            }
+            serde_json::Value::Object(map) => {
+                if let Some(value) = map.get_mut(*segment) {
+                    walk(value, rest);
+                }
+            }
+            serde_json::Value::Array(items) if *segment == "*" => {
+                items.iter_mut().for_each(|value| walk(value, rest))
+            }
+            serde_json::Value::Array(items) => {
+                if let Some(value) = segment
+                    .parse()
+                    .ok()
+                    .and_then(|index: usize| items.get_mut(index))
+                {
+                    walk(value, rest);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let segments: Vec<_> = path.split('.').collect();
+    walk(value, &segments);
+}
+
+/// Compares `value` with the snapshot file at `path`, creating it on first run.
+///
+/// Set `REAXIVE_UPDATE_SNAPSHOTS=1` to accept changes. Otherwise a mismatch
+/// writes the new state next to the snapshot as `.snap.new` and panics.
+#[cfg(feature = "serde")]
+#[track_caller]
+pub fn assert_json_snapshot(value: &serde_json::Value, path: &std::path::Path) {
+    let actual = serde_json::to_string_pretty(value).expect("snapshot serializes") + "\n";
+    let update = std::env::var_os("REAXIVE_UPDATE_SNAPSHOTS").is_some_and(|v| v != "0");
+
+    match std::fs::read_to_string(path) {
+        Ok(expected) if expected == actual => {}
+        Ok(expected) if !update => {
+            let new_path = path.with_extension("snap.new");
+            let _ = std::fs::write(&new_path, &actual);
+            panic!(
+                "store snapshot {} does not match\n--- expected\n{expected}+++ actual\n{actual}\n\
+                 new snapshot written to {}; rerun with REAXIVE_UPDATE_SNAPSHOTS=1 to accept it",
+                path.display(),
+                new_path.display(),
+            );
+        }
+        _ => {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).expect("create snapshot directory");
+            }
+            std::fs::write(path, actual).expect("write snapshot");
+            let _ = std::fs::remove_file(path.with_extension("snap.new"));
+        }
+    }
+}
+
+/// Snapshot-tests a [`SerializableStore`](crate::SerializableStore) (features `testing` and `serde`):
+///
+/// ```ignore
+/// assert_store_snapshot!(CartStore);
+/// assert_store_snapshot!(CartStore, name = "after_checkout");
+/// assert_store_snapshot!(CartStore, redact = ["created_at", "items.*.added_at"]);
+/// ```
+///
+/// Snapshots live in `snapshots/` under the crate root, named after the
+/// calling module and the store (or `name`).
+#[cfg(feature = "serde")]
+#[macro_export]
+macro_rules! assert_store_snapshot {
+    ($store:ty $(, name = $name:expr)? $(, redact = [$($path:expr),* $(,)?])? $(,)?) => {{
+        let store = $crate::use_store::<$store>();
+        let mut value = $crate::SerializableStore::snapshot(&store).expect("store snapshot");
+        $($(
+            $crate::testing::redact(&mut value, $path);
+        )*)?
+
+        #[allow(unused_mut, unused_assignments)]
+        let mut name = stringify!($store).to_string();
+        $( name = ::std::string::ToString::to_string(&$name); )?
+
+        let file = format!("{}__{}.snap", module_path!().replace("::", "__"), name);
+        let path = ::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("snapshots")
+            .join(file);
+        $crate::testing::assert_json_snapshot(&value, &path);
+    }};
+}
+
+struct TaskWaker {
+    woken: AtomicBool,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+    }
+}
+
+struct TestTask {
+    future: Pin<Box<dyn Future<Output = ()>>>,
+    waker: Arc<TaskWaker>,
+}
+
+/// Deterministic virtual time for tests of debounces, retries and polling.
+///
+/// Installs a [`VirtualClock`] on the current thread, so `time::sleep` and
+/// `time::now` follow it instead of real time, and runs spawned futures on a
+/// single-threaded executor. [`TestScheduler::advance`] fires every timer due
+/// in deadline order, polling woken tasks in between, so timers they start
+/// along the way fire too.
+///
+/// ```ignore
+/// let scheduler = TestScheduler::new();
+/// scheduler.spawn(async move { store.search.set_debounced(query, ms(300)).await });
+/// scheduler.advance(Duration::from_millis(299));
+/// assert_eq!(results.get(), None);
+/// scheduler.advance(Duration::from_millis(1));
+/// assert!(results.get().is_some());
+/// ```
+pub struct TestScheduler {
+    clock: Rc<VirtualClock>,
+    start: SystemTime,
+    tasks: RefCell<Vec<TestTask>>,
+    spawned: RefCell<Vec<TestTask>>,
+}
+
+impl TestScheduler {
+    pub fn new() -> Self {
+        Self::starting_at(SystemTime::UNIX_EPOCH)
+    }
+
+    pub fn starting_at(start: SystemTime) -> Self {
+        Self {
+            clock: VirtualClock::install(start),
+            start,
+            tasks: RefCell::new(Vec::new()),
+            spawned: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn now(&self) -> SystemTime {
+        self.clock.now()
+    }
+
+    /// Virtual time elapsed since the scheduler was created.
+    pub fn elapsed(&self) -> Duration {
+        self.now().duration_since(self.start).unwrap_or_default()
+    }
+
+    pub fn pending_timers(&self) -> usize {
+        self.clock.pending_timers()
+    }
+
+    /// Number of spawned futures that have not completed.
+    pub fn pending_tasks(&self) -> usize {
+        self.tasks.borrow().len() + self.spawned.borrow().len()
+    }
+
+    /// Queues `future` on the scheduler; it first runs on the next
+    /// [`run_until_stalled`](Self::run_until_stalled) or [`advance`](Self::advance).
+    pub fn spawn(&self, future: impl Future<Output = ()> + 'static) {
+        self.spawned.borrow_mut().push(TestTask {
+            future: Box::pin(future),
+            waker: Arc::new(TaskWaker {
+                woken: AtomicBool::new(true),
+            }),
+        });
+    }
+
+    /// Polls woken tasks until none can make progress without time passing.
+    pub fn run_until_stalled(&self) {
+        loop {
+            let spawned = std::mem::take(&mut *self.spawned.borrow_mut());
+            self.tasks.borrow_mut().extend(spawned);
+
+            let mut tasks = std::mem::take(&mut *self.tasks.borrow_mut());
+            let mut progressed = false;
+            tasks.retain_mut(|task| {
+                if !task.waker.woken.swap(false, Ordering::SeqCst) {
+                    return true;
+                }
+                progressed = true;
+                let waker = Waker::from(task.waker.clone());
+                let mut cx = Context::from_waker(&waker);
+                task.future.as_mut().poll(&mut cx).is_pending()
+            });
+
+            self.tasks.borrow_mut().extend(tasks);
+            if !progressed && self.spawned.borrow().is_empty() {
+                break;
+            }
+        }
+    }
+
+    /// Moves virtual time forward by `duration`, firing due timers in order
+    /// and running the tasks they wake.
+    pub fn advance(&self, duration: Duration) {
+        let target = self.now() + duration;
+        self.run_until_stalled();
+        while self.clock.fire_next(target) {
+            self.run_until_stalled();
+        }
+        self.clock
+            .advance(target.duration_since(self.now()).unwrap_or_default());
+        self.run_until_stalled();
+    }
+
+    /// Fires timers until no task is waiting on one.
+    pub fn run_all(&self) {
+        self.run_until_stalled();
+        while let Some(deadline) = self.clock.next_deadline() {
+            self.clock.fire_next(deadline);
+            self.run_until_stalled();
+        }
+    }
+
+    /// Drives `future` to completion, skipping virtual time ahead whenever it waits on a timer.
+    ///
+    /// Panics if it stalls with no timer left to fire.
+    pub fn block_on<F: Future + 'static>(&self, future: F) -> F::Output {
+        let output = Rc::new(RefCell::new(None));
+        let slot = output.clone();
+        self.spawn(async move {
+            *slot.borrow_mut() = Some(future.await);
+        });
+
+        loop {
+            self.run_until_stalled();
+            if let Some(output) = output.borrow_mut().take() {
+                return output;
+            }
+            match self.clock.next_deadline() {
+                Some(deadline) => {
+                    self.clock.fire_next(deadline);
+                }
+                None => panic!("future stalled with no pending timers"),
+            }
+        }
+    }
+}
+
+impl Default for TestScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TestScheduler {
+    fn drop(&mut self) {
+        VirtualClock::uninstall();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Observable, ObservableValue};
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn advance_fires_sleeps_at_their_deadline() {
+        let scheduler = TestScheduler::new();
+        let woke = Rc::new(RefCell::new(None));
+        let slot = woke.clone();
+        scheduler.spawn(async move {
+            crate::time::sleep(ms(100)).await;
+            *slot.borrow_mut() = Some(crate::time::now());
+        });
+
+        scheduler.advance(ms(99));
+        assert_eq!(*woke.borrow(), None);
+        assert_eq!(scheduler.pending_timers(), 1);
+
+        scheduler.advance(ms(1));
+        assert_eq!(*woke.borrow(), Some(SystemTime::UNIX_EPOCH + ms(100)));
+        assert_eq!(scheduler.pending_tasks(), 0);
+        assert_eq!(scheduler.pending_timers(), 0);
+    }
+
+    #[test]
+    fn advance_runs_timers_started_by_woken_tasks() {
+        let scheduler = TestScheduler::new();
+        let steps = Rc::new(RefCell::new(Vec::new()));
+        let log = steps.clone();
+        scheduler.spawn(async move {
+            for _ in 0..3 {
+                crate::time::sleep(ms(10)).await;
+                log.borrow_mut().push(crate::time::now());
+            }
+        });
+
+        scheduler.advance(ms(25));
+        let epoch = SystemTime::UNIX_EPOCH;
+        assert_eq!(*steps.borrow(), vec![epoch + ms(10), epoch + ms(20)]);
+        assert_eq!(scheduler.elapsed(), ms(25));
+
+        scheduler.run_all();
+        assert_eq!(steps.borrow().len(), 3);
+        assert_eq!(scheduler.elapsed(), ms(30));
+    }
+
+    #[test]
+    fn block_on_skips_ahead_to_each_timer() {
+        let scheduler = TestScheduler::new();
+        let output = scheduler.block_on(async {
+            crate::time::sleep(ms(500)).await;
+            crate::time::sleep(ms(250)).await;
+            7
+        });
+
+        assert_eq!(output, 7);
+        assert_eq!(scheduler.elapsed(), ms(750));
+    }
+
+    #[test]
+    fn debounce_applies_the_last_value_once_the_delay_passes() {
+        let scheduler = TestScheduler::new();
+        let query = ObservableValue::new(String::new());
+        scheduler.spawn(query.set_debounced("r".to_string(), ms(300)));
+        scheduler.advance(ms(200));
+        scheduler.spawn(query.set_debounced("rust".to_string(), ms(300)));
+
+        scheduler.advance(ms(299));
+        assert_eq!(query.get(), "");
+
+        scheduler.advance(ms(1));
+        assert_eq!(query.get(), "rust");
+        assert_eq!(scheduler.pending_tasks(), 0);
+    }
+
+    #[test]
+    fn other_writes_cancel_a_pending_debounce() {
+        let scheduler = TestScheduler::new();
+        let query = ObservableValue::new(String::new());
+        scheduler.spawn(query.set_debounced("stale".to_string(), ms(300)));
+        scheduler.advance(ms(100));
+
+        query.assign("typed".to_string());
+        scheduler.run_all();
+        assert_eq!(query.get(), "typed");
+    }
+}
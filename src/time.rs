@@ -0,0 +1,337 @@
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, SystemTime};
+
+/// Waits for `duration` without blocking the UI thread.
+///
+/// Uses `setTimeout` in the browser and a single shared timer thread on native targets,
+/// so it works on any executor, including the Dioxus runtime. While a
+/// [`VirtualClock`] is installed on the thread, it waits for virtual time instead.
+pub fn sleep(duration: Duration) -> Sleep {
+    match virtual_clock() {
+        Some(clock) => Sleep {
+            inner: SleepInner::Virtual(clock.timer(duration)),
+        },
+        None => Sleep {
+            inner: SleepInner::Real(platform::Sleep::new(duration)),
+        },
+    }
+}
+
+/// Current wall clock time. Unlike `SystemTime::now()` this also works on wasm.
+pub fn now() -> SystemTime {
+    if let Some(clock) = virtual_clock() {
+        return clock.now();
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        SystemTime::UNIX_EPOCH + Duration::from_millis(js_sys::Date::now() as u64)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        SystemTime::now()
+    }
+}
+
+#[derive(Default)]
+struct TimerState {
+    done: bool,
+    waker: Option<Waker>,
+}
+
+impl TimerState {
+    fn fire(state: &Mutex<TimerState>) {
+        let mut state = state.lock().unwrap();
+        state.done = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn poll(state: &Mutex<TimerState>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = state.lock().unwrap();
+        if state.done {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// Future returned by [`sleep`].
+pub struct Sleep {
+    inner: SleepInner,
+}
+
+enum SleepInner {
+    Real(platform::Sleep),
+    Virtual(Arc<Mutex<TimerState>>),
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        match &mut self.inner {
+            SleepInner::Real(sleep) => Pin::new(sleep).poll(cx),
+            SleepInner::Virtual(state) => TimerState::poll(state, cx),
+        }
+    }
+}
+
+thread_local! {
+    static VIRTUAL_CLOCK: RefCell<Option<Rc<VirtualClock>>> = const { RefCell::new(None) };
+}
+
+fn virtual_clock() -> Option<Rc<VirtualClock>> {
+    VIRTUAL_CLOCK.with(|clock| clock.borrow().clone())
+}
+
+struct VirtualTimer {
+    deadline: SystemTime,
+    id: u64,
+    state: Arc<Mutex<TimerState>>,
+}
+
+/// A manually advanced clock replacing real time for [`sleep`] and [`now`] on one thread.
+///
+/// Timers only fire when the clock is advanced, in deadline order, which makes
+/// debounces, retries and polling deterministic in tests. See
+/// `testing::TestScheduler` for a wrapper that also runs the woken tasks.
+pub struct VirtualClock {
+    now: Cell<SystemTime>,
+    next_id: Cell<u64>,
+    timers: RefCell<Vec<VirtualTimer>>,
+}
+
+impl VirtualClock {
+    /// Installs a clock starting at `start` on the current thread, replacing any previous one.
+    pub fn install(start: SystemTime) -> Rc<Self> {
+        let clock = Rc::new(Self {
+            now: Cell::new(start),
+            next_id: Cell::new(0),
+            timers: RefCell::new(Vec::new()),
+        });
+        VIRTUAL_CLOCK.with(|current| *current.borrow_mut() = Some(clock.clone()));
+        clock
+    }
+
+    /// Restores real time on the current thread.
+    pub fn uninstall() {
+        VIRTUAL_CLOCK.with(|current| current.borrow_mut().take());
+    }
+
+    pub fn now(&self) -> SystemTime {
+        self.now.get()
+    }
+
+    /// Number of sleeps that have not fired yet.
+    pub fn pending_timers(&self) -> usize {
+        self.timers.borrow().len()
+    }
+
+    /// Deadline of the earliest pending sleep.
+    pub fn next_deadline(&self) -> Option<SystemTime> {
+        self.timers
+            .borrow()
+            .iter()
+            .map(|timer| timer.deadline)
+            .min()
+    }
+
+    /// Fires the earliest timer due by `limit`, moving the clock to its deadline.
+    /// Returns `false` when no timer is due.
+    pub fn fire_next(&self, limit: SystemTime) -> bool {
+        let timer = {
+            let mut timers = self.timers.borrow_mut();
+            let next = timers
+                .iter()
+                .enumerate()
+                .filter(|(_, timer)| timer.deadline <= limit)
+                .min_by_key(|(_, timer)| (timer.deadline, timer.id))
+                .map(|(index, _)| index);
+            match next {
+                Some(index) => timers.remove(index),
+                None => return false,
+            }
+        };
+
+        if timer.deadline > self.now.get() {
+            self.now.set(timer.deadline);
+        }
+        TimerState::fire(&timer.state);
+        true
+    }
+
+    /// Moves the clock forward by `duration`, firing every timer due on the way.
+    ///
+    /// Sleeps started by woken tasks only fire if their tasks are polled before
+    /// this returns; use `TestScheduler::advance` to have that done for you.
+    pub fn advance(&self, duration: Duration) {
+        let target = self.now.get() + duration;
+        while self.fire_next(target) {}
+        self.now.set(target);
+    }
+
+    fn timer(&self, duration: Duration) -> Arc<Mutex<TimerState>> {
+        let state = Arc::new(Mutex::new(TimerState::default()));
+        if duration.is_zero() {
+            state.lock().unwrap().done = true;
+            return state;
+        }
+
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.timers.borrow_mut().push(VirtualTimer {
+            deadline: self.now.get() + duration,
+            id,
+            state: state.clone(),
+        });
+        state
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Condvar, LazyLock, Weak};
+    use std::time::Instant;
+
+    type Pending = BTreeMap<(Instant, u64), Weak<Mutex<TimerState>>>;
+
+    /// Pending sleeps by deadline, fired by a single background thread.
+    struct Timers {
+        pending: Mutex<Pending>,
+        changed: Condvar,
+    }
+
+    static NEXT_TIMER: AtomicU64 = AtomicU64::new(0);
+
+    static TIMERS: LazyLock<&'static Timers> = LazyLock::new(|| {
+        let timers: &'static Timers = Box::leak(Box::new(Timers {
+            pending: Mutex::new(BTreeMap::new()),
+            changed: Condvar::new(),
+        }));
+        std::thread::Builder::new()
+            .name("reaxive-timer".into())
+            .spawn(move || timers.run())
+            .expect("failed to spawn the timer thread");
+        timers
+    });
+
+    impl Timers {
+        fn add(&self, deadline: Instant, state: &Arc<Mutex<TimerState>>) {
+            let id = NEXT_TIMER.fetch_add(1, Ordering::Relaxed);
+            self.pending
+                .lock()
+                .unwrap()
+                .insert((deadline, id), Arc::downgrade(state));
+            self.changed.notify_one();
+        }
+
+        fn run(&self) {
+            let mut pending = self.pending.lock().unwrap();
+            loop {
+                let now = Instant::now();
+                match pending.first_key_value() {
+                    None => pending = self.changed.wait(pending).unwrap(),
+                    Some((&(deadline, _), _)) if deadline > now => {
+                        pending = self
+                            .changed
+                            .wait_timeout(pending, deadline - now)
+                            .unwrap()
+                            .0;
+                    }
+                    Some(_) => {
+                        let (_, state) = pending.pop_first().expect("a timer is pending");
+                        // Fired without the lock, since waking may run code that sleeps again.
+                        drop(pending);
+                        if let Some(state) = state.upgrade() {
+                            TimerState::fire(&state);
+                        }
+                        pending = self.pending.lock().unwrap();
+                    }
+                }
+            }
+        }
+    }
+
+    pub struct Sleep {
+        duration: Duration,
+        state: Arc<Mutex<TimerState>>,
+        started: bool,
+    }
+
+    impl Sleep {
+        pub(super) fn new(duration: Duration) -> Self {
+            Self {
+                duration,
+                state: Arc::new(Mutex::new(TimerState::default())),
+                started: false,
+            }
+        }
+    }
+
+    impl Future for Sleep {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if TimerState::poll(&self.state, cx).is_ready() {
+                return Poll::Ready(());
+            }
+
+            if !self.started {
+                self.started = true;
+                // A deadline `Instant` can't represent is never reached.
+                if let Some(deadline) = Instant::now().checked_add(self.duration) {
+                    TIMERS.add(deadline, &self.state);
+                }
+            }
+
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    use super::*;
+    use wasm_bindgen_futures::JsFuture;
+
+    pub struct Sleep {
+        future: JsFuture,
+    }
+
+    impl Sleep {
+        pub(super) fn new(duration: Duration) -> Self {
+            let millis = duration.as_millis().min(i32::MAX as u128) as i32;
+            let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+                if let Some(window) = web_sys::window() {
+                    let _ = window
+                        .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, millis);
+                }
+            });
+
+            Self {
+                future: JsFuture::from(promise),
+            }
+        }
+    }
+
+    impl Future for Sleep {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            Pin::new(&mut self.future).poll(cx).map(|_| ())
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{Observable, ObservableValue};
+use std::fmt;
+use std::sync::Arc;
+
+pub(crate) type Validator<T> = Arc<dyn Fn(&T) -> Result<(), String> + Send + Sync>;
+
+/// What happens to a write that fails validation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    /// The write is discarded and the previous value is kept.
+    #[default]
+    Reject,
+    /// The write is applied and the violations are only reported.
+    Flag,
+}
+
+/// How [`ObservableValue::try_assign`] applied a write.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WriteOutcome {
+    /// The value was stored as given.
+    Applied,
+    /// An interceptor replaced the value, and the replacement was stored.
+    Replaced,
+}
+
+/// Why [`ObservableValue::try_assign`] did not apply a write.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WriteError {
+    /// An interceptor rejected the value.
+    Intercepted,
+    /// The value failed these validators.
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Intercepted => write!(f, "the write was rejected by an interceptor"),
+            Self::Invalid(errors) => {
+                write!(f, "the write failed validation: {}", errors.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for WriteError {}
+
+pub(crate) struct Validation<T: Clone + 'static> {
+    validators: Vec<Validator<T>>,
+    mode: ValidationMode,
+    errors: ObservableValue<Vec<String>>,
+}
+
+impl<T: Clone + 'static> Validation<T> {
+    fn new() -> Self {
+        Self {
+            validators: Vec::new(),
+            mode: ValidationMode::default(),
+            errors: ObservableValue::new(Vec::new()),
+        }
+    }
+}
+
+impl<T: Clone + 'static> ObservableValue<T> {
+    /// Rejects writes that fail `validator`: `age.with_validator(|v| ...)`
+    pub fn with_validator<F>(self, validator: F) -> Self
+    where
+        F: Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.add_validator(validator);
+        self
+    }
+
+    pub fn add_validator<F>(&self, validator: F)
+    where
+        F: Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    {
+        let errors = {
+            let mut validation = self.validation.lock().unwrap();
+            let validation = validation.get_or_insert_with(Validation::new);
+            validation.validators.push(Arc::new(validator));
+            validation.errors.clone()
+        };
+
+        let current = self.run_validators(&self.peek());
+        if errors.peek() != current {
+            errors.assign(current);
+        }
+    }
+
+    /// Chooses whether invalid writes are rejected (default) or only flagged
+    pub fn with_validation_mode(self, mode: ValidationMode) -> Self {
+        self.validation
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Validation::new)
+            .mode = mode;
+        self
+    }
+
+    /// Violations reported by the last write, as an observable
+    pub fn errors(&self) -> ObservableValue<Vec<String>> {
+        self.validation
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Validation::new)
+            .errors
+            .clone()
+    }
+
+    /// Tracked check that the last write passed every validator
+    pub fn is_valid(&self) -> bool {
+        self.errors().when(|errors| errors.is_empty())
+    }
+
+    /// Assigns `value` after the interceptors and validators, reporting whether it was
+    /// stored. Unlike [`Observable::assign`], invalid values are rejected even in
+    /// [`ValidationMode::Flag`].
+    pub fn try_assign(&self, value: T) -> Result<WriteOutcome, WriteError> {
+        self.write(value, true)
+    }
+
+    pub(crate) fn has_validators(&self) -> bool {
+        self.validation
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|validation| !validation.validators.is_empty())
+    }
+
+    /// Validates a pending write and publishes its errors. Returns the violations
+    /// if the write should not be applied; `strict` rejects it even in
+    /// [`ValidationMode::Flag`].
+    pub(crate) fn check_write(&self, value: &T, strict: bool) -> Result<(), Vec<String>> {
+        let (errors, mode) = {
+            let validation = self.validation.lock().unwrap();
+            match validation.as_ref() {
+                Some(validation) => (validation.errors.clone(), validation.mode),
+                None => return Ok(()),
+            }
+        };
+
+        let violations = self.run_validators(value);
+        if errors.peek() != violations {
+            errors.assign(violations.clone());
+        }
+
+        if violations.is_empty() || (mode == ValidationMode::Flag && !strict) {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Runs a copy of the validators without the lock held, so a validator
+    /// may read this observable or add validators to it.
+    fn run_validators(&self, value: &T) -> Vec<String> {
+        let validators: Vec<Validator<T>> = self
+            .validation
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|validation| validation.validators.clone())
+            .unwrap_or_default();
+
+        validators
+            .iter()
+            .filter_map(|validator| validator(value).err())
+            .collect()
+    }
+}
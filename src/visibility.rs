@@ -0,0 +1,102 @@
+use crate::Store;
+use std::any::TypeId;
+use std::collections::HashSet;
+use std::sync::{LazyLock, RwLock};
+
+static PRIVATE: LazyLock<RwLock<HashSet<TypeId>>> = LazyLock::new(|| RwLock::new(HashSet::new()));
+
+/// Makes `S` private to the subtree whose provider registers it.
+///
+/// A private store is never created on demand and never resolved from the
+/// global registry: [`get_store`](crate::get_store) returns `None` for it, and
+/// [`use_store`](crate::use_store) or `get_or_create` panic unless a
+/// [`StoreContext`](crate::StoreContext) in the lookup chain registered it.
+/// Inside a Dioxus component, the chain starts at the nearest `StoreProvider`,
+/// so `use_store` keeps working in its subtree.
+///
+/// ```ignore
+/// mark_private::<WizardStore>();
+///
+/// rsx! {
+///     StoreProvider { stores: |context: StoreContext| context.register(WizardStore::default()),
+///         WizardSteps {}
+///     }
+/// }
+/// ```
+pub fn mark_private<S: Store>() {
+    PRIVATE.write().unwrap().insert(TypeId::of::<S>());
+}
+
+pub fn is_private<S: Store>() -> bool {
+    PRIVATE.read().unwrap().contains(&TypeId::of::<S>())
+}
+
+/// The instance of `S` in the `StoreProvider` context enclosing the current
+/// Dioxus component, if any.
+pub(crate) fn provided<S: Store>() -> Option<S> {
+    #[cfg(feature = "dioxus")]
+    {
+        dioxus::prelude::try_consume_context::<crate::StoreContext>()?.get::<S>()
+    }
+    #[cfg(not(feature = "dioxus"))]
+    {
+        None
+    }
+}
+
+/// Panics if `S` is private, as it was looked up outside its providing subtree.
+pub(crate) fn assert_not_private<S: Store>() {
+    if is_private::<S>() {
+        panic!(
+            "{} is private to the subtree that provides it and was used outside of it",
+            std::any::type_name::<S>()
+        );
+    }
+}
+
+#[cfg(all(test, feature = "dioxus"))]
+mod tests {
+    use super::*;
+    use crate::{get_store, use_store, StoreContext, StoreProvider};
+    use dioxus::prelude::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Clone, Default)]
+    struct WizardStore {
+        step: usize,
+    }
+
+    impl Store for WizardStore {
+        fn id(&self) -> TypeId {
+            TypeId::of::<Self>()
+        }
+    }
+
+    static STEP: AtomicUsize = AtomicUsize::new(0);
+
+    #[component]
+    fn Step() -> Element {
+        assert!(get_store::<WizardStore>().is_some());
+        STEP.store(use_store::<WizardStore>().step, Ordering::SeqCst);
+        rsx! {}
+    }
+
+    fn app() -> Element {
+        rsx! {
+            StoreProvider {
+                stores: |context: StoreContext| context.register(WizardStore { step: 3 }),
+                Step {}
+            }
+        }
+    }
+
+    #[test]
+    fn private_store_resolves_inside_its_provider() {
+        mark_private::<WizardStore>();
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+
+        assert_eq!(STEP.load(Ordering::SeqCst), 3);
+        assert!(get_store::<WizardStore>().is_none());
+    }
+}
@@ -0,0 +1,37 @@
+//! Yew bindings: `use reaxive::yew_support::*;`
+
+use crate::{ObserverContext, TrackingScope};
+use yew::prelude::*;
+
+/// Re-renders the component when an observable read before the returned
+/// scope is dropped changes. Keep it alive for the whole render:
+///
+/// ```ignore
+/// #[function_component]
+/// fn Counter() -> Html {
+///     let _tracking = use_reaxive();
+///     let store = CounterStore::new();
+///     html! { <span>{ store.count.get() }</span> }
+/// }
+/// ```
+#[hook]
+pub fn use_reaxive() -> TrackingScope {
+    let update = use_force_update();
+    let context = use_memo((), move |_| {
+        ObserverContext::new_untracked(move || update.force_update())
+    });
+    context.track()
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ReaxiveProps {
+    pub render: Callback<(), Html>,
+}
+
+/// Renders `render`, re-rendering whenever an observable it read changes:
+/// `<ReaxiveComponent render={Callback::from(move |_| html! { { store.count.get() } })} />`
+#[function_component(ReaxiveComponent)]
+pub fn reaxive_component(props: &ReaxiveProps) -> Html {
+    let _tracking = use_reaxive();
+    props.render.emit(())
+}